@@ -1,6 +1,6 @@
 use log::LevelFilter;
 use rbatis::dark_std::defer;
-use rbatis::intercept_log::LogInterceptor;
+use rbatis::intercept_log::{LogInterceptor, MaskRule};
 use rbatis::{crud, RBatis};
 use std::time::Duration;
 
@@ -43,5 +43,16 @@ pub async fn main() {
     tokio::time::sleep(Duration::from_secs(1)).await;
     println!("-----------------------------------------------------------------------");
 
+    println!("---------------mask args / render sql----------------------------------");
+    let log = rb.get_intercept::<LogInterceptor>().unwrap();
+    log.set_level_filter(LevelFilter::Info);
+    log.add_mask_rule(MaskRule::column("name"));
+    log.set_max_value_len(64);
+    log.set_render_sql(true);
+    //log: `select * from activity where name = '***'`
+    _ = Activity::select_by_column(&rb.clone(), "name", "secret").await;
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    println!("-----------------------------------------------------------------------");
+
     log::logger().flush();
 }
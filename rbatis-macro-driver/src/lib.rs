@@ -6,6 +6,8 @@ use syn::parse::{Parse, ParseStream};
 use syn::punctuated::Punctuated;
 use syn::{parse_macro_input, ItemFn, Token};
 
+use crate::macros::columns_impl::impl_columns;
+use crate::macros::db_enum_impl::impl_db_enum;
 use crate::macros::html_sql_impl::impl_macro_html_sql;
 use crate::macros::py_sql_impl::impl_macro_py_sql;
 use crate::macros::sql_impl::impl_macro_sql;
@@ -104,6 +106,9 @@ pub fn sql(args: TokenStream, func: TokenStream) -> TokenStream {
 /// //#[rbatis::py_sql(r#"include!("C:/rs/rbatis/target/debug/xx.py_sql")"#)]
 /// //pub async fn test_same_id(rb: &dyn Executor, id: &u64) -> Result<Value, Error> { impled!() }
 /// ```
+/// the arg name `driver_type` is reserved, it is the driver name of the executor(for example `mysql`),
+/// so the sql can use it like `${driver_type.json_path('attrs','$.color')}`.
+/// a fn arg named `driver_type` is kept and replaces it
 #[proc_macro_attribute]
 pub fn py_sql(args: TokenStream, func: TokenStream) -> TokenStream {
     let args = parse_macro_input!(args as ParseArgs);
@@ -161,6 +166,9 @@ pub fn py_sql(args: TokenStream, func: TokenStream) -> TokenStream {
 /// #[html_sql("xxxx.html")]
 /// pub async fn select_by_name(rbatis: &dyn Executor, name: &str) -> Option<MockTable> {}
 /// ```
+/// the arg name `driver_type` is reserved, it is the driver name of the executor(for example `mysql`),
+/// so the sql can use it like `${driver_type.json_path('attrs','$.color')}`.
+/// a fn arg named `driver_type` is kept and replaces it
 #[proc_macro_attribute]
 pub fn html_sql(args: TokenStream, func: TokenStream) -> TokenStream {
     let args = parse_macro_input!(args as ParseArgs);
@@ -197,3 +205,35 @@ pub fn rb_html(args: TokenStream, func: TokenStream) -> TokenStream {
 pub fn snake_name(args: TokenStream, func: TokenStream) -> TokenStream {
     macros::snake_name::snake_name(args, func)
 }
+
+/// gen typed column constants `COL_XXX: rbatis::column::Column<Table, FieldType>` and `COLUMNS`
+/// ```log
+/// #[derive(serde::Serialize, serde::Deserialize, rbatis::Columns)]
+/// pub struct Activity {
+///     pub id: Option<String>,
+///     #[serde(rename = "type")]
+///     pub kind: Option<i32>,
+/// }
+/// //Activity::COL_ID: Column<Activity, String> = "id"
+/// //Activity::COL_KIND: Column<Activity, i32> = "type"
+/// ```
+#[proc_macro_derive(Columns, attributes(serde))]
+pub fn columns(input: TokenStream) -> TokenStream {
+    impl_columns(input)
+}
+
+/// impl `rbatis::db_enum::DbEnum` and serde by the db values, the enum is stored as the int or string column
+/// ```log
+/// #[derive(Clone, Copy, Debug, rbatis::DbEnum)]
+/// pub enum Status {
+///     #[db(str = "active")]
+///     Active,
+///     #[db(str = "disabled")]
+///     Disabled,
+/// }
+/// //rbs::to_value!(Status::Active) = Value::String("active")
+/// ```
+#[proc_macro_derive(DbEnum, attributes(db))]
+pub fn db_enum(input: TokenStream) -> TokenStream {
+    impl_db_enum(input)
+}
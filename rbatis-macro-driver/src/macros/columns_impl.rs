@@ -0,0 +1,136 @@
+use proc_macro::TokenStream;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, Data, DeriveInput, Fields, GenericArgument, PathArguments, Type};
+
+/// gen `pub const COL_XXX: Column<Table, FieldType>` for every named field,
+/// the column name is the serialized name(`#[serde(rename)]`,`#[serde(rename_all)]`)
+pub fn impl_columns(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let fields = match &input.data {
+        Data::Struct(s) => match &s.fields {
+            Fields::Named(fields) => fields.named.iter().collect::<Vec<_>>(),
+            _ => vec![],
+        },
+        _ => {
+            return syn::Error::new_spanned(&input.ident, "Columns only support struct")
+                .to_compile_error()
+                .into();
+        }
+    };
+    let rename_all = serde_attr(&input.attrs, "rename_all");
+    let mut consts = vec![];
+    let mut columns = vec![];
+    for field in fields {
+        let ident = match &field.ident {
+            None => continue,
+            Some(v) => v,
+        };
+        if serde_attr(&field.attrs, "skip").is_some() {
+            continue;
+        }
+        let field_name = ident.to_string();
+        let field_name = field_name.trim_start_matches("r#");
+        let column = serde_attr(&field.attrs, "rename").unwrap_or_else(|| match &rename_all {
+            Some(rule) => rename_field(field_name, rule),
+            None => field_name.to_string(),
+        });
+        let const_name = format_ident!("COL_{}", field_name.to_uppercase());
+        let value_type = strip_option(&field.ty);
+        consts.push(quote! {
+            pub const #const_name: rbatis::column::Column<Self, #value_type> = rbatis::column::Column::new(#column);
+        });
+        columns.push(column);
+    }
+    let stream = quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            #(#consts)*
+            /// all column names
+            pub const COLUMNS: &'static [&'static str] = &[#(#columns),*];
+        }
+    };
+    stream.into()
+}
+
+/// the serde `rename_all` rule of a `snake_case` field
+fn rename_field(field: &str, rule: &str) -> String {
+    let pascal = || {
+        let mut s = String::with_capacity(field.len());
+        let mut upper = true;
+        for c in field.chars() {
+            if c == '_' {
+                upper = true;
+            } else if upper {
+                s.push(c.to_ascii_uppercase());
+                upper = false;
+            } else {
+                s.push(c);
+            }
+        }
+        s
+    };
+    match rule {
+        "UPPERCASE" | "SCREAMING_SNAKE_CASE" => field.to_ascii_uppercase(),
+        "PascalCase" => pascal(),
+        "camelCase" => {
+            let s = pascal();
+            let mut chars = s.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_lowercase().to_string() + chars.as_str(),
+                None => s,
+            }
+        }
+        "kebab-case" => field.replace('_', "-"),
+        "SCREAMING-KEBAB-CASE" => field.to_ascii_uppercase().replace('_', "-"),
+        //lowercase, snake_case
+        _ => field.to_string(),
+    }
+}
+
+/// `Option<T>` => `T`
+fn strip_option(ty: &Type) -> &Type {
+    if let Type::Path(p) = ty {
+        if let Some(seg) = p.path.segments.last() {
+            if seg.ident == "Option" {
+                if let PathArguments::AngleBracketed(args) = &seg.arguments {
+                    if let Some(GenericArgument::Type(inner)) = args.args.first() {
+                        return inner;
+                    }
+                }
+            }
+        }
+    }
+    ty
+}
+
+/// find `#[serde(rename = "xxx")]`,`#[serde(rename_all = "xxx")]` or `#[serde(skip)]`
+fn serde_attr(attrs: &[syn::Attribute], key: &str) -> Option<String> {
+    let mut result = None;
+    for attr in attrs {
+        if !attr.path().is_ident("serde") {
+            continue;
+        }
+        let _ = attr.parse_nested_meta(|meta| {
+            if meta.input.peek(syn::token::Paren) {
+                //skip `rename(serialize = "a")`
+                let _: proc_macro2::Group = meta.input.parse()?;
+            } else if meta.input.peek(syn::Token![=]) {
+                let s: syn::Expr = meta.value()?.parse()?;
+                if meta.path.is_ident(key) {
+                    if let syn::Expr::Lit(syn::ExprLit {
+                        lit: syn::Lit::Str(s),
+                        ..
+                    }) = s
+                    {
+                        result = Some(s.value());
+                    }
+                }
+            } else if meta.path.is_ident(key) {
+                result = Some(String::new());
+            }
+            Ok(())
+        });
+    }
+    result
+}
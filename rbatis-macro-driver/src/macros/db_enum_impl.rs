@@ -0,0 +1,155 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields};
+
+enum DbValue {
+    Int(i64),
+    Str(String),
+}
+
+/// impl `rbatis::db_enum::DbEnum`, `serde::Serialize` and `serde::Deserialize` for the unit enum,
+/// every variant has a `#[db(int = 1)]` or `#[db(str = "active")]`
+pub fn impl_db_enum(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match gen_db_enum(&input) {
+        Ok(v) => v.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn gen_db_enum(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &input.ident;
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            "DbEnum not support generics",
+        ));
+    }
+    let variants = match &input.data {
+        Data::Enum(e) => &e.variants,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "DbEnum only support enum",
+            ));
+        }
+    };
+    if variants.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "DbEnum need at least one variant",
+        ));
+    }
+    let mut values: Vec<DbValue> = vec![];
+    let mut idents = vec![];
+    for variant in variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(syn::Error::new_spanned(
+                variant,
+                "DbEnum only support unit variant",
+            ));
+        }
+        let value = db_attr(variant)?;
+        let duplicate = values.iter().any(|v| match (v, &value) {
+            (DbValue::Int(a), DbValue::Int(b)) => a == b,
+            (DbValue::Str(a), DbValue::Str(b)) => a == b,
+            _ => false,
+        });
+        if duplicate {
+            return Err(syn::Error::new_spanned(variant, "duplicate db value"));
+        }
+        if let Some(first) = values.first() {
+            if matches!(first, DbValue::Int(_)) != matches!(value, DbValue::Int(_)) {
+                return Err(syn::Error::new_spanned(
+                    variant,
+                    "DbEnum can not mix `int` and `str` values",
+                ));
+            }
+        }
+        values.push(value);
+        idents.push(&variant.ident);
+    }
+    let name_str = name.to_string();
+    let db_values = values.iter().map(|v| match v {
+        DbValue::Int(v) => quote! { rbatis::db_enum::DbValue::Int(#v) },
+        DbValue::Str(v) => quote! { rbatis::db_enum::DbValue::Str(#v) },
+    });
+    let indexes = 0..idents.len();
+    let from_indexes = 0..idents.len();
+    Ok(quote! {
+        impl rbatis::db_enum::DbEnum for #name {
+            const NAME: &'static str = #name_str;
+            const VALUES: &'static [rbatis::db_enum::DbValue] = &[#(#db_values),*];
+
+            fn index(&self) -> usize {
+                match self {
+                    #(Self::#idents => #indexes,)*
+                }
+            }
+
+            fn from_index(index: usize) -> Option<Self> {
+                match index {
+                    #(#from_indexes => Some(Self::#idents),)*
+                    _ => None,
+                }
+            }
+        }
+
+        impl serde::Serialize for #name {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                rbatis::db_enum::serialize(self, serializer)
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for #name {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                rbatis::db_enum::deserialize(deserializer)
+            }
+        }
+    })
+}
+
+/// find `#[db(int = 1)]` or `#[db(str = "active")]`
+fn db_attr(variant: &syn::Variant) -> syn::Result<DbValue> {
+    let mut result = None;
+    for attr in &variant.attrs {
+        if !attr.path().is_ident("db") {
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("int") {
+                let v: syn::Expr = meta.value()?.parse()?;
+                result = Some(DbValue::Int(parse_int(&v)?));
+                Ok(())
+            } else if meta.path.is_ident("str") {
+                let v: syn::LitStr = meta.value()?.parse()?;
+                result = Some(DbValue::Str(v.value()));
+                Ok(())
+            } else {
+                Err(meta.error("expected `int = ..` or `str = \"..\"`"))
+            }
+        })?;
+    }
+    result.ok_or_else(|| {
+        syn::Error::new_spanned(
+            variant,
+            "DbEnum variant need #[db(int = ..)] or #[db(str = \"..\")]",
+        )
+    })
+}
+
+/// `1` or `-1`
+fn parse_int(expr: &syn::Expr) -> syn::Result<i64> {
+    match expr {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Int(v),
+            ..
+        }) => v.base10_parse::<i64>(),
+        syn::Expr::Unary(syn::ExprUnary {
+            op: syn::UnOp::Neg(_),
+            expr,
+            ..
+        }) => Ok(-parse_int(expr)?),
+        _ => Err(syn::Error::new_spanned(expr, "expected an integer")),
+    }
+}
@@ -160,6 +160,10 @@ pub(crate) fn impl_macro_html_sql(target_fn: &ItemFn, args: &ParseArgs) -> Token
          #fn_body
          use rbatis::executor::{RBatisRef};
          let driver_type = #rbatis_ident.rb_ref().driver_type()?;
+         //the driver_type arg of the sql, for example `${driver_type.json_path('attrs','$.color')}`
+         if !rb_arg_map.0.contains_key(&rbs::Value::from("driver_type")) {
+             rb_arg_map.insert("driver_type".into(), driver_type.into());
+         }
          use rbatis::rbatis_codegen;
          #gen_func
          let (mut sql,rb_args) = impl_html_sql(rbs::Value::Map(rb_arg_map),'?');
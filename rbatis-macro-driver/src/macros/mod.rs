@@ -1,3 +1,5 @@
+pub mod columns_impl;
+pub mod db_enum_impl;
 pub mod html_sql_impl;
 pub mod py_sql_impl;
 pub mod snake_name;
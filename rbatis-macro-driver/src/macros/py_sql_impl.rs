@@ -152,6 +152,10 @@ pub(crate) fn impl_macro_py_sql(target_fn: &ItemFn, args: ParseArgs) -> TokenStr
          #fn_body
          use rbatis::executor::{RBatisRef};
          let driver_type = #rbatis_ident.rb_ref().driver_type()?;
+         //the driver_type arg of the sql, for example `${driver_type.json_path('attrs','$.color')}`
+         if !rb_arg_map.0.contains_key(&rbs::Value::from("driver_type")) {
+             rb_arg_map.insert("driver_type".into(), driver_type.into());
+         }
          use rbatis::rbatis_codegen;
          #include_data
          #gen_func
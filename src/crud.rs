@@ -13,6 +13,11 @@
 ///  let table = MockTable{id: Some("1".to_string())};
 ///  let r = MockTable::insert(rb, &table).await;
 ///  let r = MockTable::insert_batch(rb, std::slice::from_ref(&table),10).await;
+///  //insert and return the inserted rows(RETURNING/OUTPUT)
+///  let tables = MockTable::insert_batch_returning(rb, std::slice::from_ref(&table),10,"id").await;
+///  let ids = MockTable::insert_batch_returning_ids(rb, std::slice::from_ref(&table),10,"id").await;
+///  //multi-row insert statements on one connection
+///  let total = MockTable::bulk_insert(rb, vec![MockTable{id: Some("2".to_string())}], rbatis::bulk::BulkInsertOption::new()).await;
 ///
 ///  let tables = MockTable::select_by_column(rb,"id","1").await;
 ///  let tables = MockTable::select_all(rb).await;
@@ -21,6 +26,18 @@
 ///  let r = MockTable::update_by_column(rb, &table,"id").await;
 ///
 ///  let r = MockTable::delete_by_column(rb, "id","1").await;
+///
+///  let w = rbatis::wrapper::QueryWrapper::new().eq("id", "1");
+///  let tables = MockTable::select_by_wrapper(rb, &w).await;
+///  let count = MockTable::count_by_wrapper(rb, &w).await;
+///  let r = MockTable::update_by_wrapper(rb, &table, &w, true).await;
+///  let r = MockTable::delete_by_wrapper(rb, &w).await;
+///
+///  //update only the changed columns
+///  let mut tables = MockTable::select_by_column_tracked(rb,"id","1").await?;
+///  for table in tables.iter_mut() {
+///      let r = MockTable::update_changed(rb, table, "id").await;
+///  }
 ///  //... and more
 ///  Ok(())
 /// }
@@ -34,12 +51,187 @@ macro_rules! crud {
         $crate::impl_select!($table {});
         $crate::impl_update!($table {});
         $crate::impl_delete!($table {});
+        $crate::impl_by_map!($table {});
+        $crate::impl_tracked!($table {});
     };
     ($table:ty{},$table_name:expr) => {
         $crate::impl_insert!($table {}, $table_name);
         $crate::impl_select!($table {}, $table_name);
         $crate::impl_update!($table {}, $table_name);
         $crate::impl_delete!($table {}, $table_name);
+        $crate::impl_by_map!($table {});
+        $crate::impl_tracked!($table {}, $table_name);
+    };
+}
+
+/// gen select_by_map,count_by_map,update_by_map,delete_by_map methods.
+/// the map keys are validated against the serialized columns of table.
+/// * scalar => `column = ?`
+/// * array => `column in (?,?)`, empty array means no rows
+/// * null => `column is null`
+///
+/// notice: it needs the methods of impl_select!,impl_update!,impl_delete!(crud! has them all)
+///```rust
+/// use rbatis::{Error, RBatis};
+/// use rbs::{to_value, Value};
+///
+/// #[derive(serde::Serialize, serde::Deserialize)]
+/// pub struct MockTable{
+///    pub id: Option<String>,
+///    pub status: Option<i32>,
+///    pub remark: Option<String>,
+/// }
+/// rbatis::crud!(MockTable{});
+///
+/// async fn test_use(rb:&RBatis) -> Result<(),Error>{
+///  let map = to_value!{"status": 1, "id": ["1", "2"], "remark": Value::Null};
+///  let tables = MockTable::select_by_map(rb, map.clone()).await?;
+///  let count = MockTable::count_by_map(rb, map.clone()).await?;
+///  let r = MockTable::delete_by_map(rb, map).await?;
+///  Ok(())
+/// }
+/// ```
+#[macro_export]
+macro_rules! impl_by_map {
+    ($table:ty{}) => {
+        impl $table {
+            fn map_wrapper(
+                map: &rbs::Value,
+            ) -> std::result::Result<$crate::wrapper::QueryWrapper, $crate::rbdc::Error> {
+                if let Some(columns) = $crate::utils::table_util::table_columns::<$table>() {
+                    for (k, _) in map {
+                        let key = k.as_str().unwrap_or_default();
+                        if !columns.contains(&key) {
+                            return Err($crate::rbdc::Error::from(format!(
+                                "[rb] unknown column `{}` of {}",
+                                key,
+                                std::any::type_name::<$table>()
+                            )));
+                        }
+                    }
+                }
+                Ok($crate::wrapper::QueryWrapper::new().by_map(map))
+            }
+
+            pub async fn select_by_map(
+                executor: &dyn $crate::executor::Executor,
+                map: rbs::Value,
+            ) -> std::result::Result<Vec<$table>, $crate::rbdc::Error> {
+                let wrapper = <$table>::map_wrapper(&map)?;
+                <$table>::select_by_wrapper(executor, &wrapper).await
+            }
+
+            pub async fn count_by_map(
+                executor: &dyn $crate::executor::Executor,
+                map: rbs::Value,
+            ) -> std::result::Result<u64, $crate::rbdc::Error> {
+                let wrapper = <$table>::map_wrapper(&map)?;
+                <$table>::count_by_wrapper(executor, &wrapper).await
+            }
+
+            /// update by map conditions, will skip null column
+            pub async fn update_by_map(
+                executor: &dyn $crate::executor::Executor,
+                table: &$table,
+                map: rbs::Value,
+            ) -> std::result::Result<$crate::rbdc::db::ExecResult, $crate::rbdc::Error> {
+                let wrapper = <$table>::map_wrapper(&map)?;
+                <$table>::update_by_wrapper(executor, table, &wrapper, true).await
+            }
+
+            pub async fn delete_by_map(
+                executor: &dyn $crate::executor::Executor,
+                map: rbs::Value,
+            ) -> std::result::Result<$crate::rbdc::db::ExecResult, $crate::rbdc::Error> {
+                let wrapper = <$table>::map_wrapper(&map)?;
+                <$table>::delete_by_wrapper(executor, &wrapper).await
+            }
+        }
+    };
+}
+
+/// gen select_by_column_tracked,select_by_wrapper_tracked,update_changed methods, see `rbatis::tracked::Tracked`.
+///
+/// notice: it needs the methods of impl_select!(crud! has them all)
+///```rust
+/// use rbatis::{Error, RBatis};
+///
+/// #[derive(serde::Serialize, serde::Deserialize)]
+/// pub struct MockTable{
+///    pub id: Option<String>,
+///    pub remark: Option<String>,
+/// }
+/// rbatis::crud!(MockTable{});
+///
+/// async fn test_use(rb:&RBatis) -> Result<(),Error>{
+///  let mut tables = MockTable::select_by_column_tracked(rb, "id", "1").await?;
+///  for table in tables.iter_mut() {
+///     table.remark = None;
+///     //update mock_table set remark=? where id = ?
+///     let r = MockTable::update_changed(rb, table, "id").await?;
+///     table.reset();
+///  }
+///  Ok(())
+/// }
+/// ```
+#[macro_export]
+macro_rules! impl_tracked {
+    ($table:ty{}) => {
+        $crate::impl_tracked!($table {}, "");
+    };
+    ($table:ty{},$table_name:expr) => {
+        impl $table {
+            /// `select_by_wrapper` and track the rows
+            pub async fn select_by_wrapper_tracked(
+                executor: &dyn $crate::executor::Executor,
+                wrapper: &$crate::wrapper::QueryWrapper,
+            ) -> std::result::Result<Vec<$crate::tracked::Tracked<$table>>, $crate::rbdc::Error> {
+                let tables = <$table>::select_by_wrapper(executor, wrapper).await?;
+                Ok(tables.into_iter().map($crate::tracked::Tracked::new).collect())
+            }
+
+            /// `select_by_column` and track the rows
+            pub async fn select_by_column_tracked<V: serde::Serialize, C: $crate::column::TableColumn<$table, V>>(
+                executor: &dyn $crate::executor::Executor,
+                column: C,
+                column_value: V,
+            ) -> std::result::Result<Vec<$crate::tracked::Tracked<$table>>, $crate::rbdc::Error> {
+                let wrapper = $crate::wrapper::QueryWrapper::new().eq(column, column_value);
+                <$table>::select_by_wrapper_tracked(executor, &wrapper).await
+            }
+
+            /// update the changed columns(include the columns changed to null) where `column` = the loaded value.
+            /// nothing is executed if no column is changed
+            pub async fn update_changed<C: $crate::column::TableColumnName<$table>>(
+                executor: &dyn $crate::executor::Executor,
+                tracked: &$crate::tracked::Tracked<$table>,
+                column: C,
+            ) -> std::result::Result<$crate::rbdc::db::ExecResult, $crate::rbdc::Error> {
+                let table = match $crate::table_hooks!($table) {
+                    Some(hooks) => rbs::to_value!((hooks.before_update)(executor, &**tracked)?),
+                    None => rbs::to_value!(&**tracked),
+                };
+                let column = column.column_name();
+                let mut changes = $crate::tracked::changes(tracked.snapshot(), &table);
+                changes.0.shift_remove(&rbs::Value::String(column.to_string()));
+                if changes.is_empty() {
+                    return Ok($crate::rbdc::db::ExecResult {
+                        rows_affected: 0,
+                        last_insert_id: rbs::Value::Null,
+                    });
+                }
+                let mut table_name = $table_name.to_string();
+                #[$crate::snake_name($table)]
+                fn snake_name(){}
+                if table_name.is_empty(){
+                    table_name = snake_name();
+                }
+                let naming = $crate::naming::naming_of(executor, &table_name);
+                let wrapper = $crate::wrapper::QueryWrapper::new().eq(naming.to_column(column), &tracked.snapshot()[column]);
+                let (sql, args) = wrapper.to_update_sql(&table_name, &naming.to_columns(rbs::Value::Map(changes)), false)?;
+                executor.exec(&sql, args).await
+            }
+        }
     };
 }
 
@@ -126,7 +318,7 @@ macro_rules! impl_insert {
                 )]
                 async fn insert_batch(
                     executor: &dyn $crate::executor::Executor,
-                    tables: &[$table],
+                    tables: &rbs::Value,
                     table_name: &str,
                 ) -> std::result::Result<$crate::rbdc::db::ExecResult, $crate::rbdc::Error>
                 {
@@ -137,6 +329,14 @@ macro_rules! impl_insert {
                         "insert can not insert empty array tables!",
                     ));
                 }
+                let hooked;
+                let tables = match $crate::table_hooks!($table) {
+                    Some(hooks) => {
+                        hooked = (hooks.before_insert)(executor, tables)?;
+                        hooked.as_slice()
+                    }
+                    None => tables,
+                };
                 #[$crate::snake_name($table)]
                 fn snake_name() {}
                 let mut table_name = $table_name.to_string();
@@ -147,11 +347,19 @@ macro_rules! impl_insert {
                     rows_affected: 0,
                     last_insert_id: rbs::Value::Null,
                 };
-                let ranges = $crate::plugin::Page::<()>::make_ranges(tables.len() as u64, batch_size);
+                //split by batch_size and the bind parameter/sql length limit
+                let first = rbs::to_value!(&tables[0]);
+                let mut base_sql_len = 32 + table_name.len();
+                for (column, _) in &first {
+                    base_sql_len += column.as_str().unwrap_or_default().len() + 1;
+                }
+                let driver_type = executor.driver_type().unwrap_or_default();
+                let naming = $crate::naming::naming_of(executor, &table_name);
+                let ranges = executor.rb_ref().batch_limit.ranges(driver_type, tables.len(), batch_size, first.len(), base_sql_len, first.len() * 2 + 2);
                 for (offset, limit) in ranges {
                     let exec_result = insert_batch(
                         executor,
-                        &tables[offset as usize..limit as usize],
+                        &naming.to_columns(rbs::to_value!(&tables[offset..limit])),
                         table_name.as_str(),
                     )
                     .await?;
@@ -167,6 +375,97 @@ macro_rules! impl_insert {
             ) -> std::result::Result<$crate::rbdc::db::ExecResult, $crate::rbdc::Error> {
                 <$table>::insert_batch(executor, std::slice::from_ref(table), 1).await
             }
+
+            /// insert and return the inserted row, see `rbatis::returning::insert_returning`
+            pub async fn insert_returning(
+                executor: &dyn $crate::executor::Executor,
+                table: &$table,
+                id_column: &str,
+            ) -> std::result::Result<$table, $crate::rbdc::Error> {
+                let mut rows = <$table>::insert_batch_returning(executor, std::slice::from_ref(table), 1, id_column).await?;
+                match rows.pop() {
+                    None => Err($crate::rbdc::Error::from("[rb] insert_returning no row returned")),
+                    Some(v) => Ok(v),
+                }
+            }
+
+            /// insert and return the inserted rows in input order, see `rbatis::returning::insert_returning`.
+            /// `id_column` is used by the mysql fallback to select the inserted rows
+            pub async fn insert_batch_returning(
+                executor: &dyn $crate::executor::Executor,
+                tables: &[$table],
+                batch_size: u64,
+                id_column: &str,
+            ) -> std::result::Result<Vec<$table>, $crate::rbdc::Error> {
+                let hooked;
+                let tables = match $crate::table_hooks!($table) {
+                    Some(hooks) => {
+                        hooked = (hooks.before_insert)(executor, tables)?;
+                        hooked.as_slice()
+                    }
+                    None => tables,
+                };
+                #[$crate::snake_name($table)]
+                fn snake_name() {}
+                let mut table_name = $table_name.to_string();
+                if table_name.is_empty() {
+                    table_name = snake_name();
+                }
+                let naming = $crate::naming::naming_of(executor, &table_name);
+                let rows = $crate::returning::insert_returning(executor, &table_name, naming.to_columns(rbs::to_value!(tables)), batch_size, id_column, false).await?;
+                let fields = $crate::utils::table_util::table_columns::<$table>().unwrap_or_default();
+                let mut tables: Vec<$table> = $crate::decode(naming.to_fields(rbs::Value::Array(rows), fields))?;
+                if let Some(hooks) = $crate::table_hooks!($table) {
+                    (hooks.after_load)(executor, &mut tables)?;
+                }
+                Ok(tables)
+            }
+
+            /// insert and return the ids of `id_column` in input order, see `rbatis::returning::insert_returning`
+            pub async fn insert_batch_returning_ids(
+                executor: &dyn $crate::executor::Executor,
+                tables: &[$table],
+                batch_size: u64,
+                id_column: &str,
+            ) -> std::result::Result<Vec<rbs::Value>, $crate::rbdc::Error> {
+                let hooked;
+                let tables = match $crate::table_hooks!($table) {
+                    Some(hooks) => {
+                        hooked = (hooks.before_insert)(executor, tables)?;
+                        hooked.as_slice()
+                    }
+                    None => tables,
+                };
+                #[$crate::snake_name($table)]
+                fn snake_name() {}
+                let mut table_name = $table_name.to_string();
+                if table_name.is_empty() {
+                    table_name = snake_name();
+                }
+                let naming = $crate::naming::naming_of(executor, &table_name);
+                $crate::returning::insert_returning(executor, &table_name, naming.to_columns(rbs::to_value!(tables)), batch_size, id_column, true).await
+            }
+
+            /// insert many tables by multi-row insert statements on a new connection, see `RBatis::bulk_insert`.
+            /// the `before_insert` hooks are called for every table
+            pub async fn bulk_insert<I: IntoIterator<Item = $table>>(
+                executor: &dyn $crate::executor::Executor,
+                tables: I,
+                option: $crate::bulk::BulkInsertOption,
+            ) -> std::result::Result<u64, $crate::rbdc::Error> {
+                #[$crate::snake_name($table)]
+                fn snake_name() {}
+                let mut table_name = $table_name.to_string();
+                if table_name.is_empty() {
+                    table_name = snake_name();
+                }
+                let hooks = $crate::table_hooks!($table);
+                $crate::bulk::bulk_insert(executor, &table_name, tables, option, |table: $table| match &hooks {
+                    Some(hooks) => Ok(rbs::to_value!((hooks.before_insert)(executor, std::slice::from_ref(&table))?.remove(0))),
+                    None => Ok(rbs::to_value!(table)),
+                })
+                .await
+            }
         }
     };
 }
@@ -180,6 +479,10 @@ macro_rules! impl_insert {
 /// pub struct MockTable{
 ///   pub id: Option<String>
 /// }
+/// #[derive(serde::Serialize, serde::Deserialize)]
+/// pub struct MockTableBrief{
+///   pub id: Option<String>
+/// }
 /// /// default
 ///rbatis::impl_select!(MockTable{});
 ///rbatis::impl_select!(MockTable{select_all_by_id(id:&str,name:&str) => "`where id = #{id} and name = #{name}`"});
@@ -193,6 +496,20 @@ macro_rules! impl_insert {
 ///    let r = MockTable::select_all_by_id(rb,"1","xxx").await?;
 ///    let r:Option<MockTable> = MockTable::select_by_id(rb,"1".to_string()).await?;
 ///    let r:Vec<MockTable> = MockTable::select_by_id2(rb,"1".to_string()).await?;
+///    //projection: select the fields of MockTableBrief and decode into it
+///    let r:Vec<MockTableBrief> = MockTable::select_by_column_as(rb,"id","1").await?;
+///    let r:Vec<MockTableBrief> = MockTable::select_all_as(rb).await?;
+///    //row lock, must in a transaction
+///    let mut tx = rb.acquire_begin().await?;
+///    let r = MockTable::select_by_column_for_update(&tx,"id","1").await?;
+///    let r = MockTable::select_for_update_skip_locked(&tx,&rbatis::wrapper::QueryWrapper::new().eq("status",0).limit(10)).await?;
+///    tx.commit().await?;
+///    //count,exists and aggregate
+///    let count = MockTable::count_all(rb).await?;
+///    let exists = MockTable::exists_by_column(rb,"id","1").await?;
+///    let w = rbatis::wrapper::QueryWrapper::new();
+///    let max_id: Option<String> = MockTable::max_by(rb,"id",&w).await?;
+///    let counts: std::collections::HashMap<String, u64> = MockTable::aggregate_group_by(rb,"count","id","id",&w).await?;
 ///    Ok(())
 /// }
 /// ```
@@ -204,12 +521,309 @@ macro_rules! impl_select {
     };
     ($table:ty{},$table_name:expr) => {
         $crate::impl_select!($table{select_all() => ""},$table_name);
-        $crate::impl_select!($table{select_by_column<V:serde::Serialize>(column: &str,column_value: V) -> Vec => "` where ${column} = #{column_value}`"},$table_name);
-        $crate::impl_select!($table{select_in_column<V:serde::Serialize>(column: &str,column_values: &[V]) -> Vec =>
-         "` where ${column} in (`
-          trim ',': for _,item in column_values:
-             #{item},
-          `)`"},$table_name);
+        impl $table {
+            /// `select * from table where column = ?`, the column is mapped by the table naming
+            pub async fn select_by_column<V:serde::Serialize,C:$crate::column::TableColumn<$table,V>>(
+                executor: &dyn $crate::executor::Executor,
+                column: C,
+                column_value: V,
+            ) -> std::result::Result<Vec<$table>, $crate::rbdc::Error> {
+                #[$crate::py_sql("`select ${table_column} from ${table_name} `","` where ${column} = #{column_value}`")]
+                async fn select_by_column<V:serde::Serialize>(executor: &dyn $crate::executor::Executor,table_column:&str,table_name:&str,column:&str,column_value:V) -> std::result::Result<Vec<$table>,$crate::rbdc::Error> {impled!()}
+                let mut table_name = $table_name.to_string();
+                #[$crate::snake_name($table)]
+                fn snake_name(){}
+                if table_name.is_empty(){
+                    table_name = snake_name();
+                }
+                let naming = $crate::naming::naming_of(executor, &table_name);
+                let column = naming.to_column(column.column_name());
+                $crate::wrapper::check_column(&column)?;
+                let mut table_column = "*".to_string();
+                if !naming.is_exact() {
+                    table_column = naming.select_columns($crate::utils::table_util::table_columns::<$table>().unwrap_or_default());
+                }
+                let mut tables = select_by_column(executor, &table_column, &table_name, &column, column_value).await?;
+                if let Some(hooks) = $crate::table_hooks!($table) {
+                    (hooks.after_load)(executor, &mut tables)?;
+                }
+                Ok(tables)
+            }
+
+            /// `select * from table where column in (?,?)`, the values are split by the bind parameter limit
+            pub async fn select_in_column<V:serde::Serialize,C:$crate::column::TableColumn<$table,V>>(
+                executor: &dyn $crate::executor::Executor,
+                column: C,
+                column_values: &[V],
+            ) -> std::result::Result<Vec<$table>, $crate::rbdc::Error> {
+                #[$crate::py_sql("`select ${table_column} from ${table_name} `","` where ${column} in (`
+                  trim ',': for _,item in column_values:
+                     #{item},
+                  `)`")]
+                async fn select_in_column<V:serde::Serialize>(executor: &dyn $crate::executor::Executor,table_column:&str,table_name:&str,column:&str,column_values:&[V]) -> std::result::Result<Vec<$table>,$crate::rbdc::Error> {impled!()}
+                let mut table_name = $table_name.to_string();
+                #[$crate::snake_name($table)]
+                fn snake_name(){}
+                if table_name.is_empty(){
+                    table_name = snake_name();
+                }
+                let naming = $crate::naming::naming_of(executor, &table_name);
+                let column = naming.to_column(column.column_name());
+                $crate::wrapper::check_column(&column)?;
+                let table_column = naming.select_columns($crate::utils::table_util::table_columns::<$table>().unwrap_or_default());
+                let mut tables = vec![];
+                if column_values.is_empty() {
+                    tables = select_in_column(executor, &table_column, &table_name, &column, column_values).await?;
+                } else {
+                    let driver_type = executor.driver_type().unwrap_or_default();
+                    let base_sql_len = 32 + table_name.len() + table_column.len() + column.len();
+                    let ranges = executor.rb_ref().batch_limit.ranges(driver_type, column_values.len(), 0, 1, base_sql_len, 2);
+                    for (offset, limit) in ranges {
+                        tables.extend(select_in_column(executor, &table_column, &table_name, &column, &column_values[offset..limit]).await?);
+                    }
+                }
+                if let Some(hooks) = $crate::table_hooks!($table) {
+                    (hooks.after_load)(executor, &mut tables)?;
+                }
+                Ok(tables)
+            }
+
+            pub async fn select_by_wrapper(
+                executor: &dyn $crate::executor::Executor,
+                wrapper: &$crate::wrapper::QueryWrapper,
+            ) -> std::result::Result<Vec<$table>, $crate::rbdc::Error> {
+                let mut table_name = $table_name.to_string();
+                #[$crate::snake_name($table)]
+                fn snake_name(){}
+                if table_name.is_empty(){
+                    table_name = snake_name();
+                }
+                wrapper.check_lock(executor)?;
+                let naming = $crate::naming::naming_of(executor, &table_name);
+                let driver_type = executor.driver_type().unwrap_or_default();
+                let (sql, args) = wrapper.with_naming(&naming).to_select_sql(driver_type, &table_name)?;
+                let rows = executor.query_rows(&sql, args).await?;
+                let fields = $crate::utils::table_util::table_columns::<$table>().unwrap_or_default();
+                let mut tables: Vec<$table> = $crate::decode::decode_rows(&naming.to_fields_rows(rows, fields))?;
+                if let Some(hooks) = $crate::table_hooks!($table) {
+                    (hooks.after_load)(executor, &mut tables)?;
+                }
+                Ok(tables)
+            }
+
+            /// `select * from table where column = ? for update`, executor must be a transaction
+            pub async fn select_by_column_for_update<V: serde::Serialize, C: $crate::column::TableColumn<$table, V>>(
+                executor: &dyn $crate::executor::Executor,
+                column: C,
+                column_value: V,
+            ) -> std::result::Result<Vec<$table>, $crate::rbdc::Error> {
+                let wrapper = $crate::wrapper::QueryWrapper::new()
+                    .eq(column, column_value)
+                    .for_update();
+                <$table>::select_by_wrapper(executor, &wrapper).await
+            }
+
+            /// select by wrapper with `for update skip locked`, executor must be a transaction.
+            /// for example take 10 jobs of a work queue: `QueryWrapper::new().eq("status", 0).limit(10)`
+            pub async fn select_for_update_skip_locked(
+                executor: &dyn $crate::executor::Executor,
+                wrapper: &$crate::wrapper::QueryWrapper,
+            ) -> std::result::Result<Vec<$table>, $crate::rbdc::Error> {
+                let wrapper = wrapper.clone().for_update_skip_locked();
+                <$table>::select_by_wrapper(executor, &wrapper).await
+            }
+
+            /// select by wrapper and decode into `T`, the columns are the serialized fields of `T`
+            /// if the wrapper not select any column
+            pub async fn select_by_wrapper_as<T: serde::de::DeserializeOwned>(
+                executor: &dyn $crate::executor::Executor,
+                wrapper: &$crate::wrapper::QueryWrapper,
+            ) -> std::result::Result<Vec<T>, $crate::rbdc::Error> {
+                let mut table_name = $table_name.to_string();
+                #[$crate::snake_name($table)]
+                fn snake_name(){}
+                if table_name.is_empty(){
+                    table_name = snake_name();
+                }
+                let naming = $crate::naming::naming_of(executor, &table_name);
+                let fields = $crate::utils::table_util::table_columns::<T>().unwrap_or_default();
+                let mut wrapper = wrapper.with_naming(&naming);
+                if wrapper.columns.is_empty() {
+                    wrapper.columns = fields.iter().map(|v| naming.to_column(v)).collect();
+                }
+                wrapper.check_lock(executor)?;
+                let driver_type = executor.driver_type().unwrap_or_default();
+                let (sql, args) = wrapper.to_select_sql(driver_type, &table_name)?;
+                let rows = executor.query_rows(&sql, args).await?;
+                $crate::decode::decode_rows(&naming.to_fields_rows(rows, fields))
+            }
+
+            /// select all and decode into `T`, the columns are the serialized fields of `T`
+            pub async fn select_all_as<T: serde::de::DeserializeOwned>(
+                executor: &dyn $crate::executor::Executor,
+            ) -> std::result::Result<Vec<T>, $crate::rbdc::Error> {
+                <$table>::select_by_wrapper_as(executor, &$crate::wrapper::QueryWrapper::new()).await
+            }
+
+            /// `select_by_column` and decode into `T`, the columns are the serialized fields of `T`
+            pub async fn select_by_column_as<T: serde::de::DeserializeOwned, V: serde::Serialize, C: $crate::column::TableColumn<$table, V>>(
+                executor: &dyn $crate::executor::Executor,
+                column: C,
+                column_value: V,
+            ) -> std::result::Result<Vec<T>, $crate::rbdc::Error> {
+                let wrapper = $crate::wrapper::QueryWrapper::new().eq(column, column_value);
+                <$table>::select_by_wrapper_as(executor, &wrapper).await
+            }
+
+            /// `select_in_column` and decode into `T`, the columns are the serialized fields of `T`
+            pub async fn select_in_column_as<T: serde::de::DeserializeOwned, V: serde::Serialize, C: $crate::column::TableColumn<$table, V>>(
+                executor: &dyn $crate::executor::Executor,
+                column: C,
+                column_values: &[V],
+            ) -> std::result::Result<Vec<T>, $crate::rbdc::Error> {
+                let wrapper = $crate::wrapper::QueryWrapper::new().in_(column, column_values);
+                <$table>::select_by_wrapper_as(executor, &wrapper).await
+            }
+
+            pub async fn count_all(
+                executor: &dyn $crate::executor::Executor,
+            ) -> std::result::Result<u64, $crate::rbdc::Error> {
+                <$table>::count_by_wrapper(executor, &$crate::wrapper::QueryWrapper::new()).await
+            }
+
+            pub async fn count_by_column<V: serde::Serialize, C: $crate::column::TableColumn<$table, V>>(
+                executor: &dyn $crate::executor::Executor,
+                column: C,
+                column_value: V,
+            ) -> std::result::Result<u64, $crate::rbdc::Error> {
+                let wrapper = $crate::wrapper::QueryWrapper::new().eq(column, column_value);
+                <$table>::count_by_wrapper(executor, &wrapper).await
+            }
+
+            /// `select 1 from table where column = ? limit 1`
+            pub async fn exists_by_column<V: serde::Serialize, C: $crate::column::TableColumn<$table, V>>(
+                executor: &dyn $crate::executor::Executor,
+                column: C,
+                column_value: V,
+            ) -> std::result::Result<bool, $crate::rbdc::Error> {
+                let mut table_name = $table_name.to_string();
+                #[$crate::snake_name($table)]
+                fn snake_name(){}
+                if table_name.is_empty(){
+                    table_name = snake_name();
+                }
+                let wrapper = $crate::wrapper::QueryWrapper::new()
+                    .select(&["1"])
+                    .eq(column, column_value)
+                    .limit(1)
+                    .with_naming(&$crate::naming::naming_of(executor, &table_name));
+                let driver_type = executor.driver_type().unwrap_or_default();
+                let (sql, args) = wrapper.to_select_sql(driver_type, &table_name)?;
+                let v = executor.query(&sql, args).await?;
+                Ok(!v.is_empty())
+            }
+
+            /// aggregate `func(column)` of rows matching the wrapper, decode the single value into `R`, for example `Option<f64>`
+            pub async fn aggregate_by<R: serde::de::DeserializeOwned, C: $crate::column::TableColumnName<$table>>(
+                executor: &dyn $crate::executor::Executor,
+                func: &str,
+                column: C,
+                wrapper: &$crate::wrapper::QueryWrapper,
+            ) -> std::result::Result<R, $crate::rbdc::Error> {
+                let mut table_name = $table_name.to_string();
+                #[$crate::snake_name($table)]
+                fn snake_name(){}
+                if table_name.is_empty(){
+                    table_name = snake_name();
+                }
+                let naming = $crate::naming::naming_of(executor, &table_name);
+                let column = naming.to_column(column.column_name());
+                let (sql, args) = wrapper.with_naming(&naming).to_aggregate_sql(&table_name, func, &column, None)?;
+                let v = executor.query(&sql, args).await?;
+                $crate::decode(v)
+            }
+
+            /// aggregate `func(column)` of rows matching the wrapper grouped by `group_by`,
+            /// decode `{group value: aggregate value}` map into `R`, for example `HashMap<String, f64>`
+            pub async fn aggregate_group_by<R: serde::de::DeserializeOwned, C: $crate::column::TableColumnName<$table>, G: $crate::column::TableColumnName<$table>>(
+                executor: &dyn $crate::executor::Executor,
+                func: &str,
+                column: C,
+                group_by: G,
+                wrapper: &$crate::wrapper::QueryWrapper,
+            ) -> std::result::Result<R, $crate::rbdc::Error> {
+                let mut table_name = $table_name.to_string();
+                #[$crate::snake_name($table)]
+                fn snake_name(){}
+                if table_name.is_empty(){
+                    table_name = snake_name();
+                }
+                let naming = $crate::naming::naming_of(executor, &table_name);
+                let column = naming.to_column(column.column_name());
+                let group_by = naming.to_column(group_by.column_name());
+                let (sql, args) = wrapper.with_naming(&naming).to_aggregate_sql(&table_name, func, &column, Some(&group_by))?;
+                let v = executor.query(&sql, args).await?;
+                let mut map = rbs::value::map::ValueMap::new();
+                if let rbs::Value::Array(rows) = v {
+                    for row in rows {
+                        map.insert(row["agg_key"].clone(), row["agg_value"].clone());
+                    }
+                }
+                rbs::from_value(rbs::Value::Map(map))
+            }
+
+            /// `sum(column)`, see aggregate_by
+            pub async fn sum_by<R: serde::de::DeserializeOwned, C: $crate::column::TableColumnName<$table>>(
+                executor: &dyn $crate::executor::Executor,
+                column: C,
+                wrapper: &$crate::wrapper::QueryWrapper,
+            ) -> std::result::Result<R, $crate::rbdc::Error> {
+                <$table>::aggregate_by(executor, "sum", column, wrapper).await
+            }
+
+            /// `max(column)`, see aggregate_by
+            pub async fn max_by<R: serde::de::DeserializeOwned, C: $crate::column::TableColumnName<$table>>(
+                executor: &dyn $crate::executor::Executor,
+                column: C,
+                wrapper: &$crate::wrapper::QueryWrapper,
+            ) -> std::result::Result<R, $crate::rbdc::Error> {
+                <$table>::aggregate_by(executor, "max", column, wrapper).await
+            }
+
+            /// `min(column)`, see aggregate_by
+            pub async fn min_by<R: serde::de::DeserializeOwned, C: $crate::column::TableColumnName<$table>>(
+                executor: &dyn $crate::executor::Executor,
+                column: C,
+                wrapper: &$crate::wrapper::QueryWrapper,
+            ) -> std::result::Result<R, $crate::rbdc::Error> {
+                <$table>::aggregate_by(executor, "min", column, wrapper).await
+            }
+
+            /// `avg(column)`, see aggregate_by
+            pub async fn avg_by<R: serde::de::DeserializeOwned, C: $crate::column::TableColumnName<$table>>(
+                executor: &dyn $crate::executor::Executor,
+                column: C,
+                wrapper: &$crate::wrapper::QueryWrapper,
+            ) -> std::result::Result<R, $crate::rbdc::Error> {
+                <$table>::aggregate_by(executor, "avg", column, wrapper).await
+            }
+
+            pub async fn count_by_wrapper(
+                executor: &dyn $crate::executor::Executor,
+                wrapper: &$crate::wrapper::QueryWrapper,
+            ) -> std::result::Result<u64, $crate::rbdc::Error> {
+                let mut table_name = $table_name.to_string();
+                #[$crate::snake_name($table)]
+                fn snake_name(){}
+                if table_name.is_empty(){
+                    table_name = snake_name();
+                }
+                let wrapper = wrapper.with_naming(&$crate::naming::naming_of(executor, &table_name));
+                let (sql, args) = wrapper.to_count_sql(&table_name)?;
+                let rows = executor.query_rows(&sql, args).await?;
+                $crate::decode::decode_rows(&rows)
+            }
+        }
     };
     ($table:ty{$fn_name:ident $(< $($gkey:ident:$gtype:path $(,)?)* >)? ($($param_key:ident:$param_type:ty $(,)?)*) => $sql:expr}$(,$table_name:expr)?) => {
         $crate::impl_select!($table{$fn_name$(<$($gkey:$gtype,)*>)?($($param_key:$param_type,)*) ->Vec => $sql}$(,$table_name)?);
@@ -228,7 +842,15 @@ macro_rules! impl_select {
                      if table_name.is_empty(){
                          table_name = snake_name();
                      }
-                     $fn_name(executor,&table_column,&table_name,$($param_key ,)*).await
+                     let naming = $crate::naming::naming_of(executor, &table_name);
+                     if !naming.is_exact() {
+                         table_column = naming.select_columns($crate::utils::table_util::table_columns::<$table>().unwrap_or_default());
+                     }
+                     let mut tables = $fn_name(executor,&table_column,&table_name,$($param_key ,)*).await?;
+                     if let Some(hooks) = $crate::table_hooks!($table) {
+                         (hooks.after_load)(executor, $crate::hooks::Loaded::tables_mut(&mut tables))?;
+                     }
+                     Ok(tables)
             }
         }
     };
@@ -261,40 +883,50 @@ macro_rules! impl_update {
         $crate::impl_update!($table{update_by_column_value(column: &str, column_value: &rbs::Value, skip_null: bool) => "`where ${column} = #{column_value}`"},$table_name);
         impl $table {
             ///  will skip null column
-            pub async fn update_by_column(
+            pub async fn update_by_column<C: $crate::column::TableColumnName<$table>>(
                 executor: &dyn $crate::executor::Executor,
                 table: &$table,
-                column: &str) -> std::result::Result<$crate::rbdc::db::ExecResult, $crate::rbdc::Error>{
+                column: C) -> std::result::Result<$crate::rbdc::db::ExecResult, $crate::rbdc::Error>{
                 <$table>::update_by_column_skip(executor,table,column,true).await
             }
 
             ///will skip null column
-            pub async fn update_by_column_batch(
+            pub async fn update_by_column_batch<C: $crate::column::TableColumnName<$table>>(
                 executor: &dyn $crate::executor::Executor,
                 tables: &[$table],
-                column: &str,
+                column: C,
                 batch_size: u64
             ) -> std::result::Result<$crate::rbdc::db::ExecResult, $crate::rbdc::Error> {
               <$table>::update_by_column_batch_skip(executor,tables,column,batch_size,true).await
             }
 
-            pub async fn update_by_column_skip(
+            pub async fn update_by_column_skip<C: $crate::column::TableColumnName<$table>>(
                 executor: &dyn $crate::executor::Executor,
                 table: &$table,
-                column: &str,
+                column: C,
                 skip_null: bool) -> std::result::Result<$crate::rbdc::db::ExecResult, $crate::rbdc::Error>{
+                let column = column.column_name();
                 let columns = rbs::to_value!(table);
                 let column_value = &columns[column];
-                <$table>::update_by_column_value(executor,table,column,column_value,skip_null).await
+                let mut table_name = $table_name.to_string();
+                #[$crate::snake_name($table)]
+                fn snake_name(){}
+                if table_name.is_empty(){
+                    table_name = snake_name();
+                }
+                let column = $crate::naming::naming_of(executor, &table_name).to_column(column);
+                $crate::wrapper::check_column(&column)?;
+                <$table>::update_by_column_value(executor,table,&column,column_value,skip_null).await
             }
 
-            pub async fn update_by_column_batch_skip(
+            pub async fn update_by_column_batch_skip<C: $crate::column::TableColumnName<$table>>(
                 executor: &dyn $crate::executor::Executor,
                 tables: &[$table],
-                column: &str,
+                column: C,
                 batch_size: u64,
                 skip_null: bool
             ) -> std::result::Result<$crate::rbdc::db::ExecResult, $crate::rbdc::Error> {
+                let column = column.column_name();
                 let mut rows_affected = 0;
                 let ranges = $crate::plugin::Page::<()>::make_ranges(tables.len() as u64, batch_size);
                 for (offset, limit) in ranges {
@@ -308,6 +940,33 @@ macro_rules! impl_update {
                     last_insert_id:rbs::Value::Null,
                 })
             }
+
+            /// update by wrapper conditions, the wrapper must have where conditions
+            pub async fn update_by_wrapper(
+                executor: &dyn $crate::executor::Executor,
+                table: &$table,
+                wrapper: &$crate::wrapper::QueryWrapper,
+                skip_null: bool,
+            ) -> std::result::Result<$crate::rbdc::db::ExecResult, $crate::rbdc::Error> {
+                let hooked;
+                let table = match $crate::table_hooks!($table) {
+                    Some(hooks) => {
+                        hooked = (hooks.before_update)(executor, table)?;
+                        &hooked
+                    }
+                    None => table,
+                };
+                let mut table_name = $table_name.to_string();
+                #[$crate::snake_name($table)]
+                fn snake_name(){}
+                if table_name.is_empty(){
+                    table_name = snake_name();
+                }
+                let naming = $crate::naming::naming_of(executor, &table_name);
+                let table = naming.to_columns(rbs::to_value!(table));
+                let (sql, args) = wrapper.with_naming(&naming).to_update_sql(&table_name, &table, skip_null)?;
+                executor.exec(&sql, args).await
+            }
         }
     };
     ($table:ty{$fn_name:ident($($param_key:ident:$param_type:ty$(,)?)*) => $sql_where:expr}$(,$table_name:expr)?) => {
@@ -320,6 +979,14 @@ macro_rules! impl_update {
                 if $sql_where.is_empty(){
                     return Err($crate::rbdc::Error::from("sql_where can't be empty!"));
                 }
+                let hooked;
+                let table = match $crate::table_hooks!($table) {
+                    Some(hooks) => {
+                        hooked = (hooks.before_update)(executor, table)?;
+                        &hooked
+                    }
+                    None => table,
+                };
                 #[$crate::py_sql("`update ${table_name} set `
                                  trim ',':
                                    for k,v in table:
@@ -345,7 +1012,7 @@ macro_rules! impl_update {
                   if table_name.is_empty(){
                          table_name = snake_name();
                   }
-                  let table = rbs::to_value!(table);
+                  let table = $crate::naming::naming_of(executor, &table_name).to_columns(rbs::to_value!(table));
                   $fn_name(executor, table_name, &table, true, $($param_key,)*).await
             }
         }
@@ -376,30 +1043,115 @@ macro_rules! impl_delete {
         );
     };
     ($table:ty{},$table_name:expr) => {
-        $crate::impl_delete!($table {delete_by_column<V:serde::Serialize>(column:&str,column_value: V) => "`where ${column} = #{column_value}`"},$table_name);
-        $crate::impl_delete!($table {delete_in_column<V:serde::Serialize>(column:&str,column_values: &[V]) =>
-        "`where ${column} in (`
-          trim ',': for _,item in column_values:
-             #{item},
-          `)`"},$table_name);
-
         impl $table {
-            pub async fn delete_by_column_batch<V:serde::Serialize>(
+            pub async fn delete_by_column<V:serde::Serialize,C:$crate::column::TableColumn<$table,V>>(
+                executor: &dyn $crate::executor::Executor,
+                column: C,
+                column_value: V,
+            ) -> std::result::Result<$crate::rbdc::db::ExecResult, $crate::rbdc::Error> {
+                #[$crate::py_sql("`delete from ${table_name} `","`where ${column} = #{column_value}`")]
+                async fn delete_by_column<V:serde::Serialize>(
+                    executor: &dyn $crate::executor::Executor,
+                    table_name: &str,
+                    column: &str,
+                    column_value: V,
+                ) -> std::result::Result<$crate::rbdc::db::ExecResult, $crate::rbdc::Error> {
+                    impled!()
+                }
+                if let Some(hooks) = $crate::table_hooks!($table) {
+                    (hooks.before_delete)(executor, &$crate::wrapper::QueryWrapper::new().eq(column.column_name(), &column_value))?;
+                }
+                let mut table_name = $table_name.to_string();
+                #[$crate::snake_name($table)]
+                fn snake_name(){}
+                if table_name.is_empty(){
+                    table_name = snake_name();
+                }
+                let column = $crate::naming::naming_of(executor, &table_name).to_column(column.column_name());
+                $crate::wrapper::check_column(&column)?;
+                delete_by_column(executor, &table_name, &column, column_value).await
+            }
+
+            /// `delete from table where column in (?,?)`, the values are split by the bind parameter limit
+            pub async fn delete_in_column<V:serde::Serialize,C:$crate::column::TableColumn<$table,V>>(
+                executor: &dyn $crate::executor::Executor,
+                column: C,
+                column_values: &[V],
+            ) -> std::result::Result<$crate::rbdc::db::ExecResult, $crate::rbdc::Error> {
+                #[$crate::py_sql("`delete from ${table_name} `","`where ${column} in (`
+                  trim ',': for _,item in column_values:
+                     #{item},
+                  `)`")]
+                async fn delete_in_column<V:serde::Serialize>(
+                    executor: &dyn $crate::executor::Executor,
+                    table_name: &str,
+                    column: &str,
+                    column_values: &[V],
+                ) -> std::result::Result<$crate::rbdc::db::ExecResult, $crate::rbdc::Error> {
+                    impled!()
+                }
+                let mut table_name = $table_name.to_string();
+                #[$crate::snake_name($table)]
+                fn snake_name(){}
+                if table_name.is_empty(){
+                    table_name = snake_name();
+                }
+                if let Some(hooks) = $crate::table_hooks!($table) {
+                    (hooks.before_delete)(executor, &$crate::wrapper::QueryWrapper::new().in_(column.column_name(), column_values))?;
+                }
+                let column = $crate::naming::naming_of(executor, &table_name).to_column(column.column_name());
+                $crate::wrapper::check_column(&column)?;
+                if column_values.is_empty() {
+                    return delete_in_column(executor, &table_name, &column, column_values).await;
+                }
+                let driver_type = executor.driver_type().unwrap_or_default();
+                let base_sql_len = 32 + table_name.len() + column.len();
+                let ranges = executor.rb_ref().batch_limit.ranges(driver_type, column_values.len(), 0, 1, base_sql_len, 2);
+                let mut rows_affected = 0;
+                for (offset, limit) in ranges {
+                    rows_affected += delete_in_column(executor, &table_name, &column, &column_values[offset..limit]).await?.rows_affected;
+                }
+                Ok($crate::rbdc::db::ExecResult{
+                    rows_affected,
+                    last_insert_id: rbs::Value::Null
+                })
+            }
+
+            pub async fn delete_by_column_batch<V:serde::Serialize,C:$crate::column::TableColumn<$table,V>>(
                 executor: &dyn $crate::executor::Executor,
-                column: &str,
+                column: C,
                 values: &[V],
                 batch_size: u64,
             ) -> std::result::Result<$crate::rbdc::db::ExecResult, $crate::rbdc::Error> {
                 let mut rows_affected = 0;
                 let ranges = $crate::plugin::Page::<()>::make_ranges(values.len() as u64, batch_size);
                 for (offset, limit) in ranges {
-                    rows_affected += <$table>::delete_in_column(executor,column,&values[offset as usize..limit as usize]).await?.rows_affected;
+                    rows_affected += <$table>::delete_in_column(executor,column.column_name(),&values[offset as usize..limit as usize]).await?.rows_affected;
                 }
                 Ok($crate::rbdc::db::ExecResult{
                     rows_affected: rows_affected,
                     last_insert_id: rbs::Value::Null
                 })
             }
+
+            /// delete by wrapper conditions, the wrapper must have where conditions
+            pub async fn delete_by_wrapper(
+                executor: &dyn $crate::executor::Executor,
+                wrapper: &$crate::wrapper::QueryWrapper,
+            ) -> std::result::Result<$crate::rbdc::db::ExecResult, $crate::rbdc::Error> {
+                let mut table_name = $table_name.to_string();
+                #[$crate::snake_name($table)]
+                fn snake_name(){}
+                if table_name.is_empty(){
+                    table_name = snake_name();
+                }
+                if let Some(hooks) = $crate::table_hooks!($table) {
+                    (hooks.before_delete)(executor, wrapper)?;
+                }
+                let wrapper = wrapper.with_naming(&$crate::naming::naming_of(executor, &table_name));
+                let (sql, args) = wrapper.to_delete_sql(&table_name)?;
+                executor.exec(&sql, args).await
+            }
         }
     };
     ($table:ty{$fn_name:ident $(< $($gkey:ident:$gtype:path $(,)?)* >)? ($($param_key:ident:$param_type:ty$(,)?)*) => $sql_where:expr}$(,$table_name:expr)?) => {
@@ -442,26 +1194,26 @@ macro_rules! impl_delete {
 /// rbatis::impl_select_page!(MockTable{select_page() =>"
 ///      if do_count == false:
 ///        `order by create_time desc`"});
+///
+/// #[derive(serde::Serialize, serde::Deserialize)]
+/// pub struct MockTableBrief{}
+/// //select the fields of MockTableBrief(mapped to columns by the table naming), and decode records into MockTableBrief
+/// rbatis::impl_select_page!(MockTable{select_page_brief() -> MockTableBrief =>"
+///      if do_count == false:
+///        `order by create_time desc`"});
 /// ```
 ///
 /// you can see ${page_no} = (page_no -1) * page_size;
 /// you can see ${page_size} = page_size;
 #[macro_export]
 macro_rules! impl_select_page {
-    ($table:ty{$fn_name:ident($($param_key:ident:$param_type:ty$(,)?)*) => $where_sql:expr}) => {
-        $crate::impl_select_page!(
-            $table{$fn_name($($param_key:$param_type,)*)=> $where_sql},
-            ""
-        );
-    };
-    ($table:ty{$fn_name:ident($($param_key:ident:$param_type:ty$(,)?)*) => $where_sql:expr}$(,$table_name:expr)?) => {
+    (@impl $table:ty, $dto:ty, $select_fields:expr, $fn_name:ident($($param_key:ident:$param_type:ty$(,)?)*) => $where_sql:expr $(,$table_name:expr)?) => {
         impl $table {
             pub async fn $fn_name(
                 executor: &dyn $crate::executor::Executor,
                 page_request: &dyn $crate::plugin::IPageRequest,
                 $($param_key:$param_type,)*
-            ) -> std::result::Result<$crate::plugin::Page::<$table>, $crate::rbdc::Error> {
-                let mut table_column = "*".to_string();
+            ) -> std::result::Result<$crate::plugin::Page::<$dto>, $crate::rbdc::Error> {
                 let mut table_name = String::new();
                 $(table_name = $table_name.to_string();)?
                 #[$crate::snake_name($table)]
@@ -469,17 +1221,50 @@ macro_rules! impl_select_page {
                 if table_name.is_empty(){
                     table_name = snake_name();
                 }
+                let naming = $crate::naming::naming_of(executor, &table_name);
+                let fields = $crate::utils::table_util::table_columns::<$dto>().unwrap_or_default();
+                let table_column = if $select_fields && !fields.is_empty() {
+                    fields.iter().map(|v| naming.to_column(v)).collect::<Vec<_>>().join(",")
+                } else {
+                    "*".to_string()
+                };
                 $crate::pysql_select_page!($fn_name(
                                      table_column:&str,
                                      table_name: &str,
-                                     $($param_key:&$param_type,)*) -> $table => 
+                                     $($param_key:&$param_type,)*) -> rbs::Value =>
                "`select ${table_column} from ${table_name} `\n",$where_sql);
-               
+
                 let page = $fn_name(executor,page_request,&table_column,&table_name,$(&$param_key,)*).await?;
-                Ok(page)
+                //the columns are renamed to the fields by the table naming
+                let records = $crate::decode(naming.to_fields(rbs::Value::Array(page.records), fields))?;
+                Ok($crate::plugin::Page::<$dto> {
+                    records,
+                    total: page.total,
+                    page_no: page.page_no,
+                    page_size: page.page_size,
+                    do_count: page.do_count,
+                })
             }
         }
     };
+    ($table:ty{$fn_name:ident($($param_key:ident:$param_type:ty$(,)?)*) => $where_sql:expr}) => {
+        $crate::impl_select_page!(
+            $table{$fn_name($($param_key:$param_type,)*)=> $where_sql},
+            ""
+        );
+    };
+    ($table:ty{$fn_name:ident($($param_key:ident:$param_type:ty$(,)?)*) -> $dto:ty => $where_sql:expr}) => {
+        $crate::impl_select_page!(
+            $table{$fn_name($($param_key:$param_type,)*) -> $dto => $where_sql},
+            ""
+        );
+    };
+    ($table:ty{$fn_name:ident($($param_key:ident:$param_type:ty$(,)?)*) => $where_sql:expr}$(,$table_name:expr)?) => {
+        $crate::impl_select_page!(@impl $table, $table, false, $fn_name($($param_key:$param_type,)*) => $where_sql $(,$table_name)?);
+    };
+    ($table:ty{$fn_name:ident($($param_key:ident:$param_type:ty$(,)?)*) -> $dto:ty => $where_sql:expr}$(,$table_name:expr)?) => {
+        $crate::impl_select_page!(@impl $table, $dto, true, $fn_name($($param_key:$param_type,)*) => $where_sql $(,$table_name)?);
+    };
 }
 
 /// impl html_sql select page.
@@ -531,16 +1316,11 @@ macro_rules! htmlsql_select_page {
                  $crate::impled!()
              }
               let mut executor = executor;
-              let mut conn = None;
-              if executor.name().eq($crate::executor::Executor::name(executor.rb_ref())){
-                  conn = Some(executor.rb_ref().acquire().await?);
-                  match &conn {
-                      Some(c) => {
-                          executor = c;
-                      }
-                      None => {}
-                  }
-             }
+              //one connection for the count and select, the page intercept find them by the executor id
+              let conn = executor.acquire_pinned().await?;
+              if let Some(c) = &conn {
+                  executor = c;
+              }
              let mut total = 0;
              if page_request.do_count() {
                 if let Some(intercept) = executor.rb_ref().get_intercept::<$crate::plugin::intercept_page::PageIntercept>(){
@@ -600,15 +1380,10 @@ macro_rules! pysql_select_page {
                  $crate::impled!()
               }
               let mut executor = executor;
-              let mut conn = None;
-              if executor.name().eq($crate::executor::Executor::name(executor.rb_ref())){
-                  conn = Some(executor.rb_ref().acquire().await?);
-                  match &conn {
-                      Some(c) => {
-                          executor = c;
-                      }
-                      None => {}
-                  }
+              //one connection for the count and select, the page intercept find them by the executor id
+              let conn = executor.acquire_pinned().await?;
+              if let Some(c) = &conn {
+                  executor = c;
               }
               let mut total = 0;
               if page_request.do_count() {
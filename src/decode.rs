@@ -1,6 +1,9 @@
 //! Types and traits for decoding values from the database.
+use rbdc::db::Row;
+use rbs::value::map::ValueMap;
 use rbs::Value;
 use serde::de::DeserializeOwned;
+use std::cell::RefCell;
 
 use crate::Error;
 
@@ -16,7 +19,7 @@ where
     let is_array = rbs::from_value::<T>(Value::Array(vec![])).is_ok();
     if is_array {
         //decode array
-        Ok(rbs::from_value_ref(values)?)
+        rbs::from_value_ref(values).map_err(|e| decode_error::<T>(values, "", e))
     } else {
         match values {
             Value::Array(datas) => Ok(try_decode_map(datas)?),
@@ -49,39 +52,720 @@ where
         return Ok(rbs::from_value::<T>(Value::Null)?);
     }
     let m = datas.get(0).unwrap_or(&Value::Null);
-    match &m {
-        Value::Map(map) => {
-            if map.len() == 1 {
-                //try one
-                let type_name = std::any::type_name::<T>();
-                if type_name == std::any::type_name::<i32>()
-                    || type_name == std::any::type_name::<i64>()
-                    || type_name == std::any::type_name::<f32>()
-                    || type_name == std::any::type_name::<f64>()
-                    || type_name == std::any::type_name::<u32>()
-                    || type_name == std::any::type_name::<u64>()
-                    || type_name == std::any::type_name::<String>()
-                    || type_name == std::any::type_name::<bool>()
-                    || type_name == std::any::type_name::<Option<i32>>()
-                    || type_name == std::any::type_name::<Option<i64>>()
-                    || type_name == std::any::type_name::<Option<f32>>()
-                    || type_name == std::any::type_name::<Option<f64>>()
-                    || type_name == std::any::type_name::<Option<u32>>()
-                    || type_name == std::any::type_name::<Option<u64>>()
-                    || type_name == std::any::type_name::<Option<String>>()
-                    || type_name == std::any::type_name::<Option<bool>>()
-                    || type_name.starts_with("rbdc::types::")
-                    || type_name.starts_with("core::option::Option<rbdc::types::")
-                {
-                    if let Some((_, value)) = map.into_iter().next(){
-                        return Ok(rbs::from_value_ref::<T>(value)?);
-                    }
+    let decode_row = || rbs::from_value_ref::<T>(m).map_err(|e| decode_error::<T>(m, "[0]", e));
+    let (key, column) = match m {
+        Value::Map(map) if map.len() == 1 => match map.into_iter().next() {
+            Some(v) => v,
+            None => return decode_row(),
+        },
+        _ => return decode_row(),
+    };
+    let decode_column = || {
+        rbs::from_value_ref::<T>(column).map_err(|e| {
+            let path = format!("[0].{}", key.as_str().unwrap_or_default());
+            decode_error::<T>(column, &path, e)
+        })
+    };
+    match type_kind::<T>() {
+        TypeKind::Map => decode_row(),
+        TypeKind::Scalar => decode_column(),
+        //try the row, then the single column value
+        TypeKind::Unknown => match rbs::from_value_ref::<T>(m) {
+            Ok(v) => Ok(v),
+            Err(row_err) => decode_column().map_err(|e| {
+                Error::from(format!("{}, decode as row fail: {}", e, row_err))
+            }),
+        },
+    }
+}
+
+/// how a type is deserialized
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum TypeKind {
+    /// struct or map, decode from the row
+    Map,
+    /// number,string,bool,unit enum..., decode from the single column value
+    Scalar,
+    /// deserialize_any or a custom visitor
+    Unknown,
+}
+
+/// probe the kind of `T` by the first `deserialize_*` method it calls,
+/// `Option<T>` and newtype struct(`#[serde(transparent)]`) are probed by the inner type
+fn type_kind<T: DeserializeOwned>() -> TypeKind {
+    use serde::de::{Error as _, Visitor};
+    use serde::Deserializer;
+
+    struct Probe<'a>(&'a mut Option<TypeKind>);
+
+    impl Probe<'_> {
+        fn kind<R>(self, kind: TypeKind) -> Result<R, serde::de::value::Error> {
+            if self.0.is_none() {
+                *self.0 = Some(kind);
+            }
+            Err(serde::de::value::Error::custom("probe"))
+        }
+    }
+
+    macro_rules! probe_scalar {
+        ($($method:ident)*) => {
+            $(fn $method<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+                self.kind(TypeKind::Scalar)
+            })*
+        };
+    }
+
+    impl<'de> Deserializer<'de> for Probe<'_> {
+        type Error = serde::de::value::Error;
+
+        fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+            self.kind(TypeKind::Unknown)
+        }
+
+        probe_scalar!(deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64
+            deserialize_i128 deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64
+            deserialize_u128 deserialize_f32 deserialize_f64 deserialize_char deserialize_str
+            deserialize_string deserialize_bytes deserialize_byte_buf deserialize_unit
+            deserialize_identifier);
+
+        fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+            visitor.visit_some(self)
+        }
+
+        fn deserialize_unit_struct<V: Visitor<'de>>(
+            self,
+            _name: &'static str,
+            _visitor: V,
+        ) -> Result<V::Value, Self::Error> {
+            self.kind(TypeKind::Scalar)
+        }
+
+        fn deserialize_newtype_struct<V: Visitor<'de>>(
+            self,
+            _name: &'static str,
+            visitor: V,
+        ) -> Result<V::Value, Self::Error> {
+            visitor.visit_newtype_struct(self)
+        }
+
+        fn deserialize_seq<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+            self.kind(TypeKind::Unknown)
+        }
+
+        fn deserialize_tuple<V: Visitor<'de>>(
+            self,
+            _len: usize,
+            _visitor: V,
+        ) -> Result<V::Value, Self::Error> {
+            self.kind(TypeKind::Unknown)
+        }
+
+        fn deserialize_tuple_struct<V: Visitor<'de>>(
+            self,
+            _name: &'static str,
+            _len: usize,
+            _visitor: V,
+        ) -> Result<V::Value, Self::Error> {
+            self.kind(TypeKind::Unknown)
+        }
+
+        fn deserialize_map<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+            self.kind(TypeKind::Map)
+        }
+
+        fn deserialize_struct<V: Visitor<'de>>(
+            self,
+            _name: &'static str,
+            _fields: &'static [&'static str],
+            _visitor: V,
+        ) -> Result<V::Value, Self::Error> {
+            self.kind(TypeKind::Map)
+        }
+
+        fn deserialize_enum<V: Visitor<'de>>(
+            self,
+            _name: &'static str,
+            _variants: &'static [&'static str],
+            _visitor: V,
+        ) -> Result<V::Value, Self::Error> {
+            self.kind(TypeKind::Scalar)
+        }
+
+        fn deserialize_ignored_any<V: Visitor<'de>>(
+            self,
+            _visitor: V,
+        ) -> Result<V::Value, Self::Error> {
+            self.kind(TypeKind::Unknown)
+        }
+    }
+
+    let mut kind = None;
+    let _ = T::deserialize(Probe(&mut kind));
+    kind.unwrap_or(TypeKind::Unknown)
+}
+
+/// add the row index and field path(for example `[12].create_time`), the value type and
+/// the target type to a decode error. `value` is decoded again to find the path, so this
+/// only costs when the decode fails
+fn decode_error<T: DeserializeOwned>(value: &Value, base_path: &str, e: Error) -> Error {
+    let state = PathState {
+        path: RefCell::new(vec![base_path.to_string()]),
+        failed: RefCell::new(None),
+    };
+    let _ = T::deserialize(PathDeserializer {
+        value,
+        state: &state,
+    });
+    //the custom Deserialize may fail after the value is deserialized
+    let (path, v) = state
+        .failed
+        .into_inner()
+        .unwrap_or_else(|| (base_path.to_string(), value));
+    let mut value = value_type(v).to_string();
+    if is_debug_mode() {
+        value.push_str(&format!(" {}", v));
+    }
+    Error::from(format!(
+        "[rb] decode {} fail at `{}`({}): {}",
+        std::any::type_name::<T>(),
+        path,
+        value,
+        e
+    ))
+}
+
+/// the variant name of a value
+fn value_type(v: &Value) -> &'static str {
+    match v {
+        Value::Null => "Null",
+        Value::Bool(_) => "Bool",
+        Value::I32(_) => "I32",
+        Value::I64(_) => "I64",
+        Value::U32(_) => "U32",
+        Value::U64(_) => "U64",
+        Value::F32(_) => "F32",
+        Value::F64(_) => "F64",
+        Value::String(_) => "String",
+        Value::Binary(_) => "Binary",
+        Value::Array(_) => "Array",
+        Value::Map(_) => "Map",
+        Value::Ext(name, _) => name,
+    }
+}
+
+struct PathState<'a> {
+    path: RefCell<Vec<String>>,
+    /// the path and value of the deepest failed value
+    failed: RefCell<Option<(String, &'a Value)>>,
+}
+
+impl<'a> PathState<'a> {
+    fn check<R>(&self, value: &'a Value, r: Result<R, Error>) -> Result<R, Error> {
+        if r.is_err() && self.failed.borrow().is_none() {
+            *self.failed.borrow_mut() = Some((self.path.borrow().concat(), value));
+        }
+        r
+    }
+}
+
+/// a `&Value` deserializer records the path of arrays and maps, the other values are
+/// deserialized by `rbs`
+struct PathDeserializer<'a, 's> {
+    value: &'a Value,
+    state: &'s PathState<'a>,
+}
+
+struct PathSeq<'a, 's> {
+    iter: std::iter::Enumerate<std::slice::Iter<'a, Value>>,
+    state: &'s PathState<'a>,
+}
+
+struct PathMap<'a, 's, I> {
+    iter: I,
+    value: Option<(&'a Value, &'a Value)>,
+    state: &'s PathState<'a>,
+}
+
+impl<'de, 'a, 's> serde::de::SeqAccess<'de> for PathSeq<'a, 's> {
+    type Error = Error;
+
+    fn next_element_seed<T: serde::de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Error> {
+        match self.iter.next() {
+            None => Ok(None),
+            Some((index, value)) => {
+                self.state.path.borrow_mut().push(format!("[{}]", index));
+                let r = seed.deserialize(PathDeserializer {
+                    value,
+                    state: self.state,
+                });
+                self.state.path.borrow_mut().pop();
+                r.map(Some)
+            }
+        }
+    }
+}
+
+impl<'de, 'a, 's, I: Iterator<Item = (&'a Value, &'a Value)>> serde::de::MapAccess<'de>
+    for PathMap<'a, 's, I>
+{
+    type Error = Error;
+
+    fn next_key_seed<K: serde::de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Error> {
+        match self.iter.next() {
+            None => Ok(None),
+            Some((key, value)) => {
+                self.value = Some((key, value));
+                seed.deserialize(key).map(Some)
+            }
+        }
+    }
+
+    fn next_value_seed<V: serde::de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Error> {
+        let (key, value) = self
+            .value
+            .take()
+            .ok_or_else(|| Error::from("value is missing"))?;
+        self.state
+            .path
+            .borrow_mut()
+            .push(format!(".{}", key.as_str().unwrap_or_default()));
+        let r = seed.deserialize(PathDeserializer {
+            value,
+            state: self.state,
+        });
+        self.state.path.borrow_mut().pop();
+        r
+    }
+}
+
+macro_rules! forward_path_any {
+    ($($method:ident)*) => {
+        $(fn $method<V: serde::de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            self.deserialize_any(visitor)
+        })*
+    };
+}
+
+impl<'de, 'a, 's> serde::Deserializer<'de> for PathDeserializer<'a, 's> {
+    type Error = Error;
+
+    fn deserialize_any<V: serde::de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let r = match self.value {
+            Value::Array(arr) => visitor.visit_seq(PathSeq {
+                iter: arr.iter().enumerate(),
+                state: self.state,
+            }),
+            Value::Map(m) => visitor.visit_map(PathMap {
+                iter: m.into_iter(),
+                value: None,
+                state: self.state,
+            }),
+            v => serde::Deserializer::deserialize_any(v, visitor),
+        };
+        self.state.check(self.value, r)
+    }
+
+    fn deserialize_option<V: serde::de::Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        if self.value.is_null() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_newtype_struct<V: serde::de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        let (value, state) = (self.value, self.state);
+        state.check(value, visitor.visit_newtype_struct(self))
+    }
+
+    fn deserialize_unit_struct<V: serde::de::Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        let r = serde::Deserializer::deserialize_unit_struct(self.value, name, visitor);
+        self.state.check(self.value, r)
+    }
+
+    fn deserialize_enum<V: serde::de::Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        let r = serde::Deserializer::deserialize_enum(self.value, name, variants, visitor);
+        self.state.check(self.value, r)
+    }
+
+    fn deserialize_tuple_struct<V: serde::de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_struct<V: serde::de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_tuple<V: serde::de::Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_any(visitor)
+    }
+
+    forward_path_any!(deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32
+        deserialize_i64 deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64
+        deserialize_f32 deserialize_f64 deserialize_char deserialize_str deserialize_string
+        deserialize_bytes deserialize_byte_buf deserialize_unit deserialize_seq deserialize_map
+        deserialize_identifier deserialize_ignored_any);
+}
+
+/// the rows of a query without `Value` maps: the column names are shared by all rows,
+/// every row is the values in column order. see `Executor::query_rows` and `decode_rows`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Rows {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<Value>>,
+}
+
+impl Rows {
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// read the driver rows, a repeated column name keeps the first column(same as `Connection::get_values`)
+    pub fn from_driver(rows: Vec<Box<dyn Row>>) -> Result<Self, Error> {
+        let mut columns: Vec<String> = vec![];
+        let mut indexes = vec![];
+        if let Some(row) = rows.first() {
+            let md = row.meta_data();
+            for i in 0..md.column_len() {
+                let name = md.column_name(i);
+                if !columns.contains(&name) {
+                    columns.push(name);
+                    indexes.push(i);
+                }
+            }
+        }
+        let mut values = Vec::with_capacity(rows.len());
+        for mut row in rows {
+            let mut v = Vec::with_capacity(indexes.len());
+            for i in &indexes {
+                v.push(row.get(*i)?);
+            }
+            values.push(v);
+        }
+        Ok(Self {
+            columns,
+            rows: values,
+        })
+    }
+
+    /// from the `Value` maps of `Executor::query`, the columns are the keys of all rows in order
+    /// and a missing column is null
+    pub fn from_values(values: Vec<Value>) -> Result<Self, Error> {
+        let mut columns: Vec<String> = vec![];
+        for row in &values {
+            let Value::Map(m) = row else {
+                return Err(Error::from(format!(
+                    "[rb] query row must be a map, but it is {}",
+                    value_type(row)
+                )));
+            };
+            for (k, _) in m {
+                let k = k.as_str().unwrap_or_default();
+                if !columns.iter().any(|v| v == k) {
+                    columns.push(k.to_string());
+                }
+            }
+        }
+        let rows = values
+            .into_iter()
+            .map(|row| {
+                let mut m = match row {
+                    Value::Map(m) => m,
+                    _ => ValueMap::new(),
+                };
+                columns
+                    .iter()
+                    .map(|c| m.remove(&Value::String(c.clone())))
+                    .collect()
+            })
+            .collect();
+        Ok(Self { columns, rows })
+    }
+
+    /// to the `Value` maps of `Executor::query`
+    pub fn into_values(self) -> Vec<Value> {
+        let columns = self.columns;
+        self.rows
+            .into_iter()
+            .map(|row| {
+                let mut m = ValueMap::with_capacity(columns.len());
+                for (k, v) in columns.iter().zip(row) {
+                    m.insert(Value::String(k.clone()), v);
                 }
+                Value::Map(m)
+            })
+            .collect()
+    }
+}
+
+/// decode rows as `decode` does with the `Value` maps of the rows, but the rows are deserialized
+/// directly(no map is built). if it fails, the error of `decode` is returned
+pub fn decode_rows<T>(rows: &Rows) -> Result<T, Error>
+where
+    T: DeserializeOwned,
+{
+    let is_array = rbs::from_value::<T>(Value::Array(vec![])).is_ok();
+    let result = if is_array {
+        T::deserialize(RowsDeserializer { rows })
+    } else if rows.len() == 1 {
+        let row = RowDeserializer {
+            columns: &rows.columns,
+            values: &rows.rows[0],
+        };
+        match (rows.columns.len(), type_kind::<T>()) {
+            (1, TypeKind::Scalar) => rbs::from_value_ref(&rows.rows[0][0]),
+            (1, TypeKind::Unknown) => {
+                T::deserialize(row).or_else(|_| rbs::from_value_ref(&rows.rows[0][0]))
+            }
+            _ => T::deserialize(row),
+        }
+    } else {
+        //empty or too many rows
+        return decode(Value::Array(rows.clone().into_values()));
+    };
+    result.or_else(|_| decode(Value::Array(rows.clone().into_values())))
+}
+
+struct RowsDeserializer<'a> {
+    rows: &'a Rows,
+}
+
+struct RowDeserializer<'a> {
+    columns: &'a [String],
+    values: &'a [Value],
+}
+
+struct RowsSeq<'a> {
+    columns: &'a [String],
+    rows: std::slice::Iter<'a, Vec<Value>>,
+}
+
+struct RowMap<'a> {
+    columns: std::slice::Iter<'a, String>,
+    values: std::slice::Iter<'a, Value>,
+    value: Option<&'a Value>,
+}
+
+impl<'de> serde::de::SeqAccess<'de> for RowsSeq<'de> {
+    type Error = Error;
+
+    fn next_element_seed<S: serde::de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: S,
+    ) -> Result<Option<S::Value>, Error> {
+        match self.rows.next() {
+            Some(row) => seed
+                .deserialize(RowDeserializer {
+                    columns: self.columns,
+                    values: row,
+                })
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.rows.len())
+    }
+}
+
+impl<'de> serde::de::MapAccess<'de> for RowMap<'de> {
+    type Error = Error;
+
+    fn next_key_seed<K: serde::de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Error> {
+        match (self.columns.next(), self.values.next()) {
+            (Some(k), Some(v)) => {
+                self.value = Some(v);
+                seed.deserialize(serde::de::value::BorrowedStrDeserializer::new(k))
+                    .map(Some)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: serde::de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Error> {
+        seed.deserialize(self.value.take().unwrap_or(&Value::Null))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.values.len())
+    }
+}
+
+impl<'de> serde::Deserializer<'de> for RowsDeserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: serde::de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_seq(RowsSeq {
+            columns: &self.rows.columns,
+            rows: self.rows.rows.iter(),
+        })
+    }
+
+    fn deserialize_option<V: serde::de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: serde::de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct enum
+        identifier ignored_any
+    }
+}
+
+impl<'de> serde::Deserializer<'de> for RowDeserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: serde::de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_map(RowMap {
+            columns: self.columns.iter(),
+            values: self.values.iter(),
+            value: None,
+        })
+    }
+
+    fn deserialize_option<V: serde::de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: serde::de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct enum
+        identifier ignored_any
+    }
+}
+
+/// the separator of column prefix and column name, for example `u__id`
+pub const PREFIX_SEPARATOR: &str = "__";
+
+/// decode rows of a join query into tuples, for example `Vec<(User, Role)>` or `Vec<(User, Option<Role>)>`.
+///
+/// every row is split into one map per prefix(in order of `prefixes`) by the column
+/// `{prefix}__{column}`, a map with all null columns(left join not matched) is decoded as null.
+/// the select list can be generated by `utils::table_util::prefix_columns`
+/// ```rust
+/// #[derive(serde::Serialize, serde::Deserialize)]
+/// pub struct User{ pub id: Option<i64> }
+/// #[derive(serde::Serialize, serde::Deserialize)]
+/// pub struct Role{ pub id: Option<i64> }
+///
+/// async fn test_use(rb:&rbatis::RBatis) -> Result<(), rbatis::Error> {
+///     use rbatis::utils::table_util::prefix_columns;
+///     let sql = format!("select {},{} from user u left join role r on u.role_id = r.id",
+///         prefix_columns::<User>("u").unwrap(),
+///         prefix_columns::<Role>("r").unwrap());
+///     let v = rb.query(&sql, vec![]).await?;
+///     let rows: Vec<(User, Option<Role>)> = rbatis::decode_prefix(v, &["u", "r"])?;
+///     Ok(())
+/// }
+/// ```
+pub fn decode_prefix<T>(values: Value, prefixes: &[&str]) -> Result<T, Error>
+where
+    T: DeserializeOwned,
+{
+    match values {
+        Value::Array(rows) => {
+            let rows = rows
+                .into_iter()
+                .map(|row| split_prefix(row, prefixes))
+                .collect::<Result<Vec<Value>, Error>>()?;
+            decode(Value::Array(rows))
+        }
+        _ => Err(Error::from("decode an not array value")),
+    }
+}
+
+/// split a row map into `[map of prefix 0, map of prefix 1 ...]`
+fn split_prefix(row: Value, prefixes: &[&str]) -> Result<Value, Error> {
+    let row = match row {
+        Value::Map(row) => row,
+        _ => return Err(Error::from("[rb] decode_prefix row must be a map")),
+    };
+    let mut maps: Vec<ValueMap> = prefixes.iter().map(|_| ValueMap::new()).collect();
+    for (k, v) in row {
+        let column = k.as_str().unwrap_or_default();
+        if let Some((prefix, name)) = column.split_once(PREFIX_SEPARATOR) {
+            if let Some(index) = prefixes.iter().position(|p| *p == prefix) {
+                maps[index].insert(Value::String(name.to_string()), v);
             }
         }
-        _ => {}
     }
-    Ok(rbs::from_value_ref::<T>(m)?)
+    Ok(Value::Array(
+        maps.into_iter()
+            .map(|m| {
+                if m.0.values().all(|v| v.is_null()) {
+                    Value::Null
+                } else {
+                    Value::Map(m)
+                }
+            })
+            .collect(),
+    ))
 }
 
 pub fn is_debug_mode() -> bool {
@@ -102,9 +786,11 @@ pub fn is_debug_mode() -> bool {
 #[cfg(test)]
 mod test {
     use crate::decode::decode;
+    use rbdc::types::Decimal;
     use rbs::value::map::ValueMap;
     use rbs::Value;
     use std::collections::HashMap;
+    use std::str::FromStr;
 
     #[test]
     fn test_decode_hashmap() {
@@ -114,4 +800,147 @@ mod test {
         println!("{:#?}", m);
         assert_eq!(m.get(&1).unwrap().as_i64(), Value::I32(2).as_i64());
     }
+
+    #[test]
+    fn test_decode_single_column() {
+        #[derive(serde::Deserialize, Debug, PartialEq)]
+        #[serde(transparent)]
+        struct UserId(i64);
+        #[derive(serde::Deserialize, Debug, PartialEq)]
+        enum Status {
+            Active,
+        }
+        #[derive(serde::Deserialize, Debug, PartialEq)]
+        struct Count {
+            count: u64,
+        }
+        let row = |v: Value| Value::Array(vec![rbs::to_value! {"count": v}]);
+        assert_eq!(decode::<usize>(row(Value::I64(3))).unwrap(), 3);
+        assert_eq!(decode::<i16>(row(Value::I64(3))).unwrap(), 3);
+        assert_eq!(decode::<Option<i16>>(row(Value::Null)).unwrap(), None);
+        assert_eq!(decode::<UserId>(row(Value::I64(3))).unwrap(), UserId(3));
+        assert_eq!(
+            decode::<Option<UserId>>(row(Value::I64(3))).unwrap(),
+            Some(UserId(3))
+        );
+        assert_eq!(
+            decode::<Status>(row(Value::String("Active".to_string()))).unwrap(),
+            Status::Active
+        );
+        assert_eq!(
+            decode::<Decimal>(row(Value::String("1.5".to_string()))).unwrap(),
+            Decimal::from_str("1.5").unwrap()
+        );
+        assert_eq!(
+            decode::<Count>(row(Value::U64(3))).unwrap(),
+            Count { count: 3 }
+        );
+        assert_eq!(
+            decode::<HashMap<String, u64>>(row(Value::U64(3))).unwrap()["count"],
+            3
+        );
+        let err = decode::<Decimal>(row(Value::Bool(true)))
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.starts_with("[rb] decode rbdc::types::decimal::Decimal fail at `[0].count`(Bool")
+                && err.contains("decode as row fail"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_decode_rows() {
+        use crate::decode::{decode_rows, Rows};
+        #[derive(serde::Deserialize, Debug, PartialEq)]
+        struct A {
+            id: i64,
+            name: Option<String>,
+        }
+        let rows = Rows {
+            columns: vec!["id".to_string(), "name".to_string()],
+            rows: vec![
+                vec![Value::I64(1), Value::String("a".to_string())],
+                vec![Value::I64(2), Value::Null],
+            ],
+        };
+        let values = Value::Array(rows.clone().into_values());
+        assert_eq!(Rows::from_values(rows.clone().into_values()).unwrap(), rows);
+        let v: Vec<A> = decode_rows(&rows).unwrap();
+        assert_eq!(v, decode::<Vec<A>>(values.clone()).unwrap());
+        let v: Vec<HashMap<String, Value>> = decode_rows(&rows).unwrap();
+        assert_eq!(
+            v,
+            decode::<Vec<HashMap<String, Value>>>(values.clone()).unwrap()
+        );
+        let v: Value = decode_rows(&rows).unwrap();
+        assert_eq!(v, values);
+        //one row
+        let mut one = rows.clone();
+        one.rows.truncate(1);
+        let v: Option<A> = decode_rows(&one).unwrap();
+        assert_eq!(v.unwrap().id, 1);
+        //one column
+        let count = Rows {
+            columns: vec!["count".to_string()],
+            rows: vec![vec![Value::I64(3)]],
+        };
+        assert_eq!(decode_rows::<i64>(&count).unwrap(), 3);
+        let amount = Rows {
+            columns: vec!["amount".to_string()],
+            rows: vec![vec![Value::String("3.5".to_string())]],
+        };
+        assert_eq!(
+            decode_rows::<Option<Decimal>>(&amount).unwrap(),
+            Decimal::from_str("3.5").ok()
+        );
+        assert_eq!(decode_rows::<Option<i64>>(&Rows::default()).unwrap(), None);
+        //same error as decode
+        assert_eq!(
+            decode_rows::<A>(&rows).err().unwrap().to_string(),
+            decode::<A>(values.clone()).err().unwrap().to_string()
+        );
+        assert_eq!(
+            decode_rows::<Vec<i32>>(&rows).err().unwrap().to_string(),
+            decode::<Vec<i32>>(values).err().unwrap().to_string()
+        );
+    }
+
+    #[test]
+    fn test_decode_prefix() {
+        #[derive(serde::Deserialize, Debug, PartialEq)]
+        struct User {
+            id: i64,
+            name: String,
+        }
+        #[derive(serde::Deserialize, Debug, PartialEq)]
+        struct Role {
+            id: i64,
+        }
+        let rows = Value::Array(vec![
+            rbs::to_value! {"u__id": 1, "u__name": "a", "r__id": 7},
+            rbs::to_value! {"u__id": 2, "u__name": "b", "r__id": Value::Null},
+        ]);
+        let v: Vec<(User, Option<Role>)> = crate::decode_prefix(rows, &["u", "r"]).unwrap();
+        assert_eq!(
+            v,
+            vec![
+                (
+                    User {
+                        id: 1,
+                        name: "a".to_string()
+                    },
+                    Some(Role { id: 7 })
+                ),
+                (
+                    User {
+                        id: 2,
+                        name: "b".to_string()
+                    },
+                    None
+                ),
+            ]
+        );
+    }
 }
@@ -1,5 +1,6 @@
-use crate::decode::decode;
+use crate::decode::{decode_rows, Rows};
 use crate::intercept::ResultType;
+use crate::json::encode_json_args;
 use crate::rbatis::RBatis;
 use crate::Error;
 use dark_std::sync::SyncVec;
@@ -9,8 +10,10 @@ use rbdc::db::{Connection, ExecResult};
 use rbdc::rt::tokio::sync::Mutex;
 use rbs::Value;
 use serde::de::DeserializeOwned;
-use std::any::Any;
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
 use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
 
 /// the rbatis's Executor. this trait impl with structs = RBatis,RBatisConnExecutor,RBatisTxExecutor,RBatisTxExecutorGuard
 pub trait Executor: RBatisRef + Send + Sync {
@@ -20,12 +23,274 @@ pub trait Executor: RBatisRef + Send + Sync {
     }
     fn exec(&self, sql: &str, args: Vec<Value>) -> BoxFuture<'_, Result<ExecResult, Error>>;
     fn query(&self, sql: &str, args: Vec<Value>) -> BoxFuture<'_, Result<Value, Error>>;
+    /// query rows without building `Value` maps, see `Rows` and `decode_rows`.
+    /// default convert the result of `query`
+    fn query_rows(&self, sql: &str, args: Vec<Value>) -> BoxFuture<'_, Result<Rows, Error>> {
+        let sql = sql.to_string();
+        Box::pin(async move {
+            match self.query(&sql, args).await? {
+                Value::Array(rows) => Rows::from_values(rows),
+                _ => Ok(Rows::default()),
+            }
+        })
+    }
     fn as_any(&self) -> &dyn Any
     where
         Self: Sized,
     {
         self
     }
+    /// the context values of this executor(for example current user,tenant,request id)
+    fn context(&self) -> Option<&Context> {
+        None
+    }
+    /// is this executor running in a transaction
+    fn in_transaction(&self) -> bool {
+        false
+    }
+    /// execute one sql many times(one time for every args) on one connection,
+    /// see `BatchExecResult`
+    fn exec_batch(
+        &self,
+        _sql: &str,
+        _args: Vec<Vec<Value>>,
+    ) -> BoxFuture<'_, Result<BatchExecResult, Error>> {
+        Box::pin(async {
+            Err(Error::from(
+                "[rb] exec_batch is not supported by this executor",
+            ))
+        })
+    }
+}
+
+/// the result of `Executor::exec_batch`
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct BatchExecResult {
+    /// total rows affected
+    pub rows_affected: u64,
+    /// the result of every args, in args order
+    pub results: Vec<ExecResult>,
+}
+
+/// run query_rows on a held connection, the intercepts see `before` and `after_rows`
+async fn query_rows_conn(
+    executor: &dyn Executor,
+    task_id: i64,
+    conn: &Mutex<Box<dyn Connection>>,
+    mut sql: String,
+    mut args: Vec<Value>,
+) -> Result<Rows, Error> {
+    let mut before_result = Err(Error::from(""));
+    for item in executor.rb_ref().intercepts.iter() {
+        let next = item
+            .before(
+                task_id,
+                executor,
+                &mut sql,
+                &mut args,
+                ResultType::Query(&mut before_result),
+            )
+            .await?;
+        if let Some(next) = next {
+            if !next {
+                break;
+            }
+        } else {
+            return before_result.and_then(Rows::from_values);
+        }
+    }
+    let mut args_after = args.clone();
+    encode_json_args(&mut args);
+    let mut result = match conn.lock().await.get_rows(&sql, args).await {
+        Ok(rows) => Rows::from_driver(rows),
+        Err(e) => Err(e),
+    };
+    for item in executor.rb_ref().intercepts.iter() {
+        let next = item
+            .after_rows(task_id, executor, &mut sql, &mut args_after, &mut result)
+            .await?;
+        if let Some(next) = next {
+            if !next {
+                break;
+            }
+        } else {
+            return result;
+        }
+    }
+    result
+}
+
+/// run exec_batch on a held connection.
+///
+/// * the intercepts see one batch call(`before_batch`/`after_batch`)
+/// * if the executor is not in a transaction, the calls are wrapped in one transaction
+/// * the same sql is executed on one connection, so the driver can reuse the prepared statement
+async fn exec_batch_conn(
+    executor: &dyn Executor,
+    task_id: i64,
+    conn: &Mutex<Box<dyn Connection>>,
+    mut sql: String,
+    mut args: Vec<Vec<Value>>,
+) -> Result<BatchExecResult, Error> {
+    let mut before_result = Err(Error::from(""));
+    for item in executor.rb_ref().intercepts.iter() {
+        let next = item
+            .before_batch(task_id, executor, &mut sql, &mut args, &mut before_result)
+            .await?;
+        if let Some(next) = next {
+            if !next {
+                break;
+            }
+        } else {
+            return before_result;
+        }
+    }
+    let mut args_after = args.clone();
+    for arg in args.iter_mut() {
+        encode_json_args(arg);
+    }
+    let in_transaction = executor.in_transaction();
+    let mut result = async {
+        let mut result = BatchExecResult::default();
+        if args.is_empty() {
+            return Ok(result);
+        }
+        let mut conn = conn.lock().await;
+        if !in_transaction {
+            conn.begin().await?;
+        }
+        for arg in args {
+            match conn.exec(&sql, arg).await {
+                Ok(v) => {
+                    result.rows_affected += v.rows_affected;
+                    result.results.push(v);
+                }
+                Err(e) => {
+                    if !in_transaction {
+                        _ = conn.rollback().await;
+                    }
+                    return Err(e);
+                }
+            }
+        }
+        if !in_transaction {
+            if let Err(e) = conn.commit().await {
+                _ = conn.rollback().await;
+                return Err(e);
+            }
+        }
+        Ok(result)
+    }
+    .await;
+    for item in executor.rb_ref().intercepts.iter() {
+        let next = item
+            .after_batch(task_id, executor, &mut sql, &mut args_after, &mut result)
+            .await?;
+        if let Some(next) = next {
+            if !next {
+                break;
+            }
+        } else {
+            return result;
+        }
+    }
+    result
+}
+
+impl dyn Executor {
+    /// get a context value by type
+    /// ```rust
+    /// use rbatis::executor::Executor;
+    /// pub struct CurrentUser(pub i64);
+    ///
+    /// fn user_id(executor: &dyn Executor) -> Option<i64> {
+    ///     executor.context_value::<CurrentUser>().map(|v| v.0)
+    /// }
+    /// ```
+    pub fn context_value<T: Any>(&self) -> Option<&T> {
+        self.context().and_then(|v| v.get::<T>())
+    }
+
+    /// acquire one connection(with the context) if this executor acquires a connection for every
+    /// call(`RBatis`,`ContextExecutor`), so the sqls of it have the same executor id.
+    /// `None` if this executor is a connection or transaction
+    pub async fn acquire_pinned(&self) -> Result<Option<RBatisConnExecutor>, Error> {
+        let name = self.name();
+        if name != std::any::type_name::<RBatis>()
+            && name != std::any::type_name::<ContextExecutor>()
+        {
+            return Ok(None);
+        }
+        let conn = self.rb_ref().acquire().await?;
+        Ok(Some(match self.context() {
+            Some(context) => conn.with_context(context.clone()),
+            None => conn,
+        }))
+    }
+}
+
+/// typed values attached to an executor, every intercept can read them by `executor.context()`.
+/// the context of RBatisConnExecutor will be moved into the RBatisTxExecutor it begins.
+/// ```rust
+/// use rbatis::executor::Context;
+/// pub struct CurrentUser(pub i64);
+/// pub struct RequestId(pub String);
+///
+/// let ctx = Context::new()
+///     .with(CurrentUser(1))
+///     .with(RequestId("abc".to_string()));
+/// assert_eq!(ctx.get::<CurrentUser>().map(|v| v.0), Some(1));
+/// ```
+#[derive(Clone, Default)]
+pub struct Context {
+    values: HashMap<TypeId, Arc<dyn Any + Send + Sync>>,
+}
+
+impl Context {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// insert a value and return self
+    pub fn with<T: Any + Send + Sync>(mut self, v: T) -> Self {
+        self.insert(v);
+        self
+    }
+
+    /// insert a value, the old value of the same type will be replaced
+    pub fn insert<T: Any + Send + Sync>(&mut self, v: T) {
+        self.values.insert(TypeId::of::<T>(), Arc::new(v));
+    }
+
+    pub fn get<T: Any>(&self) -> Option<&T> {
+        self.values
+            .get(&TypeId::of::<T>())
+            .and_then(|v| v.downcast_ref::<T>())
+    }
+
+    pub fn contains<T: Any>(&self) -> bool {
+        self.values.contains_key(&TypeId::of::<T>())
+    }
+
+    pub fn remove<T: Any>(&mut self) -> bool {
+        self.values.remove(&TypeId::of::<T>()).is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+impl Debug for Context {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Context")
+            .field("len", &self.values.len())
+            .finish()
+    }
 }
 
 pub trait RBatisRef: Any + Send + Sync {
@@ -46,6 +311,7 @@ pub struct RBatisConnExecutor {
     pub id: i64,
     pub rb: RBatis,
     pub conn: Mutex<Box<dyn Connection>>,
+    pub context: Context,
 }
 
 impl RBatisConnExecutor {
@@ -54,8 +320,15 @@ impl RBatisConnExecutor {
             id: id,
             conn: Mutex::new(conn),
             rb: rb,
+            context: Context::new(),
         }
     }
+
+    /// set context values, intercepts can read them by `executor.context()`
+    pub fn with_context(mut self, context: Context) -> Self {
+        self.context = context;
+        self
+    }
 }
 
 impl Debug for RBatisConnExecutor {
@@ -63,6 +336,7 @@ impl Debug for RBatisConnExecutor {
         f.debug_struct("RBatisTxExecutor")
             .field("id", &self.id)
             .field("rb", &self.rb)
+            .field("context", &self.context)
             .finish()
     }
 }
@@ -82,8 +356,8 @@ impl RBatisConnExecutor {
     where
         T: DeserializeOwned,
     {
-        let v = Executor::query(self, sql, args).await?;
-        Ok(decode(v)?)
+        let v = Executor::query_rows(self, sql, args).await?;
+        decode_rows(&v)
     }
 }
 
@@ -92,6 +366,10 @@ impl Executor for RBatisConnExecutor {
         self.id
     }
 
+    fn context(&self) -> Option<&Context> {
+        Some(&self.context)
+    }
+
     fn exec(&self, sql: &str, mut args: Vec<Value>) -> BoxFuture<'_, Result<ExecResult, Error>> {
         let mut sql = sql.to_string();
         Box::pin(async move {
@@ -116,6 +394,7 @@ impl Executor for RBatisConnExecutor {
                 }
             }
             let mut args_after = args.clone();
+            encode_json_args(&mut args);
             let mut result = self.conn.lock().await.exec(&sql, args).await;
             for item in self.rb_ref().intercepts.iter() {
                 let next = item
@@ -139,6 +418,26 @@ impl Executor for RBatisConnExecutor {
         })
     }
 
+    fn exec_batch(
+        &self,
+        sql: &str,
+        args: Vec<Vec<Value>>,
+    ) -> BoxFuture<'_, Result<BatchExecResult, Error>> {
+        let sql = sql.to_string();
+        Box::pin(async move {
+            let rb_task_id = self.rb.task_id_generator.generate();
+            exec_batch_conn(self, rb_task_id, &self.conn, sql, args).await
+        })
+    }
+
+    fn query_rows(&self, sql: &str, args: Vec<Value>) -> BoxFuture<'_, Result<Rows, Error>> {
+        let sql = sql.to_string();
+        Box::pin(async move {
+            let rb_task_id = self.rb.task_id_generator.generate();
+            query_rows_conn(self, rb_task_id, &self.conn, sql, args).await
+        })
+    }
+
     fn query(&self, sql: &str, mut args: Vec<Value>) -> BoxFuture<'_, Result<Value, Error>> {
         let mut sql = sql.to_string();
         Box::pin(async move {
@@ -164,6 +463,7 @@ impl Executor for RBatisConnExecutor {
             }
             let mut conn = self.conn.lock().await;
             let mut args_after = args.clone();
+            encode_json_args(&mut args);
             let mut result = conn.get_values(&sql, args).await;
             for item in self.rb_ref().intercepts.iter() {
                 let next = item
@@ -199,11 +499,10 @@ impl RBatisConnExecutor {
         Box::pin(async move {
             let mut conn = self.conn.into_inner();
             conn.begin().await?;
-            Ok(RBatisTxExecutor::new(
-                self.rb.task_id_generator.generate(),
-                self.rb,
-                conn,
-            ))
+            Ok(
+                RBatisTxExecutor::new(self.rb.task_id_generator.generate(), self.rb, conn)
+                    .with_context(self.context),
+            )
         })
     }
 
@@ -223,6 +522,7 @@ pub struct RBatisTxExecutor {
     /// if tx call .commit() or .rollback() done = true.
     /// if tx not call .commit() or .rollback() done = false
     pub done: bool,
+    pub context: Context,
 }
 
 impl Debug for RBatisTxExecutor {
@@ -231,6 +531,7 @@ impl Debug for RBatisTxExecutor {
             .field("tx_id", &self.tx_id)
             .field("rb", &self.rb)
             .field("done", &self.done)
+            .field("context", &self.context)
             .finish()
     }
 }
@@ -242,9 +543,16 @@ impl<'a> RBatisTxExecutor {
             conn: Mutex::new(conn),
             rb: rb,
             done: false,
+            context: Context::new(),
         }
     }
 
+    /// set context values, intercepts can read them by `executor.context()`
+    pub fn with_context(mut self, context: Context) -> Self {
+        self.context = context;
+        self
+    }
+
     /// exec
     pub async fn exec(&self, sql: &str, args: Vec<Value>) -> Result<ExecResult, Error> {
         let v = Executor::exec(self, sql, args).await?;
@@ -260,8 +568,8 @@ impl<'a> RBatisTxExecutor {
     where
         T: DeserializeOwned,
     {
-        let v = Executor::query(self, sql, args).await?;
-        Ok(decode(v)?)
+        let v = Executor::query_rows(self, sql, args).await?;
+        decode_rows(&v)
     }
 
     pub fn begin(self) -> BoxFuture<'static, Result<Self, Error>> {
@@ -293,6 +601,14 @@ impl Executor for RBatisTxExecutor {
         self.tx_id
     }
 
+    fn context(&self) -> Option<&Context> {
+        Some(&self.context)
+    }
+
+    fn in_transaction(&self) -> bool {
+        true
+    }
+
     fn exec(&self, sql: &str, mut args: Vec<Value>) -> BoxFuture<'_, Result<ExecResult, Error>> {
         let mut sql = sql.to_string();
         Box::pin(async move {
@@ -316,6 +632,7 @@ impl Executor for RBatisTxExecutor {
                 }
             }
             let mut args_after = args.clone();
+            encode_json_args(&mut args);
             let mut result = self.conn.lock().await.exec(&sql, args).await;
             for item in self.rb_ref().intercepts.iter() {
                 let next = item
@@ -339,6 +656,20 @@ impl Executor for RBatisTxExecutor {
         })
     }
 
+    fn exec_batch(
+        &self,
+        sql: &str,
+        args: Vec<Vec<Value>>,
+    ) -> BoxFuture<'_, Result<BatchExecResult, Error>> {
+        let sql = sql.to_string();
+        Box::pin(async move { exec_batch_conn(self, self.tx_id, &self.conn, sql, args).await })
+    }
+
+    fn query_rows(&self, sql: &str, args: Vec<Value>) -> BoxFuture<'_, Result<Rows, Error>> {
+        let sql = sql.to_string();
+        Box::pin(async move { query_rows_conn(self, self.tx_id, &self.conn, sql, args).await })
+    }
+
     fn query(&self, sql: &str, mut args: Vec<Value>) -> BoxFuture<'_, Result<Value, Error>> {
         let mut sql = sql.to_string();
         Box::pin(async move {
@@ -354,7 +685,7 @@ impl Executor for RBatisTxExecutor {
                     )
                     .await?;
                 if let Some(next) = next {
-                    if next {
+                    if !next {
                         break;
                     }
                 } else {
@@ -363,6 +694,7 @@ impl Executor for RBatisTxExecutor {
             }
             let mut conn = self.conn.lock().await;
             let mut args_after = args.clone();
+            encode_json_args(&mut args);
             let conn = conn.get_values(&sql, args);
             let mut result = conn.await;
             for item in self.rb_ref().intercepts.iter() {
@@ -512,6 +844,14 @@ impl Executor for RBatisTxExecutorGuard {
         }
     }
 
+    fn context(&self) -> Option<&Context> {
+        self.tx.as_ref().map(|v| &v.context)
+    }
+
+    fn in_transaction(&self) -> bool {
+        self.tx.is_some()
+    }
+
     fn exec(&self, sql: &str, args: Vec<Value>) -> BoxFuture<'_, Result<ExecResult, Error>> {
         let sql = sql.to_string();
         Box::pin(async move {
@@ -522,6 +862,20 @@ impl Executor for RBatisTxExecutorGuard {
         })
     }
 
+    fn exec_batch(
+        &self,
+        sql: &str,
+        args: Vec<Vec<Value>>,
+    ) -> BoxFuture<'_, Result<BatchExecResult, Error>> {
+        let sql = sql.to_string();
+        Box::pin(async move {
+            match self.tx.as_ref() {
+                None => Err(Error::from("the tx is done!")),
+                Some(tx) => tx.exec_batch(&sql, args).await,
+            }
+        })
+    }
+
     fn query(&self, sql: &str, args: Vec<Value>) -> BoxFuture<'_, Result<Value, Error>> {
         let sql = sql.to_string();
         Box::pin(async move {
@@ -531,6 +885,16 @@ impl Executor for RBatisTxExecutorGuard {
             }
         })
     }
+
+    fn query_rows(&self, sql: &str, args: Vec<Value>) -> BoxFuture<'_, Result<Rows, Error>> {
+        let sql = sql.to_string();
+        Box::pin(async move {
+            match self.tx.as_ref() {
+                None => Err(Error::from("the tx is done!")),
+                Some(tx) => tx.query_rows(&sql, args).await,
+            }
+        })
+    }
 }
 
 impl RBatis {
@@ -540,6 +904,16 @@ impl RBatis {
         conn.exec(sql, args).await
     }
 
+    /// execute one sql many times on one connection, see `Executor::exec_batch`
+    pub async fn exec_batch(
+        &self,
+        sql: &str,
+        args: Vec<Vec<Value>>,
+    ) -> Result<BatchExecResult, Error> {
+        let conn = self.acquire().await?;
+        conn.exec_batch(sql, args).await
+    }
+
     /// query raw Value
     pub async fn query(&self, sql: &str, args: Vec<Value>) -> Result<Value, Error> {
         let conn = self.acquire().await?;
@@ -553,8 +927,7 @@ impl RBatis {
         T: DeserializeOwned,
     {
         let conn = self.acquire().await?;
-        let v = conn.query(sql, args).await?;
-        Ok(decode(v)?)
+        conn.query_decode(sql, args).await
     }
 }
 
@@ -571,6 +944,18 @@ impl Executor for RBatis {
         })
     }
 
+    fn exec_batch(
+        &self,
+        sql: &str,
+        args: Vec<Vec<Value>>,
+    ) -> BoxFuture<'_, Result<BatchExecResult, Error>> {
+        let sql = sql.to_string();
+        Box::pin(async move {
+            let conn = self.acquire().await?;
+            conn.exec_batch(&sql, args).await
+        })
+    }
+
     fn query(&self, sql: &str, args: Vec<Value>) -> BoxFuture<'_, Result<Value, Error>> {
         let sql = sql.to_string();
         Box::pin(async move {
@@ -578,6 +963,113 @@ impl Executor for RBatis {
             conn.query(&sql, args).await
         })
     }
+
+    fn query_rows(&self, sql: &str, args: Vec<Value>) -> BoxFuture<'_, Result<Rows, Error>> {
+        let sql = sql.to_string();
+        Box::pin(async move {
+            let conn = self.acquire().await?;
+            conn.query_rows(&sql, args).await
+        })
+    }
+}
+
+/// an executor carry context values, every connection it acquires will have the context.
+/// create by `rb.with_context(ctx)`
+#[derive(Debug, Clone)]
+pub struct ContextExecutor {
+    pub rb: RBatis,
+    pub context: Context,
+}
+
+impl ContextExecutor {
+    pub fn new(rb: RBatis, context: Context) -> Self {
+        Self { rb, context }
+    }
+
+    /// get an DataBase Connection with context
+    pub async fn acquire(&self) -> Result<RBatisConnExecutor, Error> {
+        let conn = self.rb.acquire().await?;
+        Ok(conn.with_context(self.context.clone()))
+    }
+
+    /// get an DataBase Connection with context,and call begin method
+    pub async fn acquire_begin(&self) -> Result<RBatisTxExecutor, Error> {
+        let conn = self.acquire().await?;
+        conn.begin().await
+    }
+
+    /// exec sql
+    pub async fn exec(&self, sql: &str, args: Vec<Value>) -> Result<ExecResult, Error> {
+        let conn = self.acquire().await?;
+        conn.exec(sql, args).await
+    }
+
+    /// query raw Value
+    pub async fn query(&self, sql: &str, args: Vec<Value>) -> Result<Value, Error> {
+        let conn = self.acquire().await?;
+        conn.query(sql, args).await
+    }
+
+    /// query and decode
+    pub async fn query_decode<T>(&self, sql: &str, args: Vec<Value>) -> Result<T, Error>
+    where
+        T: DeserializeOwned,
+    {
+        let conn = self.acquire().await?;
+        conn.query_decode(sql, args).await
+    }
+}
+
+impl RBatisRef for ContextExecutor {
+    fn rb_ref(&self) -> &RBatis {
+        &self.rb
+    }
+}
+
+impl Executor for ContextExecutor {
+    fn id(&self) -> i64 {
+        self.rb.id()
+    }
+
+    fn exec(&self, sql: &str, args: Vec<Value>) -> BoxFuture<'_, Result<ExecResult, Error>> {
+        let sql = sql.to_string();
+        Box::pin(async move {
+            let conn = self.acquire().await?;
+            conn.exec(&sql, args).await
+        })
+    }
+
+    fn exec_batch(
+        &self,
+        sql: &str,
+        args: Vec<Vec<Value>>,
+    ) -> BoxFuture<'_, Result<BatchExecResult, Error>> {
+        let sql = sql.to_string();
+        Box::pin(async move {
+            let conn = self.acquire().await?;
+            conn.exec_batch(&sql, args).await
+        })
+    }
+
+    fn query(&self, sql: &str, args: Vec<Value>) -> BoxFuture<'_, Result<Value, Error>> {
+        let sql = sql.to_string();
+        Box::pin(async move {
+            let conn = self.acquire().await?;
+            conn.query(&sql, args).await
+        })
+    }
+
+    fn query_rows(&self, sql: &str, args: Vec<Value>) -> BoxFuture<'_, Result<Rows, Error>> {
+        let sql = sql.to_string();
+        Box::pin(async move {
+            let conn = self.acquire().await?;
+            conn.query_rows(&sql, args).await
+        })
+    }
+
+    fn context(&self) -> Option<&Context> {
+        Some(&self.context)
+    }
 }
 
 // impl RBatisRef for &RBatis {
@@ -0,0 +1,76 @@
+/// the limit of one batch statement, used by `insert_batch`,`select_in_column`,`delete_in_column`...
+///
+/// a batch is split by the caller's `batch_size`, the driver's maximum bind parameter count
+/// and the maximum sql length.
+/// ```rust
+/// let mut rb = rbatis::RBatis::new();
+/// //old sqlite(< 3.32) only support 999 parameters
+/// rb.batch_limit.max_params = Some(999);
+/// rb.batch_limit.max_sql_len = Some(1024 * 1024);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BatchLimit {
+    /// max bind parameters of one statement, None means the driver default, see `max_bind_params`
+    pub max_params: Option<usize>,
+    /// max sql length(bytes) of one statement, None means no limit
+    pub max_sql_len: Option<usize>,
+}
+
+/// the default max bind parameters of a driver
+pub fn max_bind_params(driver_type: &str) -> usize {
+    match driver_type {
+        "sqlite" => 32766,
+        //2100 includes the parameters of the rpc call
+        "mssql" => 2098,
+        _ => 65535,
+    }
+}
+
+impl BatchLimit {
+    pub fn max_params(&self, driver_type: &str) -> usize {
+        self.max_params
+            .unwrap_or_else(|| max_bind_params(driver_type))
+    }
+
+    /// split `total` items into ordered (offset, limit) ranges.
+    /// * batch_size: max items of one range, 0 means no limit
+    /// * item_params: bind parameters of one item
+    /// * base_sql_len: sql length without items
+    /// * item_sql_len: sql length of one item
+    pub fn ranges(
+        &self,
+        driver_type: &str,
+        total: usize,
+        batch_size: u64,
+        item_params: usize,
+        base_sql_len: usize,
+        item_sql_len: usize,
+    ) -> Vec<(usize, usize)> {
+        let mut size = if batch_size == 0 {
+            usize::MAX
+        } else {
+            batch_size as usize
+        };
+        if let Some(v) = self.max_params(driver_type).checked_div(item_params) {
+            size = size.min(v);
+        }
+        if let Some(max_sql_len) = self.max_sql_len {
+            if let Some(v) = max_sql_len
+                .saturating_sub(base_sql_len)
+                .checked_div(item_sql_len)
+            {
+                size = size.min(v);
+            }
+        }
+        //at least one item, the database will report the error
+        let size = size.max(1);
+        let mut result = Vec::with_capacity(total / size + 1);
+        let mut offset = 0;
+        while offset < total {
+            let limit = (offset + size).min(total);
+            result.push((offset, limit));
+            offset = limit;
+        }
+        result
+    }
+}
@@ -0,0 +1,341 @@
+use crate::executor::{Executor, RBatisConnExecutor, RBatisTxExecutor};
+use crate::naming::NamingStrategy;
+use crate::Error;
+use futures::{Stream, StreamExt};
+use rbs::Value;
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+
+/// the option of `RBatis::bulk_insert`
+#[derive(Clone)]
+pub struct BulkInsertOption {
+    /// max rows of one statement, 0 means only limited by bind parameters and `max_packet_size`
+    pub batch_size: usize,
+    /// max bytes(sql and args) of one statement, 0 means the `max_allowed_packet` of the server
+    /// for mysql(read by `select @@max_allowed_packet` on the connection) and 4MB for other drivers
+    pub max_packet_size: usize,
+    /// run all statements in one transaction, rollback if any statement fails
+    pub transaction: bool,
+    /// called after every statement, the arg is the total inserted rows(the sum of `rows_affected`)
+    pub on_progress: Option<Arc<dyn Fn(u64) + Send + Sync>>,
+}
+
+impl Default for BulkInsertOption {
+    fn default() -> Self {
+        Self {
+            batch_size: 0,
+            max_packet_size: 0,
+            transaction: true,
+            on_progress: None,
+        }
+    }
+}
+
+impl Debug for BulkInsertOption {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BulkInsertOption")
+            .field("batch_size", &self.batch_size)
+            .field("max_packet_size", &self.max_packet_size)
+            .field("transaction", &self.transaction)
+            .field("on_progress", &self.on_progress.is_some())
+            .finish()
+    }
+}
+
+impl BulkInsertOption {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// set the progress callback
+    pub fn on_progress<F: Fn(u64) + Send + Sync + 'static>(mut self, f: F) -> Self {
+        self.on_progress = Some(Arc::new(f));
+        self
+    }
+}
+
+/// insert rows by a `BulkWriter` on a new connection(with the context of executor),
+/// `to_row` converts a row to a map value of fields
+pub async fn bulk_insert<T, I, F>(
+    executor: &dyn Executor,
+    table_name: &str,
+    rows: I,
+    option: BulkInsertOption,
+    to_row: F,
+) -> Result<u64, Error>
+where
+    I: IntoIterator<Item = T>,
+    F: Fn(T) -> Result<Value, Error>,
+{
+    let mut writer = BulkWriter::acquire(executor, table_name, option).await?;
+    for row in rows {
+        if let Err(e) = writer.push_with(row, &to_row).await {
+            return Err(writer.rollback(e).await);
+        }
+    }
+    writer.finish().await
+}
+
+/// same as `bulk_insert`, but the rows are read from a `Stream`
+pub async fn bulk_insert_stream<T, S, F>(
+    executor: &dyn Executor,
+    table_name: &str,
+    mut rows: S,
+    option: BulkInsertOption,
+    to_row: F,
+) -> Result<u64, Error>
+where
+    S: Stream<Item = T> + Unpin,
+    F: Fn(T) -> Result<Value, Error>,
+{
+    let mut writer = BulkWriter::acquire(executor, table_name, option).await?;
+    while let Some(row) = rows.next().await {
+        if let Err(e) = writer.push_with(row, &to_row).await {
+            return Err(writer.rollback(e).await);
+        }
+    }
+    writer.finish().await
+}
+
+/// write rows with multi-row `insert into table (columns) values (?,?),(?,?)` on one connection.
+///
+/// * the statements run through the intercepts of the connection(encrypt, guard, log...)
+/// * the fields of rows are mapped to columns by the table naming
+/// * the columns are the keys of the first row, so every full statement has the same sql
+///   and the prepared statement can be reused by the driver. a row with other keys is an error
+/// * a statement is flushed when it reaches `batch_size`, the bind parameter limit of the driver
+///   or `max_packet_size`
+/// * the total is the sum of `rows_affected`, so the rows skipped by the database are not counted
+///
+/// postgres `COPY FROM STDIN` and mssql bulk copy are not exposed by the rbdc `Connection`,
+/// so these drivers use the multi-row insert too.
+pub struct BulkWriter {
+    conn: Option<RBatisConnExecutor>,
+    tx: Option<RBatisTxExecutor>,
+    table_name: String,
+    naming: Arc<NamingStrategy>,
+    option: BulkInsertOption,
+    max_params: usize,
+    max_packet_size: usize,
+    columns: Vec<String>,
+    args: Vec<Value>,
+    rows: usize,
+    bytes: usize,
+    total: u64,
+}
+
+impl BulkWriter {
+    pub fn new(conn: RBatisConnExecutor, table_name: &str, option: BulkInsertOption) -> Self {
+        let driver_type = conn.rb.driver_type().unwrap_or_default();
+        Self {
+            max_params: conn.rb.batch_limit.max_params(driver_type),
+            max_packet_size: option.max_packet_size,
+            naming: conn.rb.naming_of(table_name),
+            conn: Some(conn),
+            tx: None,
+            table_name: table_name.to_string(),
+            option,
+            columns: vec![],
+            args: vec![],
+            rows: 0,
+            bytes: 0,
+            total: 0,
+        }
+    }
+
+    /// create a writer on a new connection with the context of executor
+    pub async fn acquire(
+        executor: &dyn Executor,
+        table_name: &str,
+        option: BulkInsertOption,
+    ) -> Result<Self, Error> {
+        let conn = executor.rb_ref().acquire().await?;
+        let conn = match executor.context() {
+            Some(context) => conn.with_context(context.clone()),
+            None => conn,
+        };
+        Ok(Self::new(conn, table_name, option))
+    }
+
+    async fn push_with<T, F: Fn(T) -> Result<Value, Error>>(
+        &mut self,
+        row: T,
+        to_row: &F,
+    ) -> Result<(), Error> {
+        self.push(to_row(row)?).await
+    }
+
+    /// add a row(a map value of fields), flush if the statement is full
+    pub async fn push(&mut self, row: Value) -> Result<(), Error> {
+        let row = self.naming.to_columns(row);
+        if self.columns.is_empty() {
+            self.init_columns(&row)?;
+            if self.max_packet_size == 0 {
+                self.max_packet_size = self.server_packet_size().await?;
+            }
+        } else {
+            self.check_columns(&row)?;
+        }
+        let mut bytes = self.columns.len() * 2 + 2;
+        let mut values = Vec::with_capacity(self.columns.len());
+        for column in &self.columns {
+            let v = row[column.as_str()].clone();
+            bytes += value_size(&v);
+            values.push(v);
+        }
+        if self.rows > 0 && self.bytes + bytes > self.max_packet_size {
+            self.flush().await?;
+        }
+        self.args.extend(values);
+        self.rows += 1;
+        self.bytes += bytes;
+        let max_rows = (self.max_params / self.columns.len()).max(1);
+        if self.rows >= max_rows
+            || (self.option.batch_size != 0 && self.rows >= self.option.batch_size)
+        {
+            self.flush().await?;
+        }
+        Ok(())
+    }
+
+    /// flush the rest rows and commit, return the total inserted rows.
+    /// rollback if the flush or commit fails
+    pub async fn finish(mut self) -> Result<u64, Error> {
+        let r = self.flush().await;
+        if let Err(e) = r {
+            return Err(self.rollback(e).await);
+        }
+        if let Some(tx) = self.tx.as_mut() {
+            if let Err(e) = tx.commit().await {
+                return Err(self.rollback(e).await);
+            }
+        }
+        Ok(self.total)
+    }
+
+    /// rollback the transaction and return the error
+    pub async fn rollback(&mut self, e: Error) -> Error {
+        if let Some(tx) = self.tx.as_mut() {
+            if !tx.done {
+                tx.done = true;
+                if let Err(rollback_err) = tx.rollback().await {
+                    return Error::from(format!("{}, rollback fail: {}", e, rollback_err));
+                }
+            }
+        }
+        e
+    }
+
+    fn init_columns(&mut self, row: &Value) -> Result<(), Error> {
+        for (column, _) in row {
+            let column = column.as_str().unwrap_or_default();
+            if column.is_empty()
+                || !column
+                    .bytes()
+                    .all(|v| v.is_ascii_alphanumeric() || v == b'_')
+            {
+                return Err(Error::from(format!(
+                    "[rb] bulk_insert invalid column: `{}`",
+                    column
+                )));
+            }
+            self.columns.push(column.to_string());
+        }
+        if self.columns.is_empty() {
+            return Err(Error::from(
+                "[rb] bulk_insert row must be a not empty struct/map",
+            ));
+        }
+        self.bytes = self.base_bytes();
+        Ok(())
+    }
+
+    /// the keys of every row must be the columns of the first row
+    fn check_columns(&self, row: &Value) -> Result<(), Error> {
+        let same = row.len() == self.columns.len()
+            && row
+                .into_iter()
+                .all(|(k, _)| self.columns.iter().any(|c| k.as_str() == Some(c.as_str())));
+        if !same {
+            let keys: Vec<String> = row
+                .into_iter()
+                .map(|(k, _)| k.as_str().unwrap_or_default().to_string())
+                .collect();
+            return Err(Error::from(format!(
+                "[rb] bulk_insert the columns of row `{}` are not the columns of the first row `{}`",
+                keys.join(","),
+                self.columns.join(",")
+            )));
+        }
+        Ok(())
+    }
+
+    /// the `max_allowed_packet` of mysql, 4MB for other drivers
+    async fn server_packet_size(&self) -> Result<usize, Error> {
+        let conn = match (&self.tx, &self.conn) {
+            (Some(tx), _) => tx as &dyn Executor,
+            (None, Some(conn)) => conn as &dyn Executor,
+            (None, None) => return Err(Error::from("[rb] bulk_insert connection is taken")),
+        };
+        if conn.driver_type()? != "mysql" {
+            return Ok(4 * 1024 * 1024);
+        }
+        let v = conn.query("select @@max_allowed_packet", vec![]).await?;
+        let size: usize = crate::decode(v)?;
+        //keep some bytes for the protocol
+        Ok(size.saturating_sub(1024))
+    }
+
+    /// the sql bytes without rows
+    fn base_bytes(&self) -> usize {
+        32 + self.table_name.len() + self.columns.iter().map(|v| v.len() + 1).sum::<usize>()
+    }
+
+    async fn flush(&mut self) -> Result<(), Error> {
+        if self.rows == 0 {
+            return Ok(());
+        }
+        if self.option.transaction && self.tx.is_none() {
+            let conn = self
+                .conn
+                .take()
+                .ok_or_else(|| Error::from("[rb] bulk_insert connection is taken"))?;
+            self.tx = Some(conn.begin().await?);
+        }
+        let row_sql = format!("({})", vec!["?"; self.columns.len()].join(","));
+        let sql = format!(
+            "insert into {} ({}) values {}",
+            self.table_name,
+            self.columns.join(","),
+            vec![row_sql.as_str(); self.rows].join(",")
+        );
+        let args = std::mem::take(&mut self.args);
+        let r = match (&self.tx, &self.conn) {
+            (Some(tx), _) => tx.exec(&sql, args).await?,
+            (None, Some(conn)) => conn.exec(&sql, args).await?,
+            (None, None) => return Err(Error::from("[rb] bulk_insert connection is taken")),
+        };
+        self.total += r.rows_affected;
+        self.rows = 0;
+        self.bytes = self.base_bytes();
+        if let Some(f) = &self.option.on_progress {
+            f(self.total);
+        }
+        Ok(())
+    }
+}
+
+/// the bytes of a arg
+fn value_size(v: &Value) -> usize {
+    match v {
+        Value::String(v) => v.len(),
+        Value::Binary(v) => v.len(),
+        Value::Ext(_, v) => value_size(v),
+        Value::Array(v) => v.iter().map(value_size).sum(),
+        Value::Map(v) => v
+            .into_iter()
+            .map(|(k, v)| value_size(k) + value_size(v))
+            .sum(),
+        _ => 8,
+    }
+}
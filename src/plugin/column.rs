@@ -0,0 +1,152 @@
+use serde::{Serialize, Serializer};
+use std::fmt::{Debug, Display, Formatter};
+use std::marker::PhantomData;
+
+/// a typed column of table `T`, the value type of this column is `V`.
+/// usually generated by `#[derive(rbatis::Columns)]`
+/// ```rust
+/// #[derive(Clone, Debug, serde::Serialize, serde::Deserialize, rbatis::Columns)]
+/// pub struct Activity {
+///     pub id: Option<String>,
+///     pub status: Option<i32>,
+/// }
+/// assert_eq!(Activity::COL_ID.name(), "id");
+/// assert_eq!(Activity::COLUMNS, &["id", "status"]);
+/// //Activity::COL_STATUS: Column<Activity, i32>
+/// let w = rbatis::wrapper::QueryWrapper::new().eq(Activity::COL_STATUS, 1);
+/// ```
+/// the value type is checked at compile time
+/// ```compile_fail
+/// #[derive(Clone, Debug, serde::Serialize, serde::Deserialize, rbatis::Columns)]
+/// pub struct Activity {
+///     pub status: Option<i32>,
+/// }
+/// //a &str is not a i32 value
+/// let w = rbatis::wrapper::QueryWrapper::new().eq(Activity::COL_STATUS, "1");
+/// ```
+pub struct Column<T, V> {
+    name: &'static str,
+    p: PhantomData<fn() -> (T, V)>,
+}
+
+impl<T, V> Column<T, V> {
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            p: PhantomData,
+        }
+    }
+
+    pub const fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+impl<T, V> Clone for Column<T, V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, V> Copy for Column<T, V> {}
+
+impl<T, V> Debug for Column<T, V> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Column({})", self.name)
+    }
+}
+
+impl<T, V> Display for Column<T, V> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name)
+    }
+}
+
+/// serialize as column name, so it can be used by `${column}` of py_sql
+impl<T, V> Serialize for Column<T, V> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.name)
+    }
+}
+
+/// anything can be used as a column name
+pub trait ColumnName: Serialize {
+    fn column_name(&self) -> &str;
+}
+
+impl ColumnName for &str {
+    fn column_name(&self) -> &str {
+        self
+    }
+}
+
+impl ColumnName for String {
+    fn column_name(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl ColumnName for &String {
+    fn column_name(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<T, V> ColumnName for Column<T, V> {
+    fn column_name(&self) -> &str {
+        self.name
+    }
+}
+
+/// a column accept value `V`.
+/// a raw `&str` column accept any value, a `Column<T, F>` only accept `V: Into<F>`
+pub trait ColumnValue<V>: ColumnName {}
+
+impl<V> ColumnValue<V> for &str {}
+
+impl<V> ColumnValue<V> for String {}
+
+impl<V> ColumnValue<V> for &String {}
+
+impl<T, F, V: Into<F>> ColumnValue<V> for Column<T, F> {}
+
+/// a column of table `T` accept value `V`, used by crud! methods
+pub trait TableColumn<T, V>: ColumnValue<V> {}
+
+impl<T, V> TableColumn<T, V> for &str {}
+
+impl<T, V> TableColumn<T, V> for String {}
+
+impl<T, V> TableColumn<T, V> for &String {}
+
+impl<T, F, V: Into<F>> TableColumn<T, V> for Column<T, F> {}
+
+/// a column of table `T` with any value type, used by crud! methods without a column value.
+/// a `Column` of another table is rejected
+/// ```compile_fail
+/// #[derive(Clone, Debug, serde::Serialize, serde::Deserialize, rbatis::Columns)]
+/// pub struct Activity {
+///     pub id: Option<String>,
+/// }
+/// #[derive(Clone, Debug, serde::Serialize, serde::Deserialize, rbatis::Columns)]
+/// pub struct User {
+///     pub id: Option<String>,
+/// }
+/// rbatis::crud!(Activity{});
+///
+/// async fn update(rb: &rbatis::RBatis, table: &Activity) {
+///     let _ = Activity::update_by_column(rb, table, User::COL_ID).await;
+/// }
+/// ```
+pub trait TableColumnName<T>: ColumnName {}
+
+impl<T> TableColumnName<T> for &str {}
+
+impl<T> TableColumnName<T> for String {}
+
+impl<T> TableColumnName<T> for &String {}
+
+impl<T, F> TableColumnName<T> for Column<T, F> {}
@@ -0,0 +1,185 @@
+use crate::table_sync::ColumnMapper;
+use crate::Error;
+use rbs::Value;
+use serde::de::Error as DeError;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cell::Cell;
+use std::fmt::{Display, Formatter};
+
+/// the newtype name(and the `Value::Ext` type) of a `DbEnum` serialized by `to_schema_value`,
+/// the value is the array of all variant values
+pub const DB_ENUM_EXT: &str = "DbEnum";
+
+thread_local! {
+    static SCHEMA: Cell<bool> = const { Cell::new(false) };
+}
+
+/// the db value of an enum variant, `#[db(int = 1)]` or `#[db(str = "active")]`
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum DbValue {
+    Int(i64),
+    Str(&'static str),
+}
+
+impl Display for DbValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DbValue::Int(v) => write!(f, "{}", v),
+            DbValue::Str(v) => write!(f, "\"{}\"", v),
+        }
+    }
+}
+
+/// an enum stored as the int or string column, usually generated by `#[derive(rbatis::DbEnum)]`.
+/// the derive also impl `serde::Serialize`/`serde::Deserialize` by the db values,
+/// so `rbs::to_value` gives the scalar and decoding an unknown value is an error.
+///```rust
+/// #[derive(Clone, Copy, Debug, PartialEq, rbatis::DbEnum)]
+/// pub enum Status {
+///     #[db(int = 1)]
+///     Enabled,
+///     #[db(int = 0)]
+///     Disabled,
+/// }
+/// #[derive(Clone, Copy, Debug, PartialEq, rbatis::DbEnum)]
+/// pub enum Level {
+///     #[db(str = "low")]
+///     Low,
+///     #[db(str = "high")]
+///     High,
+/// }
+/// assert_eq!(rbs::to_value!(Status::Enabled), rbs::Value::I32(1));
+/// assert_eq!(rbs::to_value!(Level::High), rbs::Value::String("high".to_string()));
+/// let level: Level = rbs::from_value(rbs::Value::String("low".to_string())).unwrap();
+/// assert_eq!(level, Level::Low);
+/// assert!(rbs::from_value::<Status>(rbs::Value::I32(2)).is_err());
+/// ```
+pub trait DbEnum: Sized + 'static {
+    /// the enum name in the errors
+    const NAME: &'static str;
+    /// the db values of the variants, in the declaration order
+    const VALUES: &'static [DbValue];
+
+    /// the index of the variant in `VALUES`
+    fn index(&self) -> usize;
+
+    /// the variant at `index` of `VALUES`
+    fn from_index(index: usize) -> Option<Self>;
+
+    fn db_value(&self) -> DbValue {
+        Self::VALUES[self.index()]
+    }
+
+    /// I32(I64 if a value is out of i32) or String
+    fn to_value(&self) -> Value {
+        match self.db_value() {
+            DbValue::Int(v) => {
+                if is_i64(Self::VALUES) {
+                    Value::I64(v)
+                } else {
+                    Value::I32(v as i32)
+                }
+            }
+            DbValue::Str(v) => Value::String(v.to_string()),
+        }
+    }
+
+    /// decode the column value, accept the number string of the int enum
+    fn from_value(v: &Value) -> Result<Self, Error> {
+        let v = match v {
+            Value::Ext(_, v) => v,
+            v => v,
+        };
+        let found = Self::VALUES.iter().position(|db| match db {
+            DbValue::Int(i) => match v {
+                Value::I32(_) | Value::I64(_) | Value::U32(_) => v.as_i64() == Some(*i),
+                Value::U64(u) => i64::try_from(*u).ok() == Some(*i),
+                Value::String(s) => s.trim().parse::<i64>().ok() == Some(*i),
+                _ => false,
+            },
+            DbValue::Str(s) => v.as_str() == Some(*s),
+        });
+        found.and_then(Self::from_index).ok_or_else(|| {
+            Error::from(format!(
+                "[rb] unknown {} value: {}, expected one of {}",
+                Self::NAME,
+                v,
+                Self::VALUES
+                    .iter()
+                    .map(|v| v.to_string())
+                    .collect::<Vec<_>>()
+                    .join(",")
+            ))
+        })
+    }
+
+    /// the values of all variants
+    fn values() -> Vec<Value> {
+        (0..Self::VALUES.len())
+            .filter_map(Self::from_index)
+            .map(|v| v.to_value())
+            .collect()
+    }
+
+    /// the table_sync column type, for example `INT` or `VARCHAR(8)`.
+    /// `table_sync::sync_table` use it for the `DbEnum` fields
+    /// ```rust
+    /// use rbatis::db_enum::DbEnum;
+    /// use rbatis::table_sync::{MysqlTableMapper, PGTableMapper, SqliteTableMapper};
+    /// #[derive(Clone, Copy, Debug, PartialEq, rbatis::DbEnum)]
+    /// pub enum Level {
+    ///     #[db(str = "low")]
+    ///     Low,
+    ///     #[db(str = "medium")]
+    ///     Medium,
+    /// }
+    /// assert_eq!(Level::column_type(&MysqlTableMapper {}), "VARCHAR(6)");
+    /// assert_eq!(Level::column_type(&SqliteTableMapper {}), "TEXT");
+    /// #[derive(Clone, Copy, Debug, PartialEq, rbatis::DbEnum)]
+    /// pub enum Status {
+    ///     #[db(int = 1)]
+    ///     Enabled,
+    /// }
+    /// assert_eq!(Status::column_type(&PGTableMapper {}), "INT4");
+    /// ```
+    fn column_type(mapper: &dyn ColumnMapper) -> String {
+        mapper.get_enum_column_type(&Self::values())
+    }
+}
+
+fn is_i64(values: &[DbValue]) -> bool {
+    values
+        .iter()
+        .any(|v| matches!(v, DbValue::Int(i) if i32::try_from(*i).is_err()))
+}
+
+/// serialize `v` into a `Value`, the `DbEnum`s are `Ext("DbEnum", [all variant values])`
+/// instead of the scalar, so the column type can be found. used by `table_sync::sync_table`
+pub fn to_schema_value<T: Serialize + ?Sized>(v: &T) -> Result<Value, Error> {
+    struct Reset(bool);
+    impl Drop for Reset {
+        fn drop(&mut self) {
+            SCHEMA.with(|s| s.set(self.0));
+        }
+    }
+    let _reset = Reset(SCHEMA.with(|s| s.replace(true)));
+    rbs::to_value(v)
+}
+
+/// the `Serialize` of `#[derive(DbEnum)]`
+pub fn serialize<T: DbEnum, S: Serializer>(v: &T, serializer: S) -> Result<S::Ok, S::Error> {
+    if SCHEMA.with(|s| s.get()) {
+        return serializer.serialize_newtype_struct(DB_ENUM_EXT, &T::values());
+    }
+    match v.to_value() {
+        Value::I32(v) => serializer.serialize_i32(v),
+        Value::I64(v) => serializer.serialize_i64(v),
+        v => serializer.serialize_str(v.as_str().unwrap_or_default()),
+    }
+}
+
+/// the `Deserialize` of `#[derive(DbEnum)]`
+pub fn deserialize<'de, T: DbEnum, D: Deserializer<'de>>(deserializer: D) -> Result<T, D::Error> {
+    let v = Value::deserialize(deserializer)?;
+    T::from_value(&v).map_err(D::Error::custom)
+}
@@ -0,0 +1,156 @@
+use crate::executor::Executor;
+use crate::wrapper::QueryWrapper;
+use crate::Error;
+use std::marker::PhantomData;
+
+/// lifecycle hooks of a table, called by the `crud!`(impl_insert!,impl_select!,impl_update!,impl_delete!)
+/// methods if the table impl it. an error of a hook aborts the statement.
+///
+/// * `before_insert`/`before_update`: validate or normalize, they run on a clone of the argument tables
+/// * `after_load`: after the rows are decoded into the table
+/// * `before_delete`: the condition of `delete_by_column`,`delete_in_column`,`delete_by_wrapper`,`delete_by_map`
+///   (the custom sql methods of impl_delete! are not checked)
+///```rust
+/// use rbatis::executor::Executor;
+/// use rbatis::hooks::TableHooks;
+/// use rbatis::wrapper::QueryWrapper;
+/// use rbatis::Error;
+///
+/// #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+/// pub struct User {
+///     pub id: Option<i64>,
+///     pub name: Option<String>,
+///     pub status: Option<i32>,
+/// }
+/// rbatis::crud!(User{});
+///
+/// impl TableHooks for User {
+///     fn before_insert(&mut self, _executor: &dyn Executor) -> Result<(), Error> {
+///         self.name = self.name.as_ref().map(|v| v.trim().to_string());
+///         if self.status.is_none() {
+///             self.status = Some(1);
+///         }
+///         Ok(())
+///     }
+///
+///     fn before_delete(_executor: &dyn Executor, condition: &QueryWrapper) -> Result<(), Error> {
+///         if !condition.has_where() {
+///             return Err(Error::from("delete all users is not allowed"));
+///         }
+///         Ok(())
+///     }
+/// }
+/// ```
+pub trait TableHooks: Clone {
+    fn before_insert(&mut self, _executor: &dyn Executor) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn before_update(&mut self, _executor: &dyn Executor) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn after_load(&mut self, _executor: &dyn Executor) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn before_delete(_executor: &dyn Executor, _condition: &QueryWrapper) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// the hooks of table `T` called by the crud macros
+#[allow(clippy::type_complexity)]
+pub struct Hooks<T> {
+    /// clone the tables and call `before_insert`
+    pub before_insert: fn(&dyn Executor, &[T]) -> Result<Vec<T>, Error>,
+    /// clone the table and call `before_update`
+    pub before_update: fn(&dyn Executor, &T) -> Result<T, Error>,
+    pub after_load: fn(&dyn Executor, &mut [T]) -> Result<(), Error>,
+    pub before_delete: fn(&dyn Executor, &QueryWrapper) -> Result<(), Error>,
+}
+
+impl<T: TableHooks> Hooks<T> {
+    fn new() -> Self {
+        Self {
+            before_insert: |executor, tables| {
+                let mut tables = tables.to_vec();
+                for table in tables.iter_mut() {
+                    table.before_insert(executor)?;
+                }
+                Ok(tables)
+            },
+            before_update: |executor, table| {
+                let mut table = table.clone();
+                table.before_update(executor)?;
+                Ok(table)
+            },
+            after_load: |executor, tables| {
+                for table in tables.iter_mut() {
+                    table.after_load(executor)?;
+                }
+                Ok(())
+            },
+            before_delete: T::before_delete,
+        }
+    }
+}
+
+/// find the hooks of a table by autoref: `(&HooksOf::<T>::new()).hooks()` is `Some` only if `T: TableHooks`,
+/// see `table_hooks!`
+pub struct HooksOf<T>(PhantomData<T>);
+
+impl<T> HooksOf<T> {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+pub trait ViaTableHooks<T> {
+    fn hooks(&self) -> Option<Hooks<T>>;
+}
+
+impl<T: TableHooks> ViaTableHooks<T> for HooksOf<T> {
+    fn hooks(&self) -> Option<Hooks<T>> {
+        Some(Hooks::new())
+    }
+}
+
+pub trait ViaNoHooks<T> {
+    fn hooks(&self) -> Option<Hooks<T>>;
+}
+
+impl<T> ViaNoHooks<T> for &HooksOf<T> {
+    fn hooks(&self) -> Option<Hooks<T>> {
+        None
+    }
+}
+
+/// the loaded container of the select methods(`Vec<T>`,`Option<T>`)
+pub trait Loaded<T> {
+    fn tables_mut(&mut self) -> &mut [T];
+}
+
+impl<T> Loaded<T> for Vec<T> {
+    fn tables_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+impl<T> Loaded<T> for Option<T> {
+    fn tables_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+/// `Option<Hooks<$table>>`, `Some` if the table impl `TableHooks`
+#[doc(hidden)]
+#[macro_export]
+macro_rules! table_hooks {
+    ($table:ty) => {{
+        #[allow(unused_imports)]
+        use $crate::hooks::{ViaNoHooks as _, ViaTableHooks as _};
+        (&$crate::hooks::HooksOf::<$table>::new()).hooks()
+    }};
+}
@@ -1,4 +1,5 @@
-use crate::executor::Executor;
+use crate::decode::Rows;
+use crate::executor::{BatchExecResult, Executor};
 use crate::Error;
 use async_trait::async_trait;
 use rbdc::db::ExecResult;
@@ -85,4 +86,79 @@ pub trait Intercept: Send + Sync + Debug {
     ) -> Result<Option<bool>, Error> {
         Ok(Some(true))
     }
+
+    /// return true if `after` should see the rows of `Executor::query_rows` as `Value` maps,
+    /// the rows are converted only for these intercepts
+    fn wants_values(&self) -> bool {
+        false
+    }
+
+    /// called after `Executor::query_rows`(used by `query_decode`), the rows are not `Value` maps.
+    ///
+    /// default do nothing, or convert the rows to `Value` maps and call `after` if `wants_values` is true
+    async fn after_rows(
+        &self,
+        task_id: i64,
+        rb: &dyn Executor,
+        sql: &mut String,
+        args: &mut Vec<Value>,
+        result: &mut Result<Rows, Error>,
+    ) -> Result<Option<bool>, Error> {
+        if !self.wants_values() {
+            return Ok(Some(true));
+        }
+        let mut values = std::mem::replace(result, Ok(Rows::default())).map(Rows::into_values);
+        let next = self
+            .after(task_id, rb, sql, args, ResultType::Query(&mut values))
+            .await;
+        *result = values.and_then(Rows::from_values);
+        next
+    }
+
+    /// called once before `exec_batch`, args is the args of every execution.
+    ///
+    /// default call `before` with every args(the sql rewritten by the first args is used),
+    /// override it if the intercept should only run once for a batch(for example log)
+    async fn before_batch(
+        &self,
+        task_id: i64,
+        rb: &dyn Executor,
+        sql: &mut String,
+        args: &mut Vec<Vec<Value>>,
+        _result: &mut Result<BatchExecResult, Error>,
+    ) -> Result<Option<bool>, Error> {
+        let origin = sql.clone();
+        let mut next = Some(true);
+        for (idx, arg) in args.iter_mut().enumerate() {
+            let mut arg_sql = origin.clone();
+            let mut arg_result = Ok(ExecResult::default());
+            let arg_next = self
+                .before(
+                    task_id,
+                    rb,
+                    &mut arg_sql,
+                    arg,
+                    ResultType::Exec(&mut arg_result),
+                )
+                .await?;
+            if idx == 0 {
+                *sql = arg_sql;
+                next = arg_next;
+            }
+        }
+        //a batch can not return the result of one args
+        Ok(Some(next.unwrap_or(true)))
+    }
+
+    /// called once after `exec_batch`
+    async fn after_batch(
+        &self,
+        _task_id: i64,
+        _rb: &dyn Executor,
+        _sql: &mut String,
+        _args: &mut Vec<Vec<Value>>,
+        _result: &mut Result<BatchExecResult, Error>,
+    ) -> Result<Option<bool>, Error> {
+        Ok(Some(true))
+    }
 }
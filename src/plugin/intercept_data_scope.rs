@@ -0,0 +1,549 @@
+use crate::executor::Executor;
+use crate::intercept::{Intercept, ResultType};
+use crate::utils::sql_util::{tokenize, Token, TokenKind};
+use crate::Error;
+use async_trait::async_trait;
+use dark_std::sync::SyncHashMap;
+use rbdc::db::ExecResult;
+use rbs::Value;
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+
+/// data scope rule, input (executor, table alias) and return the predicate sql and args.
+/// return None means no limit.
+pub type DataScopeRule =
+    Arc<dyn Fn(&dyn Executor, &str) -> Option<(String, Vec<Value>)> + Send + Sync>;
+
+/// Row-level data permission, append the rule predicate of every table into `select` sql.
+///
+/// * the table in `from` will be limited by `where`
+/// * the table in `join ... on` will be limited by `on`
+/// * the sub queries(for example `from (select ...) t`,`where id in (select ...)`) will be limited too
+/// * the queries of `with` and the main query, and every statement split by `;` will be limited too
+/// * the count sql of PageIntercept is also a select sql, so it will be limited too
+/// * a sql which refers a table with rule but can not be limited(for example an unsupported statement)
+///   is rejected with an error
+///
+/// notice: the predicate must use `?` placeholder
+/// ```rust
+/// use std::sync::Arc;
+/// use rbatis::RBatis;
+/// use rbatis::intercept_data_scope::DataScopeIntercept;
+///
+/// pub struct CurrentUser {
+///     pub region_id: i64,
+/// }
+///
+/// let rb = RBatis::new();
+/// let scope = DataScopeIntercept::new();
+/// scope.add_rule("biz_order", |executor, alias| {
+///     //rb.with_context(Context::new().with(CurrentUser { region_id: 1 }))
+///     let user = executor.context_value::<CurrentUser>()?;
+///     Some((format!("{}.region_id = ?", alias), vec![rbs::to_value!(user.region_id)]))
+/// });
+/// rb.intercepts.push(Arc::new(scope));
+/// //select * from biz_order o where o.status = ?
+/// //=> select * from biz_order o where (o.status = ?) and o.region_id = ?
+/// ```
+pub struct DataScopeIntercept {
+    /// key: table name(lowercase)
+    pub rules: SyncHashMap<String, DataScopeRule>,
+}
+
+impl Debug for DataScopeIntercept {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let tables: Vec<&String> = self.rules.iter().map(|(k, _)| k).collect();
+        f.debug_struct("DataScopeIntercept")
+            .field("rules", &tables)
+            .finish()
+    }
+}
+
+impl Default for DataScopeIntercept {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// table in `from`/`join`
+#[derive(Debug)]
+struct TableRef {
+    name: String,
+    alias: String,
+    /// byte positions of the name and alias tokens
+    positions: Vec<usize>,
+    /// token range of join `on` condition
+    on: Option<(usize, usize)>,
+}
+
+const CLAUSE_END: [&str; 11] = [
+    "where",
+    "group",
+    "having",
+    "order",
+    "limit",
+    "offset",
+    "fetch",
+    "for",
+    "window",
+    "union",
+    "returning",
+];
+
+const JOIN_WORDS: [&str; 7] = ["join", "inner", "left", "right", "full", "cross", "natural"];
+
+impl DataScopeIntercept {
+    pub fn new() -> Self {
+        Self {
+            rules: SyncHashMap::new(),
+        }
+    }
+
+    /// add a rule for table
+    pub fn add_rule<F>(&self, table: &str, rule: F)
+    where
+        F: Fn(&dyn Executor, &str) -> Option<(String, Vec<Value>)> + Send + Sync + 'static,
+    {
+        self.rules.insert(table.to_lowercase(), Arc::new(rule));
+    }
+
+    /// rewrite select sql and args, return Err if a table with rule can not be limited
+    pub fn apply(
+        &self,
+        executor: &dyn Executor,
+        sql: &mut String,
+        args: &mut Vec<Value>,
+    ) -> Result<(), Error> {
+        if self.rules.is_empty() {
+            return Ok(());
+        }
+        let tokens: Vec<Token> = tokenize(sql)
+            .into_iter()
+            .filter(|v| v.kind != TokenKind::Comment)
+            .collect();
+        let mut scope = Scope::default();
+        for statement in tokens.split(|v| v.is_symbol(";")) {
+            self.scope_query(executor, statement, &mut scope)?;
+        }
+        self.check_limited(sql, &tokens, &scope.tables)?;
+        let mut edits = scope.edits;
+        if edits.is_empty() {
+            return Ok(());
+        }
+        edits.sort_by_key(|v| v.0);
+        let placeholders: Vec<usize> = tokens
+            .iter()
+            .filter(|v| matches!(v.kind, TokenKind::Placeholder(_)))
+            .map(|v| v.start)
+            .collect();
+        let mut new_sql = String::with_capacity(sql.len() + 64);
+        let mut last = 0;
+        let mut inserted_args = 0;
+        for (pos, insert_sql, insert_args) in edits {
+            new_sql.push_str(&sql[last..pos]);
+            new_sql.push_str(&insert_sql);
+            last = pos;
+            let arg_idx = placeholders.iter().filter(|v| **v < pos).count() + inserted_args;
+            inserted_args += insert_args.len();
+            for (i, arg) in insert_args.into_iter().enumerate() {
+                args.insert((arg_idx + i).min(args.len()), arg);
+            }
+        }
+        new_sql.push_str(&sql[last..]);
+        *sql = new_sql;
+        Ok(())
+    }
+
+    /// every table with rule must be parsed as a table of `from`/`join`(or a `with` query name),
+    /// the name before `.` is a qualifier and the name after `.` is a column
+    fn check_limited(&self, sql: &str, tokens: &[Token], tables: &[usize]) -> Result<(), Error> {
+        for (idx, t) in tokens.iter().enumerate() {
+            if !t.is_ident() || tables.contains(&t.start) {
+                continue;
+            }
+            if tokens
+                .get(idx + 1)
+                .map(|v| v.is_symbol("."))
+                .unwrap_or(false)
+                || (idx > 0 && tokens[idx - 1].is_symbol("."))
+            {
+                continue;
+            }
+            if self.rules.get(&t.text.to_lowercase()).is_some() {
+                return Err(Error::from(format!(
+                    "[rb] DataScope can not limit table `{}` of sql: `{}`",
+                    t.text, sql
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// scope a query, the queries of `with` and the selects split by `union`
+    fn scope_query(
+        &self,
+        executor: &dyn Executor,
+        tokens: &[Token],
+        scope: &mut Scope,
+    ) -> Result<(), Error> {
+        if tokens.first().map(|v| v.is_word("with")).unwrap_or(false) {
+            let rest = self.scope_with(executor, tokens, scope)?;
+            return self.scope_query(executor, rest, scope);
+        }
+        let mut start = 0;
+        let mut depth = 0;
+        for (idx, t) in tokens.iter().enumerate() {
+            if t.is_symbol("(") {
+                depth += 1;
+            } else if t.is_symbol(")") {
+                depth -= 1;
+            } else if depth == 0
+                && (t.is_word("union") || t.is_word("intersect") || t.is_word("except"))
+            {
+                self.scope_part(executor, &tokens[start..idx], scope)?;
+                start = idx + 1;
+            }
+        }
+        self.scope_part(executor, &tokens[start..], scope)
+    }
+
+    /// scope the queries of `with a as (...), b(x) as materialized (...)`, return the main query
+    fn scope_with<'a>(
+        &self,
+        executor: &dyn Executor,
+        tokens: &'a [Token],
+        scope: &mut Scope,
+    ) -> Result<&'a [Token], Error> {
+        let err = || Error::from("[rb] DataScope can not parse the `with` clause of sql");
+        let mut i = 1;
+        if tokens
+            .get(i)
+            .map(|v| v.is_word("recursive"))
+            .unwrap_or(false)
+        {
+            i += 1;
+        }
+        loop {
+            let name = tokens.get(i).filter(|v| v.is_ident()).ok_or_else(err)?;
+            scope.tables.push(name.start);
+            i += 1;
+            //column list
+            if tokens.get(i).map(|v| v.is_symbol("(")).unwrap_or(false) {
+                i = close_paren(tokens, i) + 1;
+            }
+            if !tokens.get(i).map(|v| v.is_word("as")).unwrap_or(false) {
+                return Err(err());
+            }
+            i += 1;
+            while tokens
+                .get(i)
+                .map(|v| v.is_word("not") || v.is_word("materialized"))
+                .unwrap_or(false)
+            {
+                i += 1;
+            }
+            if !tokens.get(i).map(|v| v.is_symbol("(")).unwrap_or(false) {
+                return Err(err());
+            }
+            let close = close_paren(tokens, i);
+            if close >= tokens.len() {
+                return Err(err());
+            }
+            self.scope_query(executor, &tokens[i + 1..close], scope)?;
+            i = close + 1;
+            if tokens.get(i).map(|v| v.is_symbol(",")).unwrap_or(false) {
+                i += 1;
+            } else {
+                break;
+            }
+        }
+        Ok(&tokens[i..])
+    }
+
+    fn scope_part(
+        &self,
+        executor: &dyn Executor,
+        tokens: &[Token],
+        scope: &mut Scope,
+    ) -> Result<(), Error> {
+        //skip `all` of `union all`
+        let mut tokens = tokens;
+        while tokens
+            .first()
+            .map(|v| v.is_word("all") || v.is_word("distinct"))
+            .unwrap_or(false)
+        {
+            tokens = &tokens[1..];
+        }
+        self.scope_select(executor, tokens, scope)?;
+        //`(select ...)` of `from`,`in`,`exists` and `(select ...) union (select ...)`
+        let mut i = 0;
+        while i < tokens.len() {
+            if !tokens[i].is_symbol("(") {
+                i += 1;
+                continue;
+            }
+            let close = close_paren(tokens, i);
+            self.scope_query(executor, &tokens[i + 1..close], scope)?;
+            i = close + 1;
+        }
+        Ok(())
+    }
+
+    fn scope_select(
+        &self,
+        executor: &dyn Executor,
+        tokens: &[Token],
+        scope: &mut Scope,
+    ) -> Result<(), Error> {
+        if !tokens.first().map(|v| v.is_word("select")).unwrap_or(false) {
+            return Ok(());
+        }
+        let from = match find_top(tokens, &["from"]) {
+            None => return Ok(()),
+            Some(v) => v,
+        };
+        let clause_end = find_top(&tokens[from + 1..], &CLAUSE_END)
+            .map(|v| v + from + 1)
+            .unwrap_or(tokens.len());
+        //trim `)` of `(select ...)`
+        let mut from_end = clause_end;
+        while from_end > from + 1
+            && tokens[from_end - 1].is_symbol(")")
+            && depth_of(tokens, from_end - 1) < 0
+        {
+            from_end -= 1;
+        }
+        let tables = parse_tables(&tokens[from + 1..from_end]);
+        let mut where_sql = vec![];
+        let mut where_args = vec![];
+        for table in tables {
+            scope.tables.extend(table.positions);
+            let rule = match self.rules.get(&table.name.to_lowercase()) {
+                None => continue,
+                Some(v) => v.clone(),
+            };
+            let (predicate, predicate_args) = match rule(executor, &table.alias) {
+                None => continue,
+                Some(v) => v,
+            };
+            match table.on {
+                Some((on_start, on_end)) if on_end > on_start => {
+                    let on_start = &tokens[from + 1 + on_start];
+                    let on_end = &tokens[from + on_end];
+                    scope.edits.push((on_start.start, "(".to_string(), vec![]));
+                    scope
+                        .edits
+                        .push((on_end.end, format!(") and {}", predicate), predicate_args));
+                }
+                _ => {
+                    where_sql.push(predicate);
+                    where_args.extend(predicate_args);
+                }
+            }
+        }
+        if where_sql.is_empty() {
+            return Ok(());
+        }
+        let predicate = where_sql.join(" and ");
+        if clause_end < tokens.len() && tokens[clause_end].is_word("where") {
+            let mut where_end = find_top(&tokens[clause_end + 1..], &CLAUSE_END[1..])
+                .map(|v| v + clause_end + 1)
+                .unwrap_or(tokens.len());
+            //keep `)` of `(select ... where ...)` outside
+            while where_end > clause_end + 2
+                && tokens[where_end - 1].is_symbol(")")
+                && depth_of(tokens, where_end - 1) < 0
+            {
+                where_end -= 1;
+            }
+            if where_end <= clause_end + 1 {
+                return Err(Error::from(
+                    "[rb] DataScope can not limit the empty `where` of sql",
+                ));
+            }
+            scope
+                .edits
+                .push((tokens[clause_end + 1].start, "(".to_string(), vec![]));
+            scope.edits.push((
+                tokens[where_end - 1].end,
+                format!(") and {}", predicate),
+                where_args,
+            ));
+        } else {
+            scope.edits.push((
+                tokens[from_end - 1].end,
+                format!(" where {}", predicate),
+                where_args,
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// the edits of a sql
+#[derive(Default)]
+struct Scope {
+    /// (byte position, insert sql, insert args)
+    edits: Vec<(usize, String, Vec<Value>)>,
+    /// byte positions of the parsed table names and aliases
+    tables: Vec<usize>,
+}
+
+/// parentheses depth before tokens[idx]
+fn depth_of(tokens: &[Token], idx: usize) -> i32 {
+    let mut depth = 0;
+    for t in &tokens[..idx] {
+        if t.is_symbol("(") {
+            depth += 1;
+        } else if t.is_symbol(")") {
+            depth -= 1;
+        }
+    }
+    if tokens[idx].is_symbol(")") {
+        depth -= 1;
+    }
+    depth
+}
+
+/// the index of the `)` matching tokens[open], or tokens.len()
+fn close_paren(tokens: &[Token], open: usize) -> usize {
+    let mut depth = 0;
+    for (idx, t) in tokens.iter().enumerate().skip(open) {
+        if t.is_symbol("(") {
+            depth += 1;
+        } else if t.is_symbol(")") {
+            depth -= 1;
+            if depth == 0 {
+                return idx;
+            }
+        }
+    }
+    tokens.len()
+}
+
+/// find the first keyword at depth 0
+fn find_top(tokens: &[Token], words: &[&str]) -> Option<usize> {
+    let mut depth = 0;
+    for (idx, t) in tokens.iter().enumerate() {
+        if t.is_symbol("(") {
+            depth += 1;
+        } else if t.is_symbol(")") {
+            depth -= 1;
+        } else if depth == 0 && words.iter().any(|w| t.is_word(w)) {
+            return Some(idx);
+        }
+    }
+    None
+}
+
+fn is_keyword(t: &Token) -> bool {
+    t.kind == TokenKind::Word
+        && (JOIN_WORDS.iter().any(|w| t.is_word(w))
+            || t.is_word("on")
+            || t.is_word("outer")
+            || t.is_word("using")
+            || CLAUSE_END.iter().any(|w| t.is_word(w)))
+}
+
+/// parse tables of `from` clause, for example `user u left join role r on u.role_id = r.id, dept`
+fn parse_tables(tokens: &[Token]) -> Vec<TableRef> {
+    let mut tables = vec![];
+    let mut i = 0;
+    while i < tokens.len() {
+        //skip `,` and join words
+        if tokens[i].is_symbol(",")
+            || JOIN_WORDS.iter().any(|w| tokens[i].is_word(w))
+            || tokens[i].is_word("outer")
+        {
+            i += 1;
+            continue;
+        }
+        let mut name = String::new();
+        let mut positions = vec![];
+        if tokens[i].is_symbol("(") {
+            //sub query
+            let mut depth = 0;
+            while i < tokens.len() {
+                if tokens[i].is_symbol("(") {
+                    depth += 1;
+                } else if tokens[i].is_symbol(")") {
+                    depth -= 1;
+                    if depth == 0 {
+                        break;
+                    }
+                }
+                i += 1;
+            }
+            i += 1;
+        } else if tokens[i].is_ident() {
+            name = tokens[i].text.clone();
+            positions.push(tokens[i].start);
+            i += 1;
+            //schema.table
+            while i + 1 < tokens.len() && tokens[i].is_symbol(".") && tokens[i + 1].is_ident() {
+                name = tokens[i + 1].text.clone();
+                positions.push(tokens[i + 1].start);
+                i += 2;
+            }
+        } else {
+            i += 1;
+            continue;
+        }
+        let mut alias = name.clone();
+        if i < tokens.len() && tokens[i].is_word("as") {
+            i += 1;
+        }
+        if i < tokens.len() && tokens[i].is_ident() && !is_keyword(&tokens[i]) {
+            alias = tokens[i].text.clone();
+            positions.push(tokens[i].start);
+            i += 1;
+        }
+        let mut on = None;
+        if i < tokens.len() && tokens[i].is_word("on") {
+            let on_start = i + 1;
+            let mut depth = 0;
+            i += 1;
+            while i < tokens.len() {
+                let t = &tokens[i];
+                if t.is_symbol("(") {
+                    depth += 1;
+                } else if t.is_symbol(")") {
+                    depth -= 1;
+                } else if depth == 0
+                    && (t.is_symbol(",") || JOIN_WORDS.iter().any(|w| t.is_word(w)))
+                {
+                    break;
+                }
+                i += 1;
+            }
+            on = Some((on_start, i));
+        }
+        //the name of a sub query is empty, only its alias is kept
+        tables.push(TableRef {
+            name,
+            alias,
+            positions,
+            on,
+        });
+    }
+    tables
+}
+
+#[async_trait]
+impl Intercept for DataScopeIntercept {
+    async fn before(
+        &self,
+        _task_id: i64,
+        executor: &dyn Executor,
+        sql: &mut String,
+        args: &mut Vec<Value>,
+        result: ResultType<&mut Result<ExecResult, Error>, &mut Result<Vec<Value>, Error>>,
+    ) -> Result<Option<bool>, Error> {
+        if let ResultType::Exec(_) = result {
+            return Ok(Some(true));
+        }
+        self.apply(executor, sql, args)?;
+        Ok(Some(true))
+    }
+}
@@ -0,0 +1,364 @@
+use crate::decode::Rows;
+use crate::executor::Executor;
+use crate::intercept::{Intercept, ResultType};
+use crate::Error;
+use async_trait::async_trait;
+use rbdc::db::ExecResult;
+use rbs::Value;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashSet;
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+
+/// ext name of random(non-deterministic) encrypted value
+pub const ENCRYPTED: &str = "Encrypted";
+/// ext name of deterministic encrypted value
+pub const ENCRYPTED_EQ: &str = "EncryptedEq";
+
+const TAG_STRING: u8 = b'S';
+const TAG_BINARY: u8 = b'B';
+
+/// pluggable cipher used by EncryptIntercept
+///
+/// deterministic = true means the same plaintext must always produce the same ciphertext,
+/// so that `where column = ?` lookups still work on encrypted columns.
+pub trait Cipher: Send + Sync + Debug {
+    fn encrypt(&self, plain: &[u8], deterministic: bool) -> Result<Vec<u8>, Error>;
+    fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>, Error>;
+}
+
+/// a column value encrypted with a random(non-deterministic) cipher mode.
+/// it serializes to `Value::Ext("Encrypted", plaintext)`, and EncryptIntercept replaces it with ciphertext.
+/// support inner value type: String, Vec<u8>
+/// ```rust
+/// use rbatis::intercept_encrypt::Encrypted;
+/// #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+/// pub struct User{
+///     pub id: Option<String>,
+///     pub id_card: Option<Encrypted<String>>,
+/// }
+/// ```
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct Encrypted<T>(pub T);
+
+/// a column value encrypted with a deterministic cipher mode, can be used on equality lookups.
+/// ```rust
+/// use rbatis::intercept_encrypt::EncryptedEq;
+/// #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+/// pub struct User{
+///     pub id: Option<String>,
+///     pub phone: Option<EncryptedEq<String>>,
+/// }
+/// //select_by_column(rb, "phone", EncryptedEq("123".to_string()))
+/// ```
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct EncryptedEq<T>(pub T);
+
+macro_rules! impl_encrypted {
+    ($t:ident,$name:expr) => {
+        impl<T> $t<T> {
+            pub fn into_inner(self) -> T {
+                self.0
+            }
+        }
+
+        impl<T> std::ops::Deref for $t<T> {
+            type Target = T;
+
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+
+        impl<T> From<T> for $t<T> {
+            fn from(arg: T) -> Self {
+                Self(arg)
+            }
+        }
+
+        /// never print plaintext
+        impl<T> Debug for $t<T> {
+            fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+                write!(f, "{}(***)", $name)
+            }
+        }
+
+        impl<T: Serialize> Serialize for $t<T> {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                serializer.serialize_newtype_struct($name, &self.0)
+            }
+        }
+
+        impl<'de, T: Deserialize<'de>> Deserialize<'de> for $t<T> {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                Ok(Self(T::deserialize(deserializer)?))
+            }
+        }
+    };
+}
+
+impl_encrypted!(Encrypted, ENCRYPTED);
+impl_encrypted!(EncryptedEq, ENCRYPTED_EQ);
+
+/// is value a `Encrypted`/`EncryptedEq` value
+pub fn is_encrypted_value(v: &Value) -> bool {
+    match v {
+        Value::Ext(name, _) => *name == ENCRYPTED || *name == ENCRYPTED_EQ,
+        _ => false,
+    }
+}
+
+/// Transparent column encryption.
+///
+/// * before: every `Encrypted`/`EncryptedEq` arg is encrypted and replaced by a hex string
+/// * after: every configured column of the query rows is decrypted back into plaintext,
+///   the alias of a configured column(`select phone as p`, `select u.phone as p`) is decrypted too
+/// * a value can not be decrypted(for example the plaintext written before the column is encrypted)
+///   is returned as is, unless `strict(true)` is set
+///
+/// notice: push it after LogInterceptor, so the log only sees ciphertext rows
+/// ```rust
+/// use std::sync::Arc;
+/// use rbatis::{Error, RBatis};
+/// use rbatis::intercept_encrypt::{Cipher, EncryptIntercept};
+///
+/// #[derive(Debug)]
+/// pub struct MyCipher{}
+/// impl Cipher for MyCipher{
+///     fn encrypt(&self, plain: &[u8], _deterministic: bool) -> Result<Vec<u8>, Error> {
+///         Ok(plain.iter().map(|v| v ^ 0x5a).collect())
+///     }
+///     fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>, Error> {
+///         Ok(data.iter().map(|v| v ^ 0x5a).collect())
+///     }
+/// }
+/// let rb = RBatis::new();
+/// rb.intercepts.push(Arc::new(EncryptIntercept::new(MyCipher{}).column("phone").column("id_card")));
+/// ```
+#[derive(Debug)]
+pub struct EncryptIntercept {
+    pub cipher: Arc<dyn Cipher>,
+    /// encrypted column names, query rows with these columns will be decrypted
+    pub columns: HashSet<String>,
+    /// fail the query if a column value can not be decrypted, default false(keep the value)
+    pub strict: bool,
+}
+
+impl EncryptIntercept {
+    pub fn new<C: Cipher + 'static>(cipher: C) -> Self {
+        Self {
+            cipher: Arc::new(cipher),
+            columns: HashSet::new(),
+            strict: false,
+        }
+    }
+
+    /// add an encrypted column name
+    pub fn column(mut self, name: &str) -> Self {
+        self.columns.insert(name.to_string());
+        self
+    }
+
+    /// fail the query if a column value can not be decrypted
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// encrypt an plaintext value into hex string
+    pub fn encrypt_value(&self, v: Value, deterministic: bool) -> Result<Value, Error> {
+        let mut plain = vec![];
+        match v {
+            Value::Null => return Ok(Value::Null),
+            Value::String(s) => {
+                plain.push(TAG_STRING);
+                plain.extend_from_slice(s.as_bytes());
+            }
+            Value::Binary(b) => {
+                plain.push(TAG_BINARY);
+                plain.extend_from_slice(&b);
+            }
+            Value::Ext(name, v) if name == ENCRYPTED || name == ENCRYPTED_EQ => {
+                return self.encrypt_value(*v, deterministic)
+            }
+            _ => {
+                return Err(Error::from(format!(
+                    "[rb] encrypt only support String/Binary value, but find {}",
+                    type_name(&v)
+                )));
+            }
+        }
+        let data = self.cipher.encrypt(&plain, deterministic)?;
+        Ok(Value::String(hex::encode(data)))
+    }
+
+    /// decrypt an hex string ciphertext into plaintext value
+    pub fn decrypt_value(&self, v: &Value) -> Result<Value, Error> {
+        let data = match v {
+            Value::String(s) => {
+                hex::decode(s).map_err(|e| Error::from(format!("[rb] decrypt fail: {}", e)))?
+            }
+            Value::Binary(b) => b.clone(),
+            Value::Null => return Ok(Value::Null),
+            _ => {
+                return Err(Error::from(format!(
+                    "[rb] decrypt only support String/Binary value, but find {}",
+                    type_name(v)
+                )));
+            }
+        };
+        let plain = self.cipher.decrypt(&data)?;
+        match plain.split_first() {
+            Some((&TAG_STRING, s)) => Ok(Value::String(
+                String::from_utf8(s.to_vec()).map_err(|e| Error::from(e.to_string()))?,
+            )),
+            Some((&TAG_BINARY, b)) => Ok(Value::Binary(b.to_vec())),
+            _ => Err(Error::from("[rb] decrypt fail: unknown plaintext tag")),
+        }
+    }
+
+    /// decrypt a column value, keep the value if it can not be decrypted and not strict
+    fn decrypt_column(&self, v: &mut Value) -> Result<(), Error> {
+        match self.decrypt_value(v) {
+            Ok(plain) => *v = plain,
+            Err(e) => {
+                if self.strict {
+                    return Err(e);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// the result columns to decrypt: the configured columns and their aliases(`column as alias`) in the sql
+    pub fn result_columns(&self, sql: &str) -> HashSet<String> {
+        let mut columns = self.columns.clone();
+        let words: Vec<&str> = sql
+            .split(|c: char| c.is_whitespace() || c == ',' || c == '(' || c == ')')
+            .filter(|v| !v.is_empty())
+            .collect();
+        for v in words.windows(3) {
+            if !v[1].eq_ignore_ascii_case("as") {
+                continue;
+            }
+            let column = v[0].rsplit('.').next().unwrap_or_default();
+            if self.columns.contains(unquote(column)) {
+                columns.insert(unquote(v[2]).to_string());
+            }
+        }
+        columns
+    }
+
+    fn encrypt_args(&self, args: &mut [Value]) -> Result<(), Error> {
+        for arg in args.iter_mut() {
+            match arg {
+                Value::Ext(name, _) if *name == ENCRYPTED || *name == ENCRYPTED_EQ => {
+                    let deterministic = *name == ENCRYPTED_EQ;
+                    let v = std::mem::take(arg);
+                    *arg = self.encrypt_value(v, deterministic)?;
+                }
+                Value::Array(arr) => {
+                    self.encrypt_args(arr)?;
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+fn unquote(name: &str) -> &str {
+    name.trim_matches(|c| c == '`' || c == '"' || c == '[' || c == ']')
+}
+
+fn type_name(v: &Value) -> &'static str {
+    match v {
+        Value::Null => "Null",
+        Value::Bool(_) => "Bool",
+        Value::I32(_) => "I32",
+        Value::I64(_) => "I64",
+        Value::U32(_) => "U32",
+        Value::U64(_) => "U64",
+        Value::F32(_) => "F32",
+        Value::F64(_) => "F64",
+        Value::String(_) => "String",
+        Value::Binary(_) => "Binary",
+        Value::Array(_) => "Array",
+        Value::Map(_) => "Map",
+        Value::Ext(name, _) => name,
+    }
+}
+
+#[async_trait]
+impl Intercept for EncryptIntercept {
+    async fn before(
+        &self,
+        _task_id: i64,
+        _rb: &dyn Executor,
+        _sql: &mut String,
+        args: &mut Vec<Value>,
+        _result: ResultType<&mut Result<ExecResult, Error>, &mut Result<Vec<Value>, Error>>,
+    ) -> Result<Option<bool>, Error> {
+        self.encrypt_args(args)?;
+        Ok(Some(true))
+    }
+
+    async fn after(
+        &self,
+        _task_id: i64,
+        _rb: &dyn Executor,
+        sql: &mut String,
+        _args: &mut Vec<Value>,
+        result: ResultType<&mut Result<ExecResult, Error>, &mut Result<Vec<Value>, Error>>,
+    ) -> Result<Option<bool>, Error> {
+        if self.columns.is_empty() {
+            return Ok(Some(true));
+        }
+        if let ResultType::Query(Ok(rows)) = result {
+            let columns = self.result_columns(sql);
+            for row in rows.iter_mut() {
+                if let Value::Map(m) = row {
+                    for (k, v) in m.0.iter_mut() {
+                        if let Value::String(k) = k {
+                            if columns.contains(k) {
+                                self.decrypt_column(v)?;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        Ok(Some(true))
+    }
+
+    /// decrypt the configured columns of the rows, the rows are not converted to `Value` maps
+    async fn after_rows(
+        &self,
+        _task_id: i64,
+        _rb: &dyn Executor,
+        sql: &mut String,
+        _args: &mut Vec<Value>,
+        result: &mut Result<Rows, Error>,
+    ) -> Result<Option<bool>, Error> {
+        if self.columns.is_empty() {
+            return Ok(Some(true));
+        }
+        if let Ok(rows) = result {
+            let columns = self.result_columns(sql);
+            for (idx, column) in rows.columns.iter().enumerate() {
+                if columns.contains(column) {
+                    for row in rows.rows.iter_mut() {
+                        self.decrypt_column(&mut row[idx])?;
+                    }
+                }
+            }
+        }
+        Ok(Some(true))
+    }
+}
@@ -0,0 +1,262 @@
+use crate::executor::{BatchExecResult, Executor};
+use crate::intercept::{Intercept, ResultType};
+use crate::utils::sql_util::{find_top_level_word, split_statements, Token, TokenKind};
+use crate::Error;
+use async_trait::async_trait;
+use rbdc::db::ExecResult;
+use rbs::Value;
+
+/// Dangerous statement guard, reject the sql before it is sent to the database.
+///
+/// every rule can be turned off, and an executor with the context value `SkipSqlGuard` skips the guard
+/// (for example an intentional bulk delete)
+/// ```rust
+/// use std::sync::Arc;
+/// use rbatis::{Error, RBatis};
+/// use rbatis::executor::Context;
+/// use rbatis::intercept_guard::{SkipSqlGuard, SqlGuardIntercept};
+///
+/// let rb = RBatis::new();
+/// rb.intercepts.push(Arc::new(SqlGuardIntercept {
+///     deny_truncate: false,
+///     ..SqlGuardIntercept::default()
+/// }));
+///
+/// async fn clear_table(rb: &RBatis) -> Result<(), Error> {
+///     rb.with_context(Context::new().with(SkipSqlGuard))
+///         .exec("delete from user", vec![])
+///         .await?;
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct SqlGuardIntercept {
+    /// reject `update`/`delete` without `where`
+    pub deny_no_where: bool,
+    /// reject `update`/`delete` with an always true `where`, for example `where 1 = 1`
+    pub deny_tautology_where: bool,
+    /// reject `select` without `where` and `limit`
+    pub deny_full_table_select: bool,
+    /// reject `truncate`
+    pub deny_truncate: bool,
+    /// reject `drop`
+    pub deny_drop: bool,
+    /// reject many statements in one sql, for example `delete from a where id = 1;drop table a`
+    pub deny_multi_statement: bool,
+}
+
+/// the context value(see `executor::Context`) of an executor which skips the `SqlGuardIntercept`
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SkipSqlGuard;
+
+impl Default for SqlGuardIntercept {
+    fn default() -> Self {
+        Self {
+            deny_no_where: true,
+            deny_tautology_where: true,
+            deny_full_table_select: false,
+            deny_truncate: true,
+            deny_drop: true,
+            deny_multi_statement: true,
+        }
+    }
+}
+
+impl SqlGuardIntercept {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// check sql, return Err if the sql is rejected
+    pub fn check(&self, sql: &str) -> Result<(), Error> {
+        let statements = split_statements(sql);
+        if self.deny_multi_statement && statements.len() > 1 {
+            return Err(Error::from(format!(
+                "[rb] SqlGuard reject multi statement sql: `{}`",
+                sql
+            )));
+        }
+        for statement in &statements {
+            self.check_statement(statement)?;
+        }
+        Ok(())
+    }
+
+    fn check_statement(&self, tokens: &[Token]) -> Result<(), Error> {
+        let first = match tokens.first() {
+            None => return Ok(()),
+            Some(v) => v.text.to_lowercase(),
+        };
+        match first.as_str() {
+            "update" | "delete" => match find_top_level_word(tokens, "where") {
+                None => {
+                    if self.deny_no_where {
+                        return Err(Error::from(format!(
+                            "[rb] SqlGuard reject `{}` without where",
+                            first
+                        )));
+                    }
+                }
+                Some(idx) => {
+                    if self.deny_tautology_where && is_tautology(where_clause(&tokens[idx + 1..])) {
+                        return Err(Error::from(format!(
+                            "[rb] SqlGuard reject `{}` with an always true where",
+                            first
+                        )));
+                    }
+                }
+            },
+            "select"
+                if self.deny_full_table_select
+                    && find_top_level_word(tokens, "from").is_some()
+                    && find_top_level_word(tokens, "where").is_none()
+                    && find_top_level_word(tokens, "limit").is_none()
+                    && find_top_level_word(tokens, "fetch").is_none() =>
+            {
+                return Err(Error::from("[rb] SqlGuard reject full table select"));
+            }
+            "truncate" if self.deny_truncate => {
+                return Err(Error::from("[rb] SqlGuard reject `truncate`"));
+            }
+            "drop" if self.deny_drop => {
+                return Err(Error::from("[rb] SqlGuard reject `drop`"));
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+/// take tokens of where clause until `order by`,`limit`...
+fn where_clause(tokens: &[Token]) -> &[Token] {
+    let mut end = tokens.len();
+    for word in [
+        "order",
+        "group",
+        "limit",
+        "offset",
+        "returning",
+        "having",
+        "fetch",
+    ] {
+        if let Some(idx) = find_top_level_word(tokens, word) {
+            end = end.min(idx);
+        }
+    }
+    &tokens[..end]
+}
+
+fn strip_parens(mut tokens: &[Token]) -> &[Token] {
+    while tokens.len() >= 2 && tokens[0].is_symbol("(") && tokens[tokens.len() - 1].is_symbol(")") {
+        //make sure the first `(` matches the last `)`
+        let mut depth = 0;
+        let mut matched = true;
+        for (idx, t) in tokens.iter().enumerate() {
+            if t.is_symbol("(") {
+                depth += 1;
+            } else if t.is_symbol(")") {
+                depth -= 1;
+                if depth == 0 && idx != tokens.len() - 1 {
+                    matched = false;
+                    break;
+                }
+            }
+        }
+        if !matched {
+            break;
+        }
+        tokens = &tokens[1..tokens.len() - 1];
+    }
+    tokens
+}
+
+fn split_top_level<'a>(tokens: &'a [Token], word: &str) -> Vec<&'a [Token]> {
+    let mut terms = vec![];
+    let mut depth = 0;
+    let mut start = 0;
+    for (idx, t) in tokens.iter().enumerate() {
+        if t.is_symbol("(") {
+            depth += 1;
+        } else if t.is_symbol(")") {
+            depth -= 1;
+        } else if depth == 0 && t.is_word(word) {
+            terms.push(&tokens[start..idx]);
+            start = idx + 1;
+        }
+    }
+    terms.push(&tokens[start..]);
+    terms
+}
+
+/// is the where clause always true
+fn is_tautology(tokens: &[Token]) -> bool {
+    let tokens = strip_parens(tokens);
+    if tokens.is_empty() {
+        return true;
+    }
+    let or_terms = split_top_level(tokens, "or");
+    if or_terms.len() > 1 {
+        return or_terms.iter().any(|v| is_tautology(v));
+    }
+    let and_terms = split_top_level(tokens, "and");
+    if and_terms.len() > 1 {
+        return and_terms.iter().all(|v| is_tautology(v));
+    }
+    match tokens {
+        [v] => {
+            v.is_word("true")
+                || (v.kind == TokenKind::Number && v.text.parse::<f64>().unwrap_or(0.0) != 0.0)
+        }
+        [left, op, right] => {
+            let is_const = |t: &Token| {
+                t.kind == TokenKind::Number || t.kind == TokenKind::Literal || t.is_ident()
+            };
+            if !is_const(left) || !is_const(right) || left.kind != right.kind {
+                return false;
+            }
+            if op.is_symbol("=") {
+                left.text == right.text
+            } else if op.is_symbol("<>") || op.is_symbol("!=") {
+                left.kind != TokenKind::Word
+                    && left.kind != TokenKind::QuotedIdent
+                    && left.text != right.text
+            } else {
+                false
+            }
+        }
+        _ => false,
+    }
+}
+
+#[async_trait]
+impl Intercept for SqlGuardIntercept {
+    async fn before(
+        &self,
+        _task_id: i64,
+        executor: &dyn Executor,
+        sql: &mut String,
+        _args: &mut Vec<Value>,
+        _result: ResultType<&mut Result<ExecResult, Error>, &mut Result<Vec<Value>, Error>>,
+    ) -> Result<Option<bool>, Error> {
+        if executor.context_value::<SkipSqlGuard>().is_some() {
+            return Ok(Some(true));
+        }
+        self.check(sql)?;
+        Ok(Some(true))
+    }
+
+    async fn before_batch(
+        &self,
+        _task_id: i64,
+        executor: &dyn Executor,
+        sql: &mut String,
+        _args: &mut Vec<Vec<Value>>,
+        _result: &mut Result<BatchExecResult, Error>,
+    ) -> Result<Option<bool>, Error> {
+        if executor.context_value::<SkipSqlGuard>().is_some() {
+            return Ok(Some(true));
+        }
+        self.check(sql)?;
+        Ok(Some(true))
+    }
+}
@@ -1,41 +1,85 @@
-use crate::decode::is_debug_mode;
-use crate::executor::Executor;
+use crate::decode::{is_debug_mode, Rows};
+use crate::executor::{BatchExecResult, Executor};
 use crate::intercept::{Intercept, ResultType};
+use crate::intercept_encrypt::is_encrypted_value;
+use crate::utils::sql_util::{placeholder_columns, tokenize, TokenKind};
 use crate::Error;
 use async_trait::async_trait;
+use dark_std::sync::SyncVec;
 use log::{log, Level, LevelFilter};
 use rbdc::db::ExecResult;
 use rbs::Value;
-use std::fmt::{Display, Formatter};
+use std::fmt::{Debug, Display, Formatter};
 use std::ops::Deref;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
 
 struct RbsValueDisplay<'a> {
     inner: &'a Vec<Value>,
+    log: &'a LogInterceptor,
+    /// column name of every value
+    columns: Vec<Option<String>>,
 }
 
 impl<'a> RbsValueDisplay<'a> {
-    pub fn new(v: &'a Vec<Value>) -> Self {
-        Self { inner: v }
+    pub fn new(v: &'a Vec<Value>, log: &'a LogInterceptor) -> Self {
+        Self {
+            inner: v,
+            log,
+            columns: vec![],
+        }
+    }
+
+    pub fn with_columns(mut self, columns: Vec<Option<String>>) -> Self {
+        self.columns = columns;
+        self
     }
 }
 
 impl<'a> Display for RbsValueDisplay<'a> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         f.write_str("[")?;
-        let mut idx = 0;
-        for x in self.inner.deref() {
-            std::fmt::Display::fmt(x, f)?;
+        for (idx, x) in self.inner.iter().enumerate() {
+            let column = self.columns.get(idx).and_then(|v| v.as_deref());
+            self.log.fmt_value(f, x, column)?;
             if (idx + 1) < self.inner.len() {
                 f.write_str(",")?;
             }
-            idx += 1;
         }
         f.write_str("]")?;
         Ok(())
     }
 }
 
+/// rule of sensitive arg, the matched arg will print as `***`
+#[derive(Clone)]
+pub enum MaskRule {
+    /// mask the value bound to this column(case insensitive).
+    /// the column is parsed from sql, for example `password = ?`, `insert into user (password) values (?)`
+    Column(String),
+    /// mask the value when matcher return true, for example match String by an regex, or match the value type
+    Matcher(Arc<dyn Fn(&Value) -> bool + Send + Sync>),
+}
+
+impl Debug for MaskRule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MaskRule::Column(column) => f.debug_tuple("Column").field(column).finish(),
+            MaskRule::Matcher(_) => f.write_str("Matcher"),
+        }
+    }
+}
+
+impl MaskRule {
+    pub fn column(name: &str) -> Self {
+        MaskRule::Column(name.to_string())
+    }
+
+    pub fn matcher<F: Fn(&Value) -> bool + Send + Sync + 'static>(f: F) -> Self {
+        MaskRule::Matcher(Arc::new(f))
+    }
+}
+
 /// LogInterceptor
 #[derive(Debug)]
 pub struct LogInterceptor {
@@ -47,11 +91,23 @@ pub struct LogInterceptor {
     /// 4=Debug,
     /// 5=Trace
     pub level_filter: AtomicUsize,
+    /// sensitive arg rules
+    pub mask_rules: SyncVec<MaskRule>,
+    /// max print length of String/Binary value, 0 = unlimited
+    pub max_value_len: AtomicUsize,
+    /// print one sql with args inlined, instead of `sql [args]`
+    pub render_sql: AtomicBool,
 }
 
 impl Clone for LogInterceptor {
     fn clone(&self) -> Self {
-        LogInterceptor::new(self.get_level_filter())
+        let s = LogInterceptor::new(self.get_level_filter());
+        for x in self.mask_rules.iter() {
+            s.mask_rules.push(x.clone());
+        }
+        s.set_max_value_len(self.get_max_value_len());
+        s.set_render_sql(self.is_render_sql());
+        s
     }
 }
 
@@ -59,11 +115,177 @@ impl LogInterceptor {
     pub fn new(level_filter: LevelFilter) -> Self {
         let s = Self {
             level_filter: AtomicUsize::new(0),
+            mask_rules: SyncVec::new(),
+            max_value_len: AtomicUsize::new(0),
+            render_sql: AtomicBool::new(false),
         };
         s.set_level_filter(level_filter);
         s
     }
 
+    /// add an sensitive arg rule
+    /// ```rust
+    /// use log::LevelFilter;
+    /// use rbatis::intercept_log::{LogInterceptor, MaskRule};
+    /// use rbs::Value;
+    ///
+    /// let log = LogInterceptor::new(LevelFilter::Debug);
+    /// log.add_mask_rule(MaskRule::column("password"));
+    /// log.add_mask_rule(MaskRule::matcher(|v| matches!(v, Value::String(s) if s.starts_with("Bearer "))));
+    /// ```
+    pub fn add_mask_rule(&self, rule: MaskRule) {
+        self.mask_rules.push(rule);
+    }
+
+    pub fn get_max_value_len(&self) -> usize {
+        self.max_value_len.load(Ordering::Relaxed)
+    }
+
+    /// truncate long String/Binary value, 0 = unlimited
+    pub fn set_max_value_len(&self, len: usize) {
+        self.max_value_len.store(len, Ordering::SeqCst);
+    }
+
+    pub fn is_render_sql(&self) -> bool {
+        self.render_sql.load(Ordering::Relaxed)
+    }
+
+    /// print one copy-pasteable sql with args inlined, for example `select * from user where id = 1`
+    pub fn set_render_sql(&self, render: bool) {
+        self.render_sql.store(render, Ordering::SeqCst);
+    }
+
+    /// is the value(bound to column) sensitive
+    pub fn is_masked(&self, column: Option<&str>, v: &Value) -> bool {
+        if is_encrypted_value(v) {
+            return true;
+        }
+        for rule in self.mask_rules.iter() {
+            match rule {
+                MaskRule::Column(name) => {
+                    if let Some(column) = column {
+                        let column = column.rsplit('.').next().unwrap_or(column);
+                        if name.eq_ignore_ascii_case(column) {
+                            return true;
+                        }
+                    }
+                }
+                MaskRule::Matcher(f) => {
+                    if f(v) {
+                        return true;
+                    }
+                }
+            }
+        }
+        false
+    }
+
+    fn fmt_value(&self, f: &mut Formatter<'_>, v: &Value, column: Option<&str>) -> std::fmt::Result {
+        if self.is_masked(column, v) {
+            return f.write_str("***");
+        }
+        let max = self.get_max_value_len();
+        match v {
+            Value::String(s) if max != 0 && s.chars().count() > max => {
+                let s: String = s.chars().take(max).collect();
+                write!(f, "\"{}...\"", s)
+            }
+            Value::Binary(b) if max != 0 && b.len() > max => {
+                write!(f, "{:?}...(len={})", &b[..max], b.len())
+            }
+            Value::Array(arr) => {
+                f.write_str("[")?;
+                for (idx, x) in arr.iter().enumerate() {
+                    self.fmt_value(f, x, None)?;
+                    if idx + 1 < arr.len() {
+                        f.write_str(",")?;
+                    }
+                }
+                f.write_str("]")
+            }
+            Value::Map(m) => {
+                f.write_str("{")?;
+                for (idx, (k, v)) in m.0.iter().enumerate() {
+                    Display::fmt(k, f)?;
+                    f.write_str(":")?;
+                    self.fmt_value(f, v, k.as_str())?;
+                    if idx + 1 < m.len() {
+                        f.write_str(",")?;
+                    }
+                }
+                f.write_str("}")
+            }
+            Value::Ext(_, inner) => self.fmt_value(f, inner, column),
+            _ => Display::fmt(v, f),
+        }
+    }
+
+    /// render sql with args inlined.
+    /// support placeholder `?`, `$1`(pg), `@p1`(mssql)
+    pub fn render(&self, driver_type: &str, sql: &str, args: &[Value]) -> String {
+        let columns = placeholder_columns(sql);
+        let mut result = String::with_capacity(sql.len());
+        let mut last = 0;
+        let mut idx = 0;
+        for token in tokenize(sql) {
+            if let TokenKind::Placeholder(n) = token.kind {
+                let arg_idx = n.unwrap_or(idx);
+                let column = columns.get(idx).and_then(|v| v.as_deref());
+                idx += 1;
+                if let Some(arg) = args.get(arg_idx) {
+                    result.push_str(&sql[last..token.start]);
+                    result.push_str(&self.sql_literal(driver_type, arg, column));
+                    last = token.end;
+                }
+            }
+        }
+        result.push_str(&sql[last..]);
+        result
+    }
+
+    fn sql_literal(&self, driver_type: &str, v: &Value, column: Option<&str>) -> String {
+        if self.is_masked(column, v) {
+            return "'***'".to_string();
+        }
+        let max = self.get_max_value_len();
+        match v {
+            Value::Null => "NULL".to_string(),
+            Value::Bool(b) => {
+                if driver_type == "mssql" {
+                    (*b as i32).to_string()
+                } else {
+                    b.to_string()
+                }
+            }
+            Value::String(s) => {
+                if max != 0 && s.chars().count() > max {
+                    let s: String = s.chars().take(max).collect();
+                    return format!("'{}...'", s.replace('\'', "''"));
+                }
+                format!("'{}'", s.replace('\'', "''"))
+            }
+            Value::Binary(b) => {
+                let mut b = b.as_slice();
+                if max != 0 && b.len() > max {
+                    b = &b[..max];
+                }
+                match driver_type {
+                    "pg" | "postgres" => format!("'\\x{}'", hex::encode(b)),
+                    "mssql" => format!("0x{}", hex::encode(b)),
+                    _ => format!("X'{}'", hex::encode(b)),
+                }
+            }
+            Value::Ext(_, inner) => match inner.deref() {
+                Value::String(_) | Value::Binary(_) | Value::Null => {
+                    self.sql_literal(driver_type, inner, column)
+                }
+                inner => format!("'{}'", inner.to_string().replace('\'', "''")),
+            },
+            Value::Array(_) | Value::Map(_) => format!("'{}'", v.to_string().replace('\'', "''")),
+            _ => v.to_string(),
+        }
+    }
+
     pub fn get_level_filter(&self) -> LevelFilter {
         match self.level_filter.load(Ordering::Relaxed) {
             0 => LevelFilter::Off,
@@ -104,7 +326,7 @@ impl Intercept for LogInterceptor {
     async fn before(
         &self,
         task_id: i64,
-        _rb: &dyn Executor,
+        rb: &dyn Executor,
         sql: &mut String,
         args: &mut Vec<Value>,
         _result: ResultType<&mut Result<ExecResult, Error>, &mut Result<Vec<Value>, Error>>,
@@ -113,14 +335,22 @@ impl Intercept for LogInterceptor {
             return Ok(Some(true));
         }
         let level = self.to_level().unwrap_or(Level::Debug);
+        if self.is_render_sql() {
+            let driver_type = rb.driver_type().unwrap_or_default();
+            log!(
+                level,
+                "[rb] [{}] => `{}`",
+                task_id,
+                self.render(driver_type, sql, args)
+            );
+            return Ok(Some(true));
+        }
         //send sql/args
-        log!(
-            level,
-            "[rb] [{}] => `{}` {}",
-            task_id,
-            &sql,
-            RbsValueDisplay::new(args)
-        );
+        let mut display = RbsValueDisplay::new(args, self);
+        if !self.mask_rules.is_empty() {
+            display = display.with_columns(placeholder_columns(sql));
+        }
+        log!(level, "[rb] [{}] => `{}` {}", task_id, &sql, display);
         Ok(Some(true))
     }
 
@@ -154,7 +384,7 @@ impl Intercept for LogInterceptor {
                             "[rb] [{}] <= len={},rows={}",
                             task_id,
                             result.len(),
-                            RbsValueDisplay { inner: result }
+                            RbsValueDisplay::new(result, self)
                         );
                     } else {
                         log!(level, "[rb] [{}] <= len={}", task_id, result.len());
@@ -167,4 +397,84 @@ impl Intercept for LogInterceptor {
         }
         Ok(Some(true))
     }
+
+    /// the rows are converted to `Value` maps only in debug mode
+    async fn after_rows(
+        &self,
+        task_id: i64,
+        _rb: &dyn Executor,
+        _sql: &mut String,
+        _args: &mut Vec<Value>,
+        result: &mut Result<Rows, Error>,
+    ) -> Result<Option<bool>, Error> {
+        if self.get_level_filter() == LevelFilter::Off {
+            return Ok(Some(true));
+        }
+        let level = self.to_level().unwrap_or(Level::Debug);
+        match result {
+            Ok(result) => {
+                if is_debug_mode() {
+                    let rows = result.clone().into_values();
+                    log!(
+                        level,
+                        "[rb] [{}] <= len={},rows={}",
+                        task_id,
+                        rows.len(),
+                        RbsValueDisplay::new(&rows, self)
+                    );
+                } else {
+                    log!(level, "[rb] [{}] <= len={}", task_id, result.len());
+                }
+            }
+            Err(e) => {
+                log!(level, "[rb] [{}] <= {}", task_id, e);
+            }
+        }
+        Ok(Some(true))
+    }
+
+    async fn before_batch(
+        &self,
+        task_id: i64,
+        _rb: &dyn Executor,
+        sql: &mut String,
+        args: &mut Vec<Vec<Value>>,
+        _result: &mut Result<BatchExecResult, Error>,
+    ) -> Result<Option<bool>, Error> {
+        if self.get_level_filter() == LevelFilter::Off {
+            return Ok(Some(true));
+        }
+        let level = self.to_level().unwrap_or(Level::Debug);
+        log!(level, "[rb] [{}] => `{}` batch={}", task_id, &sql, args.len());
+        Ok(Some(true))
+    }
+
+    async fn after_batch(
+        &self,
+        task_id: i64,
+        _rb: &dyn Executor,
+        _sql: &mut String,
+        _args: &mut Vec<Vec<Value>>,
+        result: &mut Result<BatchExecResult, Error>,
+    ) -> Result<Option<bool>, Error> {
+        if self.get_level_filter() == LevelFilter::Off {
+            return Ok(Some(true));
+        }
+        let level = self.to_level().unwrap_or(Level::Debug);
+        match result {
+            Ok(result) => {
+                log!(
+                    level,
+                    "[rb] [{}] <= rows_affected={},batch={}",
+                    task_id,
+                    result.rows_affected,
+                    result.results.len()
+                );
+            }
+            Err(e) => {
+                log!(level, "[rb] [{}] <= {}", task_id, e);
+            }
+        }
+        Ok(Some(true))
+    }
 }
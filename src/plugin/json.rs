@@ -0,0 +1,257 @@
+use rbs::Value;
+use serde::de::{DeserializeOwned, Error as DeError};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt::{Debug, Display, Formatter};
+use std::ops::{Deref, DerefMut};
+
+/// a json column decoded to `T`.
+///
+/// it is serialized as the newtype struct `JsonValue`(see `JSON_VALUE_EXT`), so `rbs::to_value` keeps the marker
+/// `Value::Ext("JsonValue", value)` and other serializers(for example serde_json) pass through it and serialize the `T`.
+/// the executors encode the marker to `Value::Ext("Json", json_string)` before the driver, so mysql,pg and sqlite
+/// bind it as the json column(and table_sync creates the json column).
+/// it is decoded from the json string(mysql,sqlite text/blob), the json value(pg) or the `Ext` value.
+///```rust
+/// use rbatis::json::Json;
+/// #[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+/// pub struct Attrs {
+///     pub color: Option<String>,
+///     pub size: Option<i32>,
+/// }
+/// #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+/// pub struct Goods {
+///     pub id: Option<String>,
+///     pub attrs: Option<Json<Attrs>>,
+/// }
+/// let v = rbs::to_value!(Json(Attrs { color: Some("red".to_string()), size: None }));
+/// assert_eq!(v, rbs::Value::Ext("JsonValue", Box::new(rbs::to_value! {"color": "red", "size": rbs::Value::Null})));
+/// assert_eq!(rbatis::json::encode_json_value(v), rbs::Value::Ext("Json", Box::new(rbs::Value::String(r#"{"color":"red","size":null}"#.to_string()))));
+/// let attrs: Json<Attrs> = rbs::from_value(rbs::Value::String(r#"{"color":"red"}"#.to_string())).unwrap();
+/// assert_eq!(attrs.color.as_deref(), Some("red"));
+/// ```
+#[derive(Clone, Default, Eq, PartialEq, Hash)]
+pub struct Json<T>(pub T);
+
+/// the newtype name(and the `Value::Ext` type) of a serialized `Json<T>`
+pub const JSON_VALUE_EXT: &str = "JsonValue";
+
+/// encode the `Ext("JsonValue", value)` of `Json<T>` to the `Ext("Json", json_string)` of the drivers,
+/// the items of arrays and maps(for example the values of `in (...)`) are encoded too,
+/// other values are returned as is
+pub fn encode_json_value(v: Value) -> Value {
+    match v {
+        Value::Ext(JSON_VALUE_EXT, v) => match serde_json::to_string(&*v) {
+            Ok(s) => Value::Ext("Json", Box::new(Value::String(s))),
+            Err(_) => *v,
+        },
+        mut v @ (Value::Array(_) | Value::Map(_)) => {
+            encode_json_nested(&mut v);
+            v
+        }
+        v => v,
+    }
+}
+
+/// encode the `Json<T>` args of a sql, see `encode_json_value`
+pub fn encode_json_args(args: &mut [Value]) {
+    for arg in args {
+        encode_json_nested(arg);
+    }
+}
+
+/// encode in place, only the values contain a `Json<T>` are rebuilt
+fn encode_json_nested(v: &mut Value) {
+    match v {
+        Value::Ext(JSON_VALUE_EXT, _) => *v = encode_json_value(std::mem::take(v)),
+        Value::Array(arr) => arr.iter_mut().for_each(encode_json_nested),
+        Value::Map(m) => {
+            for (_, v) in m.0.iter_mut() {
+                encode_json_nested(v);
+            }
+        }
+        _ => {}
+    }
+}
+
+impl<T> Json<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T: DeserializeOwned> Json<T> {
+    /// decode the json column value(json string, json value or `Ext("Json")`)
+    pub fn from_value(v: Value) -> Result<Self, crate::Error> {
+        match v {
+            Value::Ext(JSON_VALUE_EXT, v) => Ok(Json(rbs::from_value(*v)?)),
+            Value::Ext(_, v) => Self::from_value(*v),
+            Value::String(s) => match serde_json::from_str(&s) {
+                Ok(v) => Ok(Json(v)),
+                //not a json string, for example the `T` is String and the column is the unquoted text
+                Err(e) => rbs::from_value(Value::String(s)).map(Json).map_err(|_| {
+                    crate::Error::from(format!("[rb] decode json column fail: {}", e))
+                }),
+            },
+            Value::Binary(b) => match String::from_utf8(b) {
+                Ok(s) => Self::from_value(Value::String(s)),
+                Err(e) => Err(crate::Error::from(format!(
+                    "[rb] decode json column fail: {}",
+                    e
+                ))),
+            },
+            v => Ok(Json(rbs::from_value(v)?)),
+        }
+    }
+}
+
+impl<T> Deref for Json<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Json<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T: Debug> Debug for Json<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<T: Serialize> Display for Json<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let s = serde_json::to_string(&self.0).map_err(|_| std::fmt::Error)?;
+        f.write_str(&s)
+    }
+}
+
+impl<T> From<T> for Json<T> {
+    fn from(value: T) -> Self {
+        Self(value)
+    }
+}
+
+impl<T: Serialize> Serialize for Json<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_newtype_struct(JSON_VALUE_EXT, &self.0)
+    }
+}
+
+impl<'de, T: DeserializeOwned> Deserialize<'de> for Json<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let v = Value::deserialize(deserializer)?;
+        Self::from_value(v).map_err(D::Error::custom)
+    }
+}
+
+/// the json path sql of the driver, use it in py_sql/html_sql by the `driver_type` arg
+///```rust
+/// use rbatis::json::JsonPath;
+/// use rbatis::executor::Executor;
+/// use rbatis::{py_sql, Error};
+/// use rbs::Value;
+///
+/// #[py_sql(
+///     "`select * from goods where ${driver_type.json_path('attrs','$.color')} = #{color}`"
+/// )]
+/// async fn select_by_color(rb: &dyn Executor, color: &str) -> Result<Value, Error> {
+///     impled!()
+/// }
+/// assert_eq!("postgres".json_path("attrs", "$.color"), "attrs->>'color'");
+/// assert_eq!("mysql".json_path("attrs", "$.color"), "JSON_UNQUOTE(JSON_EXTRACT(attrs,'$.color'))");
+/// assert_eq!("sqlite".json_path("attrs", "$.color"), "json_extract(attrs,'$.color')");
+/// ```
+pub trait JsonPath {
+    /// the text(the json string unquoted) at `path`(`$.a.b[0]` or `a.b[0]`) of the json `column`
+    fn json_path(&self, column: &str, path: &str) -> String;
+}
+
+impl JsonPath for str {
+    fn json_path(&self, column: &str, path: &str) -> String {
+        let steps = path_steps(path);
+        match self {
+            "pg" | "postgres" => {
+                if steps.is_empty() {
+                    return format!("{}#>>'{{}}'", column);
+                }
+                let mut sql = column.to_string();
+                for (idx, step) in steps.iter().enumerate() {
+                    sql.push_str(if idx + 1 == steps.len() { "->>" } else { "->" });
+                    match step {
+                        PathStep::Key(k) => sql.push_str(&format!("'{}'", k.replace('\'', "''"))),
+                        PathStep::Index(i) => sql.push_str(&i.to_string()),
+                    }
+                }
+                sql
+            }
+            "mysql" => format!(
+                "JSON_UNQUOTE(JSON_EXTRACT({},'{}'))",
+                column,
+                standard_path(&steps)
+            ),
+            "mssql" => format!("JSON_VALUE({},'{}')", column, standard_path(&steps)),
+            _ => format!("json_extract({},'{}')", column, standard_path(&steps)),
+        }
+    }
+}
+
+impl JsonPath for String {
+    fn json_path(&self, column: &str, path: &str) -> String {
+        self.as_str().json_path(column, path)
+    }
+}
+
+impl JsonPath for Value {
+    fn json_path(&self, column: &str, path: &str) -> String {
+        self.as_str().unwrap_or_default().json_path(column, path)
+    }
+}
+
+enum PathStep {
+    Key(String),
+    Index(usize),
+}
+
+fn path_steps(path: &str) -> Vec<PathStep> {
+    let path = path.trim();
+    let path = path.strip_prefix('$').unwrap_or(path);
+    let mut steps = vec![];
+    for part in path.split('.') {
+        let mut part = part;
+        if let Some(idx) = part.find('[') {
+            if idx != 0 {
+                steps.push(PathStep::Key(part[..idx].to_string()));
+            }
+            part = &part[idx..];
+            for index in part.split('[').filter(|v| !v.is_empty()) {
+                if let Ok(index) = index.trim_end_matches(']').trim().parse() {
+                    steps.push(PathStep::Index(index));
+                }
+            }
+        } else if !part.is_empty() {
+            steps.push(PathStep::Key(part.to_string()));
+        }
+    }
+    steps
+}
+
+/// the `$.a.b[0]` path of mysql,sqlite and mssql
+fn standard_path(steps: &[PathStep]) -> String {
+    let mut path = "$".to_string();
+    for step in steps {
+        match step {
+            PathStep::Key(k) => {
+                path.push('.');
+                path.push_str(&k.replace('\'', "''"));
+            }
+            PathStep::Index(i) => path.push_str(&format!("[{}]", i)),
+        }
+    }
+    path
+}
@@ -1,9 +1,23 @@
+pub mod batch;
+pub mod bulk;
+pub mod column;
+pub mod db_enum;
+pub mod hooks;
 pub mod intercept;
+pub mod intercept_data_scope;
+pub mod intercept_encrypt;
+pub mod intercept_guard;
 pub mod intercept_log;
 pub mod intercept_page;
+pub mod json;
+pub mod naming;
 pub mod object_id;
 pub mod page;
+pub mod relation;
+pub mod returning;
 pub mod snowflake;
 pub mod table_sync;
+pub mod tracked;
+pub mod wrapper;
 
 pub use page::*;
@@ -0,0 +1,283 @@
+use crate::decode::Rows;
+use crate::executor::Executor;
+use parking_lot::RwLock;
+use rbs::value::map::ValueMap;
+use rbs::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// the case of database column names, the struct fields are `snake_case`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NameCase {
+    /// column name is the field name
+    #[default]
+    Exact,
+    /// `userName`
+    Camel,
+    /// `UserName`
+    Pascal,
+    /// `USER_NAME`
+    UpperSnake,
+}
+
+/// the mapping of column names and struct field names.
+///
+/// * decode: the column of rows is renamed to the field name before decode
+///   (the py_sql methods of `impl_select!` select `Column as field` instead)
+/// * insert/update: the field of table is renamed to the column name
+///
+/// it is used by the `crud!` insert/update/select/delete methods, the column arguments and the
+/// `QueryWrapper` columns of them are field names mapped to column names(see `QueryWrapper::with_naming`).
+/// raw sql(py_sql, html_sql and the where sql of `impl_select!`) is not changed.
+/// ```rust
+/// use rbatis::naming::{NameCase, NamingStrategy};
+/// let rb = rbatis::RBatis::new();
+/// //all tables
+/// rb.naming.set_default(NamingStrategy::new(NameCase::Pascal));
+/// //one table
+/// rb.naming.set_table(
+///     "legacy_user",
+///     NamingStrategy::new(NameCase::UpperSnake)
+///         .ignore_case()
+///         .rename("name", "USER_NM"),
+/// );
+/// assert_eq!(rb.naming_of("legacy_user").to_column("name"), "USER_NM");
+/// assert_eq!(rb.naming_of("activity").to_column("create_time"), "CreateTime");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct NamingStrategy {
+    pub case: NameCase,
+    /// decode: match the column to a field ignoring ascii case
+    pub ignore_case: bool,
+    /// explicit (field, column) names, before the case conversion
+    pub renames: Vec<(String, String)>,
+}
+
+impl NamingStrategy {
+    pub fn new(case: NameCase) -> Self {
+        Self {
+            case,
+            ignore_case: false,
+            renames: vec![],
+        }
+    }
+
+    pub fn ignore_case(mut self) -> Self {
+        self.ignore_case = true;
+        self
+    }
+
+    /// map the field to an explicit column name
+    pub fn rename(mut self, field: &str, column: &str) -> Self {
+        self.renames.push((field.to_string(), column.to_string()));
+        self
+    }
+
+    /// column names are field names
+    pub fn is_exact(&self) -> bool {
+        self.case == NameCase::Exact && !self.ignore_case && self.renames.is_empty()
+    }
+
+    /// the column name of a field
+    pub fn to_column(&self, field: &str) -> String {
+        if let Some((_, column)) = self.renames.iter().find(|(f, _)| f == field) {
+            return column.clone();
+        }
+        match self.case {
+            NameCase::Exact => field.to_string(),
+            NameCase::Camel => to_camel(field, false),
+            NameCase::Pascal => to_camel(field, true),
+            NameCase::UpperSnake => field.to_ascii_uppercase(),
+        }
+    }
+
+    /// the field name of a column, `fields` are the serialized fields of the table
+    /// (used by `ignore_case`, see `utils::table_util::table_columns`)
+    pub fn to_field(&self, column: &str, fields: &[&str]) -> String {
+        if let Some((field, _)) = self.renames.iter().find(|(_, c)| {
+            if self.ignore_case {
+                c.eq_ignore_ascii_case(column)
+            } else {
+                c == column
+            }
+        }) {
+            return field.clone();
+        }
+        let field = match self.case {
+            NameCase::Exact => column.to_string(),
+            NameCase::Camel | NameCase::Pascal => to_snake(column),
+            NameCase::UpperSnake => column.to_ascii_lowercase(),
+        };
+        if self.ignore_case && !fields.contains(&field.as_str()) {
+            if let Some(v) = fields.iter().find(|v| v.eq_ignore_ascii_case(&field)) {
+                return v.to_string();
+            }
+        }
+        field
+    }
+
+    /// the select columns `Column as field` of the fields, so the rows are decoded without renaming.
+    /// `*` if the naming is exact or the fields are empty
+    pub fn select_columns(&self, fields: &[&str]) -> String {
+        if self.is_exact() || fields.is_empty() {
+            return "*".to_string();
+        }
+        fields
+            .iter()
+            .map(|field| format!("{} as {}", self.to_column(field), field))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    /// rename the fields of a table(map) or tables(array of map) to column names
+    pub fn to_columns(&self, v: Value) -> Value {
+        if self.is_exact() {
+            return v;
+        }
+        match v {
+            Value::Array(arr) => {
+                Value::Array(arr.into_iter().map(|v| self.to_columns(v)).collect())
+            }
+            Value::Map(m) => {
+                let mut map = ValueMap::with_capacity(m.len());
+                for (k, v) in m {
+                    let column = self.to_column(k.as_str().unwrap_or_default());
+                    map.insert(Value::String(column), v);
+                }
+                Value::Map(map)
+            }
+            v => v,
+        }
+    }
+
+    /// rename the columns of `Rows` to field names
+    pub fn to_fields_rows(&self, mut rows: Rows, fields: &[&str]) -> Rows {
+        if !self.is_exact() {
+            for column in rows.columns.iter_mut() {
+                *column = self.to_field(column, fields);
+            }
+        }
+        rows
+    }
+
+    /// rename the columns of a row(map) or rows(array of map) to field names
+    pub fn to_fields(&self, v: Value, fields: &[&str]) -> Value {
+        if self.is_exact() {
+            return v;
+        }
+        match v {
+            Value::Array(arr) => {
+                Value::Array(arr.into_iter().map(|v| self.to_fields(v, fields)).collect())
+            }
+            Value::Map(m) => {
+                let mut map = ValueMap::with_capacity(m.len());
+                for (k, v) in m {
+                    let field = self.to_field(k.as_str().unwrap_or_default(), fields);
+                    map.insert(Value::String(field), v);
+                }
+                Value::Map(map)
+            }
+            v => v,
+        }
+    }
+}
+
+/// the naming strategies of `RBatis`(`RBatis::naming`), shared by the clones of `RBatis`
+#[derive(Debug, Default)]
+pub struct NamingConfig {
+    default: RwLock<Arc<NamingStrategy>>,
+    tables: RwLock<HashMap<String, Arc<NamingStrategy>>>,
+}
+
+impl NamingConfig {
+    /// set the naming of all tables
+    pub fn set_default(&self, naming: NamingStrategy) {
+        *self.default.write() = Arc::new(naming);
+    }
+
+    /// set the naming of a table, override the default naming
+    pub fn set_table(&self, table_name: &str, naming: NamingStrategy) {
+        self.tables
+            .write()
+            .insert(table_name.to_string(), Arc::new(naming));
+    }
+
+    /// remove the naming of a table
+    pub fn remove_table(&self, table_name: &str) -> Option<Arc<NamingStrategy>> {
+        self.tables.write().remove(table_name)
+    }
+
+    /// the naming of a table, or the default naming
+    pub fn naming_of(&self, table_name: &str) -> Arc<NamingStrategy> {
+        match self.tables.read().get(table_name) {
+            Some(v) => v.clone(),
+            None => self.default.read().clone(),
+        }
+    }
+}
+
+/// the naming strategy of a table, see `RBatis::naming_of`
+pub fn naming_of(executor: &dyn Executor, table_name: &str) -> Arc<NamingStrategy> {
+    executor.rb_ref().naming_of(table_name)
+}
+
+/// `user_name` => `userName` or `UserName`
+fn to_camel(name: &str, upper_first: bool) -> String {
+    let mut s = String::with_capacity(name.len());
+    let mut upper = upper_first;
+    for c in name.chars() {
+        if c == '_' {
+            upper = !s.is_empty();
+            continue;
+        }
+        if upper {
+            s.push(c.to_ascii_uppercase());
+            upper = false;
+        } else {
+            s.push(c);
+        }
+    }
+    s
+}
+
+/// `userName`,`UserName`,`USERName` => `user_name`
+fn to_snake(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut s = String::with_capacity(name.len() + 4);
+    for (i, c) in chars.iter().enumerate() {
+        if c.is_ascii_uppercase() {
+            let prev_lower =
+                i > 0 && (chars[i - 1].is_ascii_lowercase() || chars[i - 1].is_ascii_digit());
+            let next_lower = i > 0
+                && chars[i - 1].is_ascii_uppercase()
+                && chars.get(i + 1).is_some_and(|v| v.is_ascii_lowercase());
+            if (prev_lower || next_lower) && !s.ends_with('_') {
+                s.push('_');
+            }
+            s.push(c.to_ascii_lowercase());
+        } else {
+            s.push(*c);
+        }
+    }
+    s
+}
+
+#[cfg(test)]
+mod test {
+    use crate::naming::{to_camel, to_snake, NameCase, NamingStrategy};
+
+    #[test]
+    fn test_case() {
+        assert_eq!(to_camel("user_name", true), "UserName");
+        assert_eq!(to_camel("user_name", false), "userName");
+        assert_eq!(to_snake("UserName"), "user_name");
+        assert_eq!(to_snake("userName"), "user_name");
+        assert_eq!(to_snake("HTTPCode"), "http_code");
+        assert_eq!(to_snake("Address2Line"), "address2_line");
+        let naming = NamingStrategy::new(NameCase::UpperSnake).ignore_case();
+        assert_eq!(naming.to_column("create_time"), "CREATE_TIME");
+        assert_eq!(naming.to_field("CREATE_TIME", &[]), "create_time");
+        let naming = NamingStrategy::new(NameCase::Exact).ignore_case();
+        assert_eq!(naming.to_field("ID", &["id", "name"]), "id");
+    }
+}
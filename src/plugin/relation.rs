@@ -0,0 +1,242 @@
+use crate::executor::Executor;
+use crate::naming::naming_of;
+use crate::wrapper::QueryWrapper;
+use crate::Error;
+use rbs::Value;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+/// the key of a relation column value, `None` if the value is null.
+/// a number and a string with the same text are the same key
+pub fn relation_key<T: Serialize>(v: &T) -> Option<String> {
+    value_key(&rbs::to_value!(v))
+}
+
+fn value_key(v: &Value) -> Option<String> {
+    match v {
+        Value::Null => None,
+        Value::String(v) => Some(v.clone()),
+        Value::Ext(_, v) => value_key(v),
+        _ => Some(v.to_string()),
+    }
+}
+
+/// the distinct not null values of a column, in order
+pub fn relation_keys<'a, T: Serialize + 'a, I: IntoIterator<Item = &'a T>>(
+    values: I,
+) -> Vec<Value> {
+    let mut set = HashSet::new();
+    let mut keys = vec![];
+    for v in values {
+        let v = rbs::to_value!(v);
+        if let Some(key) = value_key(&v) {
+            if set.insert(key) {
+                keys.push(v);
+            }
+        }
+    }
+    keys
+}
+
+/// group rows by key, the order of rows is kept
+pub fn group_many<T, F: Fn(&T) -> Option<String>>(rows: Vec<T>, key: F) -> HashMap<String, Vec<T>> {
+    let mut map: HashMap<String, Vec<T>> = HashMap::new();
+    for row in rows {
+        if let Some(k) = key(&row) {
+            map.entry(k).or_default().push(row);
+        }
+    }
+    map
+}
+
+/// map rows by key, the first row of a key is kept
+pub fn group_one<T, F: Fn(&T) -> Option<String>>(rows: Vec<T>, key: F) -> HashMap<String, T> {
+    let mut map = HashMap::with_capacity(rows.len());
+    for row in rows {
+        if let Some(k) = key(&row) {
+            map.entry(k).or_insert(row);
+        }
+    }
+    map
+}
+
+/// select `(left key, right value)` of a many-to-many join table where `left in keys`.
+/// the columns are mapped by the naming of the join table, the keys are split by the bind parameter limit
+pub async fn select_join(
+    executor: &dyn Executor,
+    join_table: &str,
+    left_column: &str,
+    right_column: &str,
+    keys: &[Value],
+) -> Result<Vec<(String, Value)>, Error> {
+    let mut pairs = vec![];
+    if keys.is_empty() {
+        return Ok(pairs);
+    }
+    let naming = naming_of(executor, join_table);
+    let left_column = naming.to_column(left_column);
+    let right_column = naming.to_column(right_column);
+    let (left_column, right_column) = (left_column.as_str(), right_column.as_str());
+    let driver_type = executor.driver_type().unwrap_or_default();
+    let ranges = executor.rb_ref().batch_limit.ranges(
+        driver_type,
+        keys.len(),
+        0,
+        1,
+        64 + join_table.len() + left_column.len() + right_column.len(),
+        2,
+    );
+    for (offset, limit) in ranges {
+        let (sql, args) = QueryWrapper::new()
+            .select(&[left_column, right_column])
+            .in_(left_column, &keys[offset..limit])
+            .to_select_sql(driver_type, join_table)?;
+        if let Value::Array(rows) = executor.query(&sql, args).await? {
+            for row in rows {
+                if let Some(left) = value_key(&row[left_column]) {
+                    let right = row[right_column].clone();
+                    if !right.is_null() {
+                        pairs.push((left, right));
+                    }
+                }
+            }
+        }
+    }
+    Ok(pairs)
+}
+
+/// gen relation loading methods, every relation is loaded by one `in` query(split by the bind
+/// parameter limit) for all parents, not one query per parent.
+///
+/// * `field: has_many(Child, child_fk => parent_key) as map_fn`: `Vec<Child>` where `child.child_fk = parent.parent_key`
+/// * `field: belongs_to(Target, parent_fk => target_key) as map_fn`: `Option<Target>` where `parent.parent_fk = target.target_key`
+/// * `field: many_to_many(Target, "join_table"(join_parent_fk => parent_key, join_target_fk => target_key)) as map_fn`: `Vec<Target>`
+///
+/// `map_fn(executor, &parents)` returns the grouped map(key is the text of `parent_key` or `target_key`),
+/// `load_relations(executor, &mut parents)` loads all relations into the parents' fields.
+/// the target tables must impl `select_in_column`(crud!/impl_select!), the column of a key field is the field name
+/// mapped by the naming of the table(see `rbatis::naming`), and the relation fields should be `#[serde(skip)]`.
+/// a parent can appear more than once, every one gets the relation rows
+///```rust
+/// #[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+/// pub struct Dept { pub id: Option<i64> }
+/// #[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+/// pub struct Order { pub id: Option<i64>, pub user_id: Option<i64> }
+/// #[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+/// pub struct Role { pub id: Option<i64> }
+/// #[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+/// pub struct User {
+///     pub id: Option<i64>,
+///     pub dept_id: Option<i64>,
+///     #[serde(skip)]
+///     pub orders: Vec<Order>,
+///     #[serde(skip)]
+///     pub dept: Option<Dept>,
+///     #[serde(skip)]
+///     pub roles: Vec<Role>,
+/// }
+/// rbatis::crud!(Dept{});
+/// rbatis::crud!(Order{});
+/// rbatis::crud!(Role{});
+/// rbatis::crud!(User{});
+/// rbatis::impl_relation!(User {
+///     orders: has_many(Order, user_id => id) as orders_map,
+///     dept: belongs_to(Dept, dept_id => id) as dept_map,
+///     roles: many_to_many(Role, "user_role"(user_id => id, role_id => id)) as roles_map,
+/// });
+///
+/// async fn test_use(rb: &rbatis::RBatis) -> Result<(), rbatis::Error> {
+///     let mut users = User::select_all(rb).await?;
+///     //3 queries(4 with the join table), not 1 + 3 * users.len()
+///     User::load_relations(rb, &mut users).await?;
+///     //or only the grouped map, key is the user id
+///     let orders = User::orders_map(rb, &users).await?;
+///     Ok(())
+/// }
+/// ```
+#[macro_export]
+macro_rules! impl_relation {
+    ($parent:ty { $($field:ident : $kind:ident ($($args:tt)*) as $map_fn:ident),* $(,)? }) => {
+        impl $parent {
+            $($crate::impl_relation!(@map $parent, $kind, $map_fn, $($args)*);)*
+
+            /// load all relations into the fields of parents
+            pub async fn load_relations(
+                executor: &dyn $crate::executor::Executor,
+                parents: &mut [$parent],
+            ) -> std::result::Result<(), $crate::rbdc::Error> {
+                $($crate::impl_relation!(@load $kind, executor, parents, $field, $map_fn, $($args)*);)*
+                Ok(())
+            }
+        }
+    };
+    (@map $parent:ty, has_many, $map_fn:ident, $child:ty, $child_fk:ident => $parent_key:ident) => {
+        /// children grouped by the parent key
+        pub async fn $map_fn(
+            executor: &dyn $crate::executor::Executor,
+            parents: &[$parent],
+        ) -> std::result::Result<std::collections::HashMap<String, Vec<$child>>, $crate::rbdc::Error> {
+            let keys = $crate::relation::relation_keys(parents.iter().map(|v| &v.$parent_key));
+            if keys.is_empty() {
+                return Ok(std::collections::HashMap::new());
+            }
+            let children = <$child>::select_in_column(executor, stringify!($child_fk), &keys).await?;
+            Ok($crate::relation::group_many(children, |v| $crate::relation::relation_key(&v.$child_fk)))
+        }
+    };
+    (@map $parent:ty, belongs_to, $map_fn:ident, $target:ty, $parent_fk:ident => $target_key:ident) => {
+        /// targets mapped by the target key
+        pub async fn $map_fn(
+            executor: &dyn $crate::executor::Executor,
+            parents: &[$parent],
+        ) -> std::result::Result<std::collections::HashMap<String, $target>, $crate::rbdc::Error> {
+            let keys = $crate::relation::relation_keys(parents.iter().map(|v| &v.$parent_fk));
+            if keys.is_empty() {
+                return Ok(std::collections::HashMap::new());
+            }
+            let targets = <$target>::select_in_column(executor, stringify!($target_key), &keys).await?;
+            Ok($crate::relation::group_one(targets, |v| $crate::relation::relation_key(&v.$target_key)))
+        }
+    };
+    (@map $parent:ty, many_to_many, $map_fn:ident, $target:ty, $join_table:literal($join_parent_fk:ident => $parent_key:ident, $join_target_fk:ident => $target_key:ident)) => {
+        /// targets grouped by the parent key
+        pub async fn $map_fn(
+            executor: &dyn $crate::executor::Executor,
+            parents: &[$parent],
+        ) -> std::result::Result<std::collections::HashMap<String, Vec<$target>>, $crate::rbdc::Error> {
+            let mut map = std::collections::HashMap::new();
+            let keys = $crate::relation::relation_keys(parents.iter().map(|v| &v.$parent_key));
+            let pairs = $crate::relation::select_join(executor, $join_table, stringify!($join_parent_fk), stringify!($join_target_fk), &keys).await?;
+            let target_keys = $crate::relation::relation_keys(pairs.iter().map(|(_, v)| v));
+            if target_keys.is_empty() {
+                return Ok(map);
+            }
+            let targets = <$target>::select_in_column(executor, stringify!($target_key), &target_keys).await?;
+            let targets = $crate::relation::group_one(targets, |v| $crate::relation::relation_key(&v.$target_key));
+            for (key, target_key) in pairs {
+                if let Some(target) = $crate::relation::relation_key(&target_key).and_then(|v| targets.get(&v)) {
+                    map.entry(key).or_insert_with(Vec::new).push(target.clone());
+                }
+            }
+            Ok(map)
+        }
+    };
+    (@load has_many, $executor:ident, $parents:ident, $field:ident, $map_fn:ident, $child:ty, $child_fk:ident => $parent_key:ident) => {
+        let map = Self::$map_fn($executor, $parents).await?;
+        for parent in $parents.iter_mut() {
+            parent.$field = $crate::relation::relation_key(&parent.$parent_key).and_then(|v| map.get(&v).cloned()).unwrap_or_default();
+        }
+    };
+    (@load belongs_to, $executor:ident, $parents:ident, $field:ident, $map_fn:ident, $target:ty, $parent_fk:ident => $target_key:ident) => {
+        let map = Self::$map_fn($executor, $parents).await?;
+        for parent in $parents.iter_mut() {
+            parent.$field = $crate::relation::relation_key(&parent.$parent_fk).and_then(|v| map.get(&v).cloned());
+        }
+    };
+    (@load many_to_many, $executor:ident, $parents:ident, $field:ident, $map_fn:ident, $target:ty, $join_table:literal($join_parent_fk:ident => $parent_key:ident, $join_target_fk:ident => $target_key:ident)) => {
+        let map = Self::$map_fn($executor, $parents).await?;
+        for parent in $parents.iter_mut() {
+            parent.$field = $crate::relation::relation_key(&parent.$parent_key).and_then(|v| map.get(&v).cloned()).unwrap_or_default();
+        }
+    };
+}
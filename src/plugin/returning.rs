@@ -0,0 +1,184 @@
+use crate::executor::Executor;
+use crate::Error;
+use rbs::Value;
+
+/// insert rows and return the inserted rows(`only_id` = false) or the ids(`only_id` = true) in input order.
+///
+/// | driver | sql |
+/// |---|---|
+/// | postgres/sqlite(3.35+) | `insert into t (a,b) values (?,?) returning *` |
+/// | mssql | `insert into t (a,b) output inserted.* values (?,?)` |
+/// | mysql | `insert into t (a,b) values (?,?)`, then the ids are `last_insert_id`..`last_insert_id + n` (or the id of the row), then `select * from t where id in (?,?)` |
+///
+/// * tables: an array of table maps, for example `rbs::to_value!(&tables)`
+/// * id_column: the id column used by mysql fallback and `only_id`
+///
+/// the mysql fallback assumes the auto increment ids of one multi-row insert are consecutive
+/// (`innodb_autoinc_lock_mode` 0 or 1, or 2 without concurrent inserts), otherwise the rows of other ids are selected.
+/// mssql does not guarantee the order of the `output` rows, so the rows of mssql may be not in input order.
+pub async fn insert_returning(
+    executor: &dyn Executor,
+    table_name: &str,
+    tables: Value,
+    batch_size: u64,
+    id_column: &str,
+    only_id: bool,
+) -> Result<Vec<Value>, Error> {
+    let rows = match tables {
+        Value::Array(v) => v,
+        _ => return Err(Error::from("[rb] insert_returning tables must be an array")),
+    };
+    if rows.is_empty() {
+        return Err(Error::from("insert can not insert empty array tables!"));
+    }
+    if !is_valid_name(id_column) {
+        return Err(Error::from(format!(
+            "[rb] insert_returning invalid id column: `{}`",
+            id_column
+        )));
+    }
+    let driver_type = executor.driver_type()?.to_string();
+    let mut result = Vec::with_capacity(rows.len());
+    let item_params = rows[0].len();
+    let ranges = executor.rb_ref().batch_limit.ranges(
+        &driver_type,
+        rows.len(),
+        batch_size,
+        item_params,
+        64 + table_name.len() + item_params * 16,
+        item_params * 2 + 2,
+    );
+    for (offset, limit) in ranges {
+        let batch = &rows[offset..limit];
+        let returning = if only_id { id_column } else { "*" };
+        match driver_type.as_str() {
+            "mysql" => {
+                let (sql, args) = insert_sql(table_name, batch, "", "")?;
+                let exec_result = executor.exec(&sql, args).await?;
+                let mut next_id = exec_result.last_insert_id.as_u64().unwrap_or_default();
+                let mut ids = Vec::with_capacity(batch.len());
+                for row in batch {
+                    let id = &row[id_column];
+                    if id.is_null() {
+                        ids.push(Value::U64(next_id));
+                        next_id += 1;
+                    } else {
+                        ids.push(id.clone());
+                    }
+                }
+                if only_id {
+                    result.extend(ids);
+                    continue;
+                }
+                let sql = format!(
+                    "select * from {} where {} in ({})",
+                    table_name,
+                    id_column,
+                    vec!["?"; ids.len()].join(",")
+                );
+                let selected = match executor.query(&sql, ids.clone()).await? {
+                    Value::Array(v) => v,
+                    _ => vec![],
+                };
+                for id in &ids {
+                    if let Some(row) = selected
+                        .iter()
+                        .find(|row| id_key(&row[id_column]) == id_key(id))
+                    {
+                        result.push(row.clone());
+                    }
+                }
+            }
+            "mssql" => {
+                let output = if only_id {
+                    format!(" output inserted.{}", id_column)
+                } else {
+                    " output inserted.*".to_string()
+                };
+                let (sql, args) = insert_sql(table_name, batch, &output, "")?;
+                result.extend(query_rows(executor, &sql, args, id_column, only_id).await?);
+            }
+            _ => {
+                let (sql, args) =
+                    insert_sql(table_name, batch, "", &format!(" returning {}", returning))?;
+                result.extend(query_rows(executor, &sql, args, id_column, only_id).await?);
+            }
+        }
+    }
+    Ok(result)
+}
+
+async fn query_rows(
+    executor: &dyn Executor,
+    sql: &str,
+    args: Vec<Value>,
+    id_column: &str,
+    only_id: bool,
+) -> Result<Vec<Value>, Error> {
+    let rows = match executor.query(sql, args).await? {
+        Value::Array(v) => v,
+        _ => vec![],
+    };
+    if only_id {
+        Ok(rows.into_iter().map(|v| v[id_column].clone()).collect())
+    } else {
+        Ok(rows)
+    }
+}
+
+/// `insert into table (columns){output} values (?,?),(?,?){returning}`,
+/// the columns are the non-null columns of all rows
+fn insert_sql(
+    table_name: &str,
+    rows: &[Value],
+    output: &str,
+    returning: &str,
+) -> Result<(String, Vec<Value>), Error> {
+    let mut column_names: Vec<String> = vec![];
+    if let Some(first) = rows.first() {
+        for (column, _) in first {
+            let name = column.as_str().unwrap_or_default();
+            if rows.iter().any(|row| !row[name].is_null()) {
+                if !is_valid_name(name) {
+                    return Err(Error::from(format!(
+                        "[rb] insert_returning invalid column: `{}`",
+                        name
+                    )));
+                }
+                column_names.push(name.to_string());
+            }
+        }
+    }
+    let mut args = Vec::with_capacity(rows.len() * column_names.len());
+    let mut values = Vec::with_capacity(rows.len());
+    for row in rows {
+        for column in &column_names {
+            args.push(row[column.as_str()].clone());
+        }
+        values.push(format!("({})", vec!["?"; column_names.len()].join(",")));
+    }
+    let sql = format!(
+        "insert into {} ({}){} values {}{}",
+        table_name,
+        column_names.join(","),
+        output,
+        values.join(","),
+        returning
+    );
+    Ok((sql, args))
+}
+
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .bytes()
+            .all(|v| v.is_ascii_alphanumeric() || v == b'_' || v == b'.')
+}
+
+/// compare ids of different number types
+fn id_key(id: &Value) -> String {
+    match id {
+        Value::String(v) => v.clone(),
+        _ => id.to_string(),
+    }
+}
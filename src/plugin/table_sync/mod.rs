@@ -3,7 +3,9 @@ pub mod mysql_mapper;
 pub mod pg_mapper;
 pub mod sqlite_mapper;
 
+use crate::db_enum::{to_schema_value, DB_ENUM_EXT};
 use crate::executor::Executor;
+use crate::json::encode_json_value;
 use crate::Error;
 use futures_core::future::BoxFuture;
 use log::debug;
@@ -96,7 +98,7 @@ pub fn sync<'a>(
                 let mut sql_column = format!("");
                 for (k, v) in &m {
                     let k = k.as_str().unwrap_or_default();
-                    let column_type_value = mapper.get_column_type(k, &v);
+                    let column_type_value = column_type(mapper, k, v);
                     sql_column.push_str(k);
                     sql_column.push_str(" ");
                     sql_column.push_str(column_type_value.as_str());
@@ -123,7 +125,7 @@ pub fn sync<'a>(
                                 if k.eq("id") || v.as_str().unwrap_or_default() == "id" {
                                     id_key = &PRIMARY_KEY;
                                 }
-                                let column_type = mapper.get_column_type(k, &v);
+                                let column_type = column_type(mapper, k, v);
                                 match executor
                                     .exec(
                                         &format!(
@@ -153,10 +155,73 @@ pub fn sync<'a>(
     })
 }
 
+/// same as `sync`, but the table is a struct. the `rbatis::DbEnum` fields
+/// are mapped by `ColumnMapper::get_enum_column_type`
+/// ```rust
+/// use rbatis::executor::Executor;
+/// use rbatis::table_sync::{sync_table, MysqlTableMapper};
+///
+/// #[derive(Clone, Copy, Debug, PartialEq, rbatis::DbEnum)]
+/// pub enum Level {
+///     #[db(str = "low")]
+///     Low,
+///     #[db(str = "high")]
+///     High,
+/// }
+/// #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+/// pub struct User {
+///     pub id: String,
+///     pub level: Option<Level>,
+/// }
+///
+/// pub async fn do_sync_table(conn: &dyn Executor) {
+///     let table = User { id: "".to_string(), level: Some(Level::Low) };
+///     //create table user (id TEXT,level VARCHAR(4))
+///     let _ = sync_table(conn, &MysqlTableMapper {}, &table, "user").await;
+/// }
+/// ```
+pub async fn sync_table<T: serde::Serialize>(
+    executor: &dyn Executor,
+    mapper: &dyn ColumnMapper,
+    table: &T,
+    table_name: &str,
+) -> Result<(), Error> {
+    sync(executor, mapper, to_schema_value(table)?, table_name).await
+}
+
+fn column_type(mapper: &dyn ColumnMapper, field: &str, v: &Value) -> String {
+    match v {
+        Value::Ext(DB_ENUM_EXT, values) => match &**values {
+            Value::Array(values) => mapper.get_enum_column_type(values),
+            _ => mapper.get_enum_column_type(&[]),
+        },
+        v => mapper.get_column_type(field, &encode_json_value(v.clone())),
+    }
+}
+
+/// the length of the longest string value of a `rbatis::DbEnum`
+pub fn enum_max_len(values: &[Value]) -> usize {
+    values
+        .iter()
+        .map(|v| v.as_str().unwrap_or_default().chars().count())
+        .max()
+        .unwrap_or_default()
+}
+
 /// Mapper Column and ColumnType
 pub trait ColumnMapper: Sync + Send {
     fn driver_type(&self) -> String;
 
     /// for example input `"id":i32` -> id:INT
     fn get_column_type(&self, field: &str, v: &Value) -> String;
+
+    /// the column type of a `rbatis::DbEnum`, `values` are the values of all variants(all I32, I64 or String).
+    /// the string enum is `VARCHAR(the length of the longest value)`, the int enum is same as `get_column_type`
+    fn get_enum_column_type(&self, values: &[Value]) -> String {
+        match values.first() {
+            Some(Value::String(_)) => format!("VARCHAR({})", enum_max_len(values)),
+            Some(v) => self.get_column_type("", v),
+            None => self.get_column_type("", &Value::Null),
+        }
+    }
 }
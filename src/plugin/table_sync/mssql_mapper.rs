@@ -1,4 +1,4 @@
-use crate::table_sync::ColumnMapper;
+use crate::table_sync::{enum_max_len, ColumnMapper};
 use rbs::Value;
 
 pub struct MssqlTableMapper {}
@@ -42,4 +42,12 @@ impl ColumnMapper for MssqlTableMapper {
             },
         }
     }
+
+    fn get_enum_column_type(&self, values: &[Value]) -> String {
+        match values.first() {
+            Some(Value::String(_)) => format!("NVARCHAR({})", enum_max_len(values)),
+            Some(v) => self.get_column_type("", v),
+            None => self.get_column_type("", &Value::Null),
+        }
+    }
 }
@@ -1,4 +1,4 @@
-use crate::table_sync::ColumnMapper;
+use crate::table_sync::{enum_max_len, ColumnMapper};
 use rbs::Value;
 
 pub struct PGTableMapper {}
@@ -41,4 +41,14 @@ impl ColumnMapper for PGTableMapper {
             },
         }
     }
+
+    fn get_enum_column_type(&self, values: &[Value]) -> String {
+        match values.first() {
+            Some(Value::I32(_)) => "INT4".to_string(),
+            Some(Value::I64(_)) => "INT8".to_string(),
+            Some(Value::String(_)) => format!("VARCHAR({})", enum_max_len(values)),
+            Some(v) => self.get_column_type("", v),
+            None => self.get_column_type("", &Value::Null),
+        }
+    }
 }
@@ -43,4 +43,12 @@ impl ColumnMapper for SqliteTableMapper {
             },
         }
     }
+
+    fn get_enum_column_type(&self, values: &[Value]) -> String {
+        match values.first() {
+            Some(Value::String(_)) => "TEXT".to_string(),
+            Some(v) => self.get_column_type("", v),
+            None => self.get_column_type("", &Value::Null),
+        }
+    }
 }
@@ -0,0 +1,124 @@
+use rbs::value::map::ValueMap;
+use rbs::Value;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt::{Debug, Formatter};
+use std::ops::{Deref, DerefMut};
+
+/// a table with the snapshot(`rbs::Value`) of the loaded fields, it derefs to the table.
+/// `update_changed`(impl_update!/crud!) only sets the changed columns, include the columns changed to null.
+///
+/// it is (de)serialized as the table, so rows can be decoded into `Vec<Tracked<T>>`
+///```rust
+/// use rbatis::tracked::Tracked;
+/// #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+/// pub struct Activity {
+///     pub id: Option<String>,
+///     pub name: Option<String>,
+///     pub remark: Option<String>,
+/// }
+/// rbatis::crud!(Activity{});
+///
+/// async fn test_use(rb: &rbatis::RBatis) -> Result<(), rbatis::Error> {
+///     let mut rows: Vec<Tracked<Activity>> = Activity::select_by_column_tracked(rb, "id", "1").await?;
+///     if let Some(row) = rows.first_mut() {
+///         row.name = Some("new name".to_string());
+///         row.remark = None;
+///         //update activity set name=?,remark=? where id = ?
+///         Activity::update_changed(rb, row, "id").await?;
+///         row.reset();
+///     }
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone)]
+pub struct Tracked<T> {
+    inner: T,
+    snapshot: Value,
+}
+
+impl<T: Serialize> Tracked<T> {
+    /// track the table, the current fields are the snapshot
+    pub fn new(inner: T) -> Self {
+        let snapshot = rbs::to_value!(&inner);
+        Self { inner, snapshot }
+    }
+
+    /// the fields when the table is loaded(or reset)
+    pub fn snapshot(&self) -> &Value {
+        &self.snapshot
+    }
+
+    /// the changed fields and the new values, a field of the snapshot missing in the table is null
+    pub fn changes(&self) -> ValueMap {
+        changes(&self.snapshot, &rbs::to_value!(&self.inner))
+    }
+
+    pub fn is_changed(&self) -> bool {
+        !self.changes().is_empty()
+    }
+
+    /// use the current fields as the snapshot, for example after the update
+    pub fn reset(&mut self) {
+        self.snapshot = rbs::to_value!(&self.inner);
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+/// the fields of `current` not equal to `snapshot`, a missing field is null
+pub fn changes(snapshot: &Value, current: &Value) -> ValueMap {
+    let mut map = ValueMap::new();
+    for (k, v) in snapshot {
+        let current = &current[k.as_str().unwrap_or_default()];
+        if current != v {
+            map.insert(k.clone(), current.clone());
+        }
+    }
+    for (k, v) in current {
+        if &snapshot[k.as_str().unwrap_or_default()] != v {
+            map.insert(k.clone(), v.clone());
+        }
+    }
+    map
+}
+
+impl<T> Deref for Tracked<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<T> DerefMut for Tracked<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+impl<T: Debug> Debug for Tracked<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.inner.fmt(f)
+    }
+}
+
+impl<T: Serialize> From<T> for Tracked<T> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T: Serialize> Serialize for Tracked<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.inner.serialize(serializer)
+    }
+}
+
+impl<'de, T: Serialize + DeserializeOwned> Deserialize<'de> for Tracked<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize(deserializer).map(Self::new)
+    }
+}
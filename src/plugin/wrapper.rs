@@ -0,0 +1,654 @@
+use crate::column::{ColumnName, ColumnValue};
+use crate::executor::Executor;
+use crate::naming::NamingStrategy;
+use crate::utils::table_util::table_columns;
+use crate::Error;
+use rbs::Value;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// row lock mode of `select`, see `QueryWrapper::lock`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LockMode {
+    ForUpdate,
+    ForUpdateNoWait,
+    ForUpdateSkipLocked,
+}
+
+impl LockMode {
+    /// the mssql table hint
+    pub fn mssql_hint(&self) -> &'static str {
+        match self {
+            LockMode::ForUpdate => " with (updlock,rowlock)",
+            LockMode::ForUpdateNoWait => " with (updlock,rowlock,nowait)",
+            LockMode::ForUpdateSkipLocked => " with (updlock,rowlock,readpast)",
+        }
+    }
+
+    /// the `for update` clause
+    pub fn clause(&self) -> &'static str {
+        match self {
+            LockMode::ForUpdate => " for update",
+            LockMode::ForUpdateNoWait => " for update nowait",
+            LockMode::ForUpdateSkipLocked => " for update skip locked",
+        }
+    }
+}
+
+/// runtime condition builder, used by crud! methods `select_by_wrapper`,`update_by_wrapper`,
+/// `delete_by_wrapper`,`count_by_wrapper`.
+///
+/// every value is bind as `?` arg, column names only allow `[a-zA-Z0-9_.]`.
+/// the column can be a `&str` or a typed `Column` generated by `#[derive(rbatis::Columns)]`
+/// ```rust
+/// use rbatis::wrapper::QueryWrapper;
+///
+/// let w = QueryWrapper::new()
+///     .eq("status", 1)
+///     .like("name", "a")
+///     .or(|w| w.in_("id", &[1, 2, 3]).is_null("delete_time"))
+///     .order_by_desc("id")
+///     .limit(10);
+/// let (sql, args) = w.to_select_sql("mysql", "biz_order").unwrap();
+/// assert_eq!(
+///     sql,
+///     "select * from biz_order where status = ? and name like ? or (id in (?,?,?) and delete_time is null) order by id desc limit 10"
+/// );
+/// assert_eq!(args.len(), 5);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct QueryWrapper {
+    /// select columns, empty means `*`
+    pub columns: Vec<String>,
+    /// where sql, without `where`
+    pub where_sql: String,
+    /// args of where sql
+    pub args: Vec<Value>,
+    pub group_by: Vec<String>,
+    /// (column, is asc)
+    pub order_by: Vec<(String, bool)>,
+    pub limit: Option<u64>,
+    /// row lock of select, must be used in a transaction
+    pub lock: Option<LockMode>,
+    /// the first invalid column name
+    pub error: Option<String>,
+}
+
+impl QueryWrapper {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// select columns, default is `*`
+    pub fn select(mut self, columns: &[&str]) -> Self {
+        for column in columns {
+            if *column != "*" {
+                self.check_column(column);
+            }
+            self.columns.push(column.to_string());
+        }
+        self
+    }
+
+    /// select the serialized fields of `T`, used to decode rows into a projection struct.
+    /// do nothing if columns are already selected
+    pub fn select_as<T: DeserializeOwned>(mut self) -> Self {
+        if self.columns.is_empty() {
+            if let Some(columns) = table_columns::<T>() {
+                self.columns = columns.iter().map(|v| v.to_string()).collect();
+            }
+        }
+        self
+    }
+
+    fn check_column(&mut self, column: &str) {
+        if !is_valid_column(column) && self.error.is_none() {
+            self.error = Some(column.to_string());
+        }
+    }
+
+    fn push(mut self, column: &str, condition: String, args: Vec<Value>) -> Self {
+        self.check_column(column);
+        self.push_sql(" and ", &condition);
+        self.args.extend(args);
+        self
+    }
+
+    fn push_sql(&mut self, join: &str, sql: &str) {
+        if !self.where_sql.is_empty() {
+            self.where_sql.push_str(join);
+        }
+        self.where_sql.push_str(sql);
+    }
+
+    /// `column = ?`
+    pub fn eq<C: ColumnValue<V>, V: Serialize>(self, column: C, v: V) -> Self {
+        let column = column.column_name();
+        self.push(column, format!("{} = ?", column), vec![rbs::to_value!(v)])
+    }
+
+    /// `column <> ?`
+    pub fn ne<C: ColumnValue<V>, V: Serialize>(self, column: C, v: V) -> Self {
+        let column = column.column_name();
+        self.push(column, format!("{} <> ?", column), vec![rbs::to_value!(v)])
+    }
+
+    /// `column > ?`
+    pub fn gt<C: ColumnValue<V>, V: Serialize>(self, column: C, v: V) -> Self {
+        let column = column.column_name();
+        self.push(column, format!("{} > ?", column), vec![rbs::to_value!(v)])
+    }
+
+    /// `column >= ?`
+    pub fn ge<C: ColumnValue<V>, V: Serialize>(self, column: C, v: V) -> Self {
+        let column = column.column_name();
+        self.push(column, format!("{} >= ?", column), vec![rbs::to_value!(v)])
+    }
+
+    /// `column < ?`
+    pub fn lt<C: ColumnValue<V>, V: Serialize>(self, column: C, v: V) -> Self {
+        let column = column.column_name();
+        self.push(column, format!("{} < ?", column), vec![rbs::to_value!(v)])
+    }
+
+    /// `column <= ?`
+    pub fn le<C: ColumnValue<V>, V: Serialize>(self, column: C, v: V) -> Self {
+        let column = column.column_name();
+        self.push(column, format!("{} <= ?", column), vec![rbs::to_value!(v)])
+    }
+
+    /// `column between ? and ?`
+    pub fn between<C: ColumnValue<V>, V: Serialize>(self, column: C, start: V, end: V) -> Self {
+        let column = column.column_name();
+        self.push(
+            column,
+            format!("{} between ? and ?", column),
+            vec![rbs::to_value!(start), rbs::to_value!(end)],
+        )
+    }
+
+    /// `column not between ? and ?`
+    pub fn not_between<C: ColumnValue<V>, V: Serialize>(self, column: C, start: V, end: V) -> Self {
+        let column = column.column_name();
+        self.push(
+            column,
+            format!("{} not between ? and ?", column),
+            vec![rbs::to_value!(start), rbs::to_value!(end)],
+        )
+    }
+
+    /// `column like ?`, arg = `%v%`
+    pub fn like<C: ColumnValue<String>>(self, column: C, v: &str) -> Self {
+        let column = column.column_name();
+        self.push(
+            column,
+            format!("{} like ?", column),
+            vec![Value::String(format!("%{}%", v))],
+        )
+    }
+
+    /// `column not like ?`, arg = `%v%`
+    pub fn not_like<C: ColumnValue<String>>(self, column: C, v: &str) -> Self {
+        let column = column.column_name();
+        self.push(
+            column,
+            format!("{} not like ?", column),
+            vec![Value::String(format!("%{}%", v))],
+        )
+    }
+
+    /// `column like ?`, arg = `%v`
+    pub fn like_left<C: ColumnValue<String>>(self, column: C, v: &str) -> Self {
+        let column = column.column_name();
+        self.push(
+            column,
+            format!("{} like ?", column),
+            vec![Value::String(format!("%{}", v))],
+        )
+    }
+
+    /// `column like ?`, arg = `v%`
+    pub fn like_right<C: ColumnValue<String>>(self, column: C, v: &str) -> Self {
+        let column = column.column_name();
+        self.push(
+            column,
+            format!("{} like ?", column),
+            vec![Value::String(format!("{}%", v))],
+        )
+    }
+
+    /// `column in (?,?)`, empty values means always false
+    pub fn in_<C: ColumnValue<V>, V: Serialize>(self, column: C, values: &[V]) -> Self {
+        let column = column.column_name();
+        if values.is_empty() {
+            let mut s = self;
+            s.check_column(column);
+            s.push_sql(" and ", "1 <> 1");
+            return s;
+        }
+        let args: Vec<Value> = values.iter().map(|v| rbs::to_value!(v)).collect();
+        let placeholders = vec!["?"; args.len()].join(",");
+        self.push(column, format!("{} in ({})", column, placeholders), args)
+    }
+
+    /// `column not in (?,?)`, empty values means no limit
+    pub fn not_in<C: ColumnValue<V>, V: Serialize>(self, column: C, values: &[V]) -> Self {
+        let column = column.column_name();
+        if values.is_empty() {
+            let mut s = self;
+            s.check_column(column);
+            return s;
+        }
+        let args: Vec<Value> = values.iter().map(|v| rbs::to_value!(v)).collect();
+        let placeholders = vec!["?"; args.len()].join(",");
+        self.push(
+            column,
+            format!("{} not in ({})", column, placeholders),
+            args,
+        )
+    }
+
+    /// `column is null`
+    pub fn is_null<C: ColumnName>(self, column: C) -> Self {
+        let column = column.column_name();
+        self.push(column, format!("{} is null", column), vec![])
+    }
+
+    /// `column is not null`
+    pub fn is_not_null<C: ColumnName>(self, column: C) -> Self {
+        let column = column.column_name();
+        self.push(column, format!("{} is not null", column), vec![])
+    }
+
+    /// add conditions of map entries:
+    /// * scalar => `column = ?`
+    /// * array => `column in (?,?)`, empty array means always false
+    /// * null => `column is null`
+    /// ```rust
+    /// use rbatis::wrapper::QueryWrapper;
+    /// let w = QueryWrapper::new().by_map(&rbs::to_value!{"status": 1, "type": [1, 2], "remark": rbs::Value::Null});
+    /// assert_eq!(w.where_sql, "status = ? and type in (?,?) and remark is null");
+    /// ```
+    pub fn by_map(mut self, map: &Value) -> Self {
+        for (k, v) in map {
+            let column = match k.as_str() {
+                Some(v) => v,
+                None => {
+                    if self.error.is_none() {
+                        self.error = Some(k.to_string());
+                    }
+                    continue;
+                }
+            };
+            self = match v {
+                Value::Null => self.is_null(column),
+                Value::Array(arr) => self.in_(column, arr),
+                _ => self.eq(column, v),
+            };
+        }
+        self
+    }
+
+    fn group(mut self, join: &str, f: impl FnOnce(QueryWrapper) -> QueryWrapper) -> Self {
+        let w = f(QueryWrapper::new());
+        if self.error.is_none() {
+            self.error = w.error;
+        }
+        if w.where_sql.is_empty() {
+            return self;
+        }
+        self.push_sql(join, &format!("({})", w.where_sql));
+        self.args.extend(w.args);
+        self
+    }
+
+    /// `and (...)`
+    /// ```rust
+    /// use rbatis::wrapper::QueryWrapper;
+    /// //status = ? and (region_id = ? or (team_id = ?))
+    /// let w = QueryWrapper::new()
+    ///     .eq("status", 1)
+    ///     .and(|w| w.eq("region_id", 1).or(|w| w.eq("team_id", 2)));
+    /// ```
+    pub fn and(self, f: impl FnOnce(QueryWrapper) -> QueryWrapper) -> Self {
+        self.group(" and ", f)
+    }
+
+    /// `or (...)`
+    pub fn or(self, f: impl FnOnce(QueryWrapper) -> QueryWrapper) -> Self {
+        self.group(" or ", f)
+    }
+
+    pub fn group_by(mut self, columns: &[&str]) -> Self {
+        for column in columns {
+            self.check_column(column);
+            self.group_by.push(column.to_string());
+        }
+        self
+    }
+
+    pub fn order_by<C: ColumnName>(mut self, column: C, asc: bool) -> Self {
+        let column = column.column_name();
+        self.check_column(column);
+        self.order_by.push((column.to_string(), asc));
+        self
+    }
+
+    pub fn order_by_asc<C: ColumnName>(self, column: C) -> Self {
+        self.order_by(column, true)
+    }
+
+    pub fn order_by_desc<C: ColumnName>(self, column: C) -> Self {
+        self.order_by(column, false)
+    }
+
+    pub fn limit(mut self, limit: u64) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// lock the selected rows, only for `select` in a transaction
+    ///
+    /// | mode | mysql 8/postgres | mssql |
+    /// |---|---|---|
+    /// | ForUpdate | `for update` | `with (updlock,rowlock)` |
+    /// | ForUpdateNoWait | `for update nowait` | `with (updlock,rowlock,nowait)` |
+    /// | ForUpdateSkipLocked | `for update skip locked` | `with (updlock,rowlock,readpast)` |
+    ///
+    /// sqlite has no row lock, the sql is refused. use a `begin immediate` transaction instead.
+    pub fn lock(mut self, mode: LockMode) -> Self {
+        self.lock = Some(mode);
+        self
+    }
+
+    /// `for update`
+    pub fn for_update(self) -> Self {
+        self.lock(LockMode::ForUpdate)
+    }
+
+    /// `for update skip locked`, the rows locked by other transactions are skipped.
+    /// usually used by work queue
+    pub fn for_update_skip_locked(self) -> Self {
+        self.lock(LockMode::ForUpdateSkipLocked)
+    }
+
+    /// return Err if the wrapper has a lock but the executor is not in a transaction
+    pub fn check_lock(&self, executor: &dyn Executor) -> Result<(), Error> {
+        if self.lock.is_some() && !executor.in_transaction() {
+            return Err(Error::from(
+                "[rb] QueryWrapper lock rows must be in a transaction, please use `rb.acquire_begin()`",
+            ));
+        }
+        Ok(())
+    }
+
+    /// the wrapper with the columns(field names) mapped to the column names by the naming,
+    /// used by the crud! methods. `*`, numbers and the table alias of `t.field` are kept
+    /// ```rust
+    /// use rbatis::naming::{NameCase, NamingStrategy};
+    /// use rbatis::wrapper::QueryWrapper;
+    /// let w = QueryWrapper::new()
+    ///     .eq("user_name", "a")
+    ///     .or(|w| w.between("t.create_time", 1, 2).in_("status", &[1]))
+    ///     .order_by_desc("id")
+    ///     .with_naming(&NamingStrategy::new(NameCase::Pascal));
+    /// assert_eq!(w.where_sql, "UserName = ? or (t.CreateTime between ? and ? and Status in (?))");
+    /// assert_eq!(w.order_by, vec![("Id".to_string(), false)]);
+    /// ```
+    pub fn with_naming(&self, naming: &NamingStrategy) -> QueryWrapper {
+        let mut w = self.clone();
+        if naming.is_exact() {
+            return w;
+        }
+        let map = |column: &str| naming_column(naming, column);
+        w.columns = w.columns.iter().map(|v| map(v)).collect();
+        w.group_by = w.group_by.iter().map(|v| map(v)).collect();
+        w.order_by = w.order_by.iter().map(|(v, asc)| (map(v), *asc)).collect();
+        w.where_sql = map_where_columns(&w.where_sql, map);
+        w
+    }
+
+    /// has where conditions
+    pub fn has_where(&self) -> bool {
+        !self.where_sql.is_empty()
+    }
+
+    fn check(&self) -> Result<(), Error> {
+        match &self.error {
+            None => Ok(()),
+            Some(column) => Err(Error::from(format!(
+                "[rb] QueryWrapper invalid column name: `{}`",
+                column
+            ))),
+        }
+    }
+
+    fn push_where(&self, sql: &mut String) {
+        if !self.where_sql.is_empty() {
+            sql.push_str(" where ");
+            sql.push_str(&self.where_sql);
+        }
+    }
+
+    /// build `select` sql and args
+    pub fn to_select_sql(
+        &self,
+        driver_type: &str,
+        table_name: &str,
+    ) -> Result<(String, Vec<Value>), Error> {
+        self.check()?;
+        let columns = if self.columns.is_empty() {
+            "*".to_string()
+        } else {
+            self.columns.join(",")
+        };
+        let mut sql = format!("select {} from {}", columns, table_name);
+        if let Some(lock) = &self.lock {
+            match driver_type {
+                "sqlite" => {
+                    return Err(Error::from(
+                        "[rb] sqlite not support row lock, please use `begin immediate` transaction",
+                    ));
+                }
+                "mssql" => sql.push_str(lock.mssql_hint()),
+                _ => {}
+            }
+        }
+        self.push_where(&mut sql);
+        if !self.group_by.is_empty() {
+            sql.push_str(" group by ");
+            sql.push_str(&self.group_by.join(","));
+        }
+        if !self.order_by.is_empty() {
+            sql.push_str(" order by ");
+            let order: Vec<String> = self
+                .order_by
+                .iter()
+                .map(|(column, asc)| format!("{} {}", column, if *asc { "asc" } else { "desc" }))
+                .collect();
+            sql.push_str(&order.join(","));
+        }
+        if let Some(limit) = self.limit {
+            if driver_type == "mssql" {
+                //mssql must have `order by`
+                if self.order_by.is_empty() {
+                    sql.push_str(" order by (select null)");
+                }
+                sql.push_str(&format!(" offset 0 rows fetch next {} rows only", limit));
+            } else {
+                sql.push_str(&format!(" limit {}", limit));
+            }
+        }
+        if let Some(lock) = &self.lock {
+            if driver_type != "mssql" {
+                sql.push_str(lock.clause());
+            }
+        }
+        Ok((sql, self.args.clone()))
+    }
+
+    /// build `select count(1)` sql and args, `order by` and `limit` are ignored
+    pub fn to_count_sql(&self, table_name: &str) -> Result<(String, Vec<Value>), Error> {
+        self.check()?;
+        let mut sql = if self.group_by.is_empty() {
+            format!("select count(1) as count from {}", table_name)
+        } else {
+            format!("select {} from {}", self.group_by.join(","), table_name)
+        };
+        self.push_where(&mut sql);
+        if !self.group_by.is_empty() {
+            sql = format!(
+                "select count(1) as count from ({} group by {}) t",
+                sql,
+                self.group_by.join(",")
+            );
+        }
+        Ok((sql, self.args.clone()))
+    }
+
+    /// build aggregate sql and args, for example `select sum(amount) as agg_value from biz_order`.
+    /// if group_by is Some, the sql is `select region as agg_key,sum(amount) as agg_value from biz_order group by region`.
+    /// * func: one of `count`,`sum`,`max`,`min`,`avg`
+    /// * `order by` and `limit` are ignored
+    pub fn to_aggregate_sql(
+        &self,
+        table_name: &str,
+        func: &str,
+        column: &str,
+        group_by: Option<&str>,
+    ) -> Result<(String, Vec<Value>), Error> {
+        self.check()?;
+        if !["count", "sum", "max", "min", "avg"].contains(&func) {
+            return Err(Error::from(format!(
+                "[rb] QueryWrapper unknown aggregate function: `{}`",
+                func
+            )));
+        }
+        for column in [Some(column), group_by].into_iter().flatten() {
+            check_column(column)?;
+        }
+        let mut sql = match group_by {
+            None => format!(
+                "select {}({}) as agg_value from {}",
+                func, column, table_name
+            ),
+            Some(group_by) => format!(
+                "select {} as agg_key,{}({}) as agg_value from {}",
+                group_by, func, column, table_name
+            ),
+        };
+        self.push_where(&mut sql);
+        if let Some(group_by) = group_by {
+            sql.push_str(" group by ");
+            sql.push_str(group_by);
+        }
+        Ok((sql, self.args.clone()))
+    }
+
+    /// build `update` sql and args, the set values are map entries of table.
+    /// the wrapper must have where conditions
+    pub fn to_update_sql(
+        &self,
+        table_name: &str,
+        table: &Value,
+        skip_null: bool,
+    ) -> Result<(String, Vec<Value>), Error> {
+        self.check()?;
+        if !self.has_where() {
+            return Err(Error::from("[rb] update_by_wrapper where can't be empty!"));
+        }
+        let mut sets = vec![];
+        let mut args = vec![];
+        for (k, v) in table {
+            if skip_null && v.is_null() {
+                continue;
+            }
+            sets.push(format!("{}=?", k.as_str().unwrap_or_default()));
+            args.push(v.clone());
+        }
+        if sets.is_empty() {
+            return Err(Error::from("[rb] update_by_wrapper set can't be empty!"));
+        }
+        let mut sql = format!("update {} set {}", table_name, sets.join(","));
+        self.push_where(&mut sql);
+        args.extend(self.args.iter().cloned());
+        Ok((sql, args))
+    }
+
+    /// build `delete` sql and args, the wrapper must have where conditions
+    pub fn to_delete_sql(&self, table_name: &str) -> Result<(String, Vec<Value>), Error> {
+        self.check()?;
+        if !self.has_where() {
+            return Err(Error::from("[rb] delete_by_wrapper where can't be empty!"));
+        }
+        let mut sql = format!("delete from {}", table_name);
+        self.push_where(&mut sql);
+        Ok((sql, self.args.clone()))
+    }
+}
+
+/// check a column name is a plain identifier (or `alias.column`) before it is put into the sql
+pub fn check_column(column: &str) -> Result<(), Error> {
+    if !is_valid_column(column) {
+        return Err(Error::from(format!(
+            "[rb] QueryWrapper invalid column name: `{}`",
+            column
+        )));
+    }
+    Ok(())
+}
+
+fn is_valid_column(column: &str) -> bool {
+    !column.is_empty()
+        && column
+            .bytes()
+            .all(|v| v.is_ascii_alphanumeric() || v == b'_' || v == b'.')
+}
+
+/// the column name of a field, `*`, numbers and the table alias are kept
+fn naming_column(naming: &NamingStrategy, column: &str) -> String {
+    if column == "*" || column.bytes().all(|v| v.is_ascii_digit()) {
+        return column.to_string();
+    }
+    match column.rsplit_once('.') {
+        Some((alias, field)) => format!("{}.{}", alias, naming_column(naming, field)),
+        None => naming.to_column(column),
+    }
+}
+
+/// map the column of every condition in the where sql built by the wrapper.
+/// a condition starts with the column: at the beginning, after `(` and after the `and`/`or` joins
+/// (the `and` of `between ? and ?` is followed by `?`)
+fn map_where_columns(sql: &str, map: impl Fn(&str) -> String) -> String {
+    let mut result = String::with_capacity(sql.len());
+    let mut expect_column = true;
+    let mut word = String::new();
+    let push_word = |word: &mut String, result: &mut String, expect_column: &mut bool| {
+        if word.is_empty() {
+            return;
+        }
+        if *expect_column {
+            result.push_str(&map(word));
+            *expect_column = false;
+        } else {
+            *expect_column = word == "and" || word == "or";
+            result.push_str(word);
+        }
+        word.clear();
+    };
+    for c in sql.chars() {
+        if c.is_ascii_alphanumeric() || c == '_' || c == '.' {
+            word.push(c);
+            continue;
+        }
+        push_word(&mut word, &mut result, &mut expect_column);
+        match c {
+            '(' => expect_column = true,
+            ' ' => {}
+            _ => expect_column = false,
+        }
+        result.push(c);
+    }
+    push_word(&mut word, &mut result, &mut expect_column);
+    result
+}
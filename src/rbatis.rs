@@ -1,11 +1,15 @@
-use crate::executor::{Executor, RBatisConnExecutor, RBatisTxExecutor};
+use crate::batch::BatchLimit;
+use crate::bulk::BulkInsertOption;
+use crate::executor::{Context, ContextExecutor, Executor, RBatisConnExecutor, RBatisTxExecutor};
 use crate::intercept_log::LogInterceptor;
+use crate::naming::{NamingConfig, NamingStrategy};
 use crate::plugin::intercept::Intercept;
 use crate::plugin::intercept_page::PageIntercept;
 use crate::snowflake::Snowflake;
 use crate::table_sync::{sync, ColumnMapper};
 use crate::{DefaultPool, Error};
 use dark_std::sync::SyncVec;
+use futures::Stream;
 use log::LevelFilter;
 use rbdc::pool::conn_manager::ConnManager;
 use rbdc::pool::Pool;
@@ -25,6 +29,10 @@ pub struct RBatis {
     pub intercepts: Arc<SyncVec<Arc<dyn Intercept>>>,
     //rb task id gen
     pub task_id_generator: Arc<Snowflake>,
+    // the limit of one batch statement(bind parameters,sql length)
+    pub batch_limit: BatchLimit,
+    // the column naming of all tables and of a table
+    pub naming: Arc<NamingConfig>,
 }
 
 impl Default for RBatis {
@@ -33,6 +41,8 @@ impl Default for RBatis {
             pool: Arc::new(Default::default()),
             intercepts: Arc::new(SyncVec::new()),
             task_id_generator: Arc::new(Snowflake::default()),
+            batch_limit: BatchLimit::default(),
+            naming: Arc::new(NamingConfig::default()),
         }
     }
 }
@@ -49,6 +59,11 @@ impl RBatis {
         rb
     }
 
+    /// the column naming strategy of a table
+    pub fn naming_of(&self, table_name: &str) -> Arc<NamingStrategy> {
+        self.naming.naming_of(table_name)
+    }
+
     /// self.init(driver, url)? and self.try_acquire().await? a connection.
     /// DefaultPool use FastPool
     pub async fn link<Driver: rbdc::db::Driver + 'static>(
@@ -193,6 +208,21 @@ impl RBatis {
         ))
     }
 
+    /// get an executor with context values, intercepts can read them by `executor.context()`
+    /// ```rust
+    /// use rbatis::executor::Context;
+    /// use rbatis::RBatis;
+    /// pub struct CurrentUser(pub i64);
+    ///
+    /// async fn get_orders(rb: &RBatis) -> Result<rbs::Value, rbatis::Error> {
+    ///     let executor = rb.with_context(Context::new().with(CurrentUser(1)));
+    ///     executor.query("select * from biz_order", vec![]).await
+    /// }
+    /// ```
+    pub fn with_context(&self, context: Context) -> ContextExecutor {
+        ContextExecutor::new(self.clone(), context)
+    }
+
     /// get an DataBase Connection,and call begin method,used for the next step
     pub async fn acquire_begin(&self) -> Result<RBatisTxExecutor, Error> {
         let conn = self.acquire().await?;
@@ -206,6 +236,50 @@ impl RBatis {
         Ok(executor)
     }
 
+    /// insert many rows with multi-row `insert ... values (?,?),(?,?)` statements on one connection,
+    /// return the total inserted rows. the statements run through the intercepts and the fields are
+    /// mapped to columns by the table naming, see `bulk::BulkWriter`.
+    /// the table hooks are not called, `Table::bulk_insert` of `crud!` calls `before_insert`.
+    ///
+    /// the statements are split by `option.batch_size`, the bind parameter limit of the driver
+    /// and `option.max_packet_size`(by default the mysql `max_allowed_packet`).
+    /// by default all statements run in one transaction, which is the fastest way of sqlite.
+    ///
+    /// notice: postgres `COPY` and mssql bulk copy are not supported by the rbdc `Connection`,
+    /// so every driver use the multi-row insert.
+    /// ```rust
+    /// use rbatis::bulk::BulkInsertOption;
+    /// #[derive(serde::Serialize)]
+    /// pub struct Activity {
+    ///     pub id: i64,
+    ///     pub name: String,
+    /// }
+    /// async fn load(rb: &rbatis::RBatis) -> Result<u64, rbatis::Error> {
+    ///     let rows = (0..100_000).map(|id| Activity { id, name: id.to_string() });
+    ///     let option = BulkInsertOption::new().on_progress(|total| println!("inserted {}", total));
+    ///     rb.bulk_insert("activity", rows, option).await
+    /// }
+    /// ```
+    pub async fn bulk_insert<T: Serialize, I: IntoIterator<Item = T>>(
+        &self,
+        table_name: &str,
+        rows: I,
+        option: BulkInsertOption,
+    ) -> Result<u64, Error> {
+        crate::bulk::bulk_insert(self, table_name, rows, option, |row| Ok(to_value!(row))).await
+    }
+
+    /// same as `bulk_insert`, but the rows are read from a `Stream`
+    pub async fn bulk_insert_stream<T: Serialize, S: Stream<Item = T> + Unpin>(
+        &self,
+        table_name: &str,
+        rows: S,
+        option: BulkInsertOption,
+    ) -> Result<u64, Error> {
+        crate::bulk::bulk_insert_stream(self, table_name, rows, option, |row| Ok(to_value!(row)))
+            .await
+    }
+
     /// is debug mode
     pub fn is_debug_mode(&self) -> bool {
         crate::decode::is_debug_mode()
@@ -1,3 +1,4 @@
 #[macro_use]
 pub mod table_util;
 pub mod impled;
+pub mod sql_util;
@@ -0,0 +1,283 @@
+/// sql token kind
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TokenKind {
+    /// keyword or identifier
+    Word,
+    /// quoted identifier, for example: `a`,"a",[a]
+    QuotedIdent,
+    /// 'string literal'
+    Literal,
+    Number,
+    /// placeholder `?`(None) or `$1`,`@p1`(Some(0))
+    Placeholder(Option<usize>),
+    /// operator or punctuation, for example: = <> ( ) , ; .
+    Symbol,
+    Comment,
+}
+
+/// sql token with byte range of source sql
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub start: usize,
+    pub end: usize,
+    /// token text (quotes removed for QuotedIdent)
+    pub text: String,
+}
+
+impl Token {
+    /// is an keyword(case insensitive)
+    pub fn is_word(&self, word: &str) -> bool {
+        self.kind == TokenKind::Word && self.text.eq_ignore_ascii_case(word)
+    }
+
+    pub fn is_symbol(&self, symbol: &str) -> bool {
+        self.kind == TokenKind::Symbol && self.text == symbol
+    }
+
+    pub fn is_ident(&self) -> bool {
+        self.kind == TokenKind::Word || self.kind == TokenKind::QuotedIdent
+    }
+}
+
+/// split sql into tokens, whitespace is skipped
+pub fn tokenize(sql: &str) -> Vec<Token> {
+    let bytes = sql.as_bytes();
+    let mut tokens = vec![];
+    let mut i = 0;
+    while i < bytes.len() {
+        let c = bytes[i];
+        let start = i;
+        let kind;
+        if c.is_ascii_whitespace() {
+            i += 1;
+            continue;
+        } else if c == b'-' && bytes.get(i + 1) == Some(&b'-') {
+            while i < bytes.len() && bytes[i] != b'\n' {
+                i += 1;
+            }
+            kind = TokenKind::Comment;
+        } else if c == b'/' && bytes.get(i + 1) == Some(&b'*') {
+            i += 2;
+            while i < bytes.len() && !(bytes[i] == b'*' && bytes.get(i + 1) == Some(&b'/')) {
+                i += 1;
+            }
+            i = (i + 2).min(bytes.len());
+            kind = TokenKind::Comment;
+        } else if c == b'\'' {
+            i += 1;
+            while i < bytes.len() {
+                if bytes[i] == b'\'' {
+                    if bytes.get(i + 1) == Some(&b'\'') {
+                        i += 2;
+                        continue;
+                    }
+                    break;
+                }
+                if bytes[i] == b'\\' {
+                    i += 1;
+                }
+                i += 1;
+            }
+            i = (i + 1).min(bytes.len());
+            kind = TokenKind::Literal;
+        } else if c == b'`' || c == b'"' || c == b'[' {
+            let close = if c == b'[' { b']' } else { c };
+            i += 1;
+            while i < bytes.len() && bytes[i] != close {
+                i += 1;
+            }
+            let text = sql[start + 1..i].to_string();
+            i = (i + 1).min(bytes.len());
+            tokens.push(Token {
+                kind: TokenKind::QuotedIdent,
+                start,
+                end: i,
+                text,
+            });
+            continue;
+        } else if c == b'?' {
+            i += 1;
+            kind = TokenKind::Placeholder(None);
+        } else if (c == b'$' || c == b'@') && i + 1 < bytes.len() {
+            let mut j = i + 1;
+            if c == b'@' && (bytes[j] == b'p' || bytes[j] == b'P') {
+                j += 1;
+            }
+            let num_start = j;
+            while j < bytes.len() && bytes[j].is_ascii_digit() {
+                j += 1;
+            }
+            if j > num_start {
+                let idx = sql[num_start..j].parse::<usize>().unwrap_or(1);
+                i = j;
+                kind = TokenKind::Placeholder(Some(idx.saturating_sub(1)));
+            } else {
+                while j < bytes.len() && (bytes[j].is_ascii_alphanumeric() || bytes[j] == b'_') {
+                    j += 1;
+                }
+                i = j;
+                kind = TokenKind::Word;
+            }
+        } else if c.is_ascii_digit() {
+            while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'.') {
+                i += 1;
+            }
+            kind = TokenKind::Number;
+        } else if c.is_ascii_alphabetic() || c == b'_' || c >= 0x80 {
+            while i < bytes.len()
+                && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_' || bytes[i] >= 0x80)
+            {
+                i += 1;
+            }
+            kind = TokenKind::Word;
+        } else {
+            i += 1;
+            if i < bytes.len() {
+                let two = &bytes[start..i + 1];
+                if two == b"<>" || two == b"!=" || two == b">=" || two == b"<=" || two == b"::" {
+                    i += 1;
+                }
+            }
+            kind = TokenKind::Symbol;
+        }
+        tokens.push(Token {
+            kind,
+            start,
+            end: i,
+            text: sql[start..i].to_string(),
+        });
+    }
+    tokens
+}
+
+/// find the column name of every placeholder(in order of appearance), None if unknown.
+/// support:
+/// * `column = ?`(and other compare operators), `column like ?`, `column in (?,?)`, `column between ? and ?`
+/// * `insert into table (column1,column2) values (?,?),(?,?)`
+/// * `update table set column1 = ?`
+pub fn placeholder_columns(sql: &str) -> Vec<Option<String>> {
+    let tokens: Vec<Token> = tokenize(sql)
+        .into_iter()
+        .filter(|v| v.kind != TokenKind::Comment)
+        .collect();
+    let mut columns = vec![];
+    let mut insert_columns = vec![];
+    let mut values_start = None;
+    if tokens.first().map(|v| v.is_word("insert")).unwrap_or(false) {
+        if let Some(open) = tokens.iter().position(|v| v.is_symbol("(")) {
+            for t in &tokens[open + 1..] {
+                if t.is_symbol(")") {
+                    break;
+                }
+                if t.is_ident() {
+                    insert_columns.push(t.text.clone());
+                }
+            }
+        }
+        values_start = tokens
+            .iter()
+            .position(|v| v.is_word("values") || v.is_word("value"));
+    }
+    let mut insert_idx = 0;
+    for (idx, t) in tokens.iter().enumerate() {
+        if let TokenKind::Placeholder(_) = t.kind {
+            if !insert_columns.is_empty() && values_start.map(|v| idx > v).unwrap_or(false) {
+                columns.push(Some(
+                    insert_columns[insert_idx % insert_columns.len()].clone(),
+                ));
+                insert_idx += 1;
+                continue;
+            }
+            columns.push(find_compare_column(&tokens[..idx]));
+        }
+    }
+    columns
+}
+
+/// find column name from tokens before an placeholder
+fn find_compare_column(before: &[Token]) -> Option<String> {
+    let mut i = before.len();
+    //skip `(?,?,` of `in (?,?,?)`
+    while i > 0 {
+        let t = &before[i - 1];
+        if t.is_symbol(",") || t.is_symbol("(") || matches!(t.kind, TokenKind::Placeholder(_)) {
+            i -= 1;
+        } else {
+            break;
+        }
+    }
+    if i == 0 {
+        return None;
+    }
+    let mut op = &before[i - 1];
+    //`between ? and ?`
+    if op.is_word("and")
+        && i >= 3
+        && matches!(before[i - 2].kind, TokenKind::Placeholder(_))
+        && before[i - 3].is_word("between")
+    {
+        i -= 2;
+        op = &before[i - 1];
+    }
+    let is_op = match op.kind {
+        TokenKind::Symbol => ["=", "<>", "!=", ">", "<", ">=", "<="].contains(&op.text.as_str()),
+        TokenKind::Word => ["like", "in", "between", "ilike"]
+            .iter()
+            .any(|v| op.text.eq_ignore_ascii_case(v)),
+        _ => false,
+    };
+    if !is_op {
+        return None;
+    }
+    i -= 1;
+    if i > 0 && before[i - 1].is_word("not") {
+        i -= 1;
+    }
+    if i == 0 {
+        return None;
+    }
+    let column = &before[i - 1];
+    if column.is_ident() {
+        Some(column.text.clone())
+    } else {
+        None
+    }
+}
+
+/// split sql tokens into statements by `;`, comments are removed
+pub fn split_statements(sql: &str) -> Vec<Vec<Token>> {
+    let mut statements = vec![];
+    let mut current = vec![];
+    for token in tokenize(sql) {
+        if token.kind == TokenKind::Comment {
+            continue;
+        }
+        if token.is_symbol(";") {
+            if !current.is_empty() {
+                statements.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+        current.push(token);
+    }
+    if !current.is_empty() {
+        statements.push(current);
+    }
+    statements
+}
+
+/// find the index of the first keyword at parentheses depth 0
+pub fn find_top_level_word(tokens: &[Token], word: &str) -> Option<usize> {
+    let mut depth = 0;
+    for (idx, t) in tokens.iter().enumerate() {
+        if t.is_symbol("(") {
+            depth += 1;
+        } else if t.is_symbol(")") {
+            depth -= 1;
+        } else if depth == 0 && t.is_word(word) {
+            return Some(idx);
+        }
+    }
+    None
+}
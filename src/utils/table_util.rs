@@ -302,3 +302,80 @@ macro_rules! make_table {
            }
         }
 }
+
+/// get the serialized column names of table struct(`#[serde(rename)]` is supported).
+/// return None if `T` is not a plain struct, for example a map or a struct with `#[serde(flatten)]`
+/// ```rust
+/// #[derive(serde::Serialize, serde::Deserialize)]
+/// pub struct MockTable{
+///    pub id: Option<String>,
+///    #[serde(rename = "type")]
+///    pub kind: Option<i32>,
+/// }
+/// assert_eq!(rbatis::utils::table_util::table_columns::<MockTable>(), Some(&["id", "type"][..]));
+/// ```
+pub fn table_columns<T: serde::de::DeserializeOwned>() -> Option<&'static [&'static str]> {
+    struct FieldsDeserializer<'a> {
+        fields: &'a mut Option<&'static [&'static str]>,
+    }
+    impl<'de> serde::Deserializer<'de> for FieldsDeserializer<'_> {
+        type Error = serde::de::value::Error;
+
+        fn deserialize_any<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: serde::de::Visitor<'de>,
+        {
+            Err(serde::de::Error::custom("not a struct"))
+        }
+
+        fn deserialize_struct<V>(
+            self,
+            _name: &'static str,
+            fields: &'static [&'static str],
+            _visitor: V,
+        ) -> Result<V::Value, Self::Error>
+        where
+            V: serde::de::Visitor<'de>,
+        {
+            *self.fields = Some(fields);
+            Err(serde::de::Error::custom("fields"))
+        }
+
+        serde::forward_to_deserialize_any! {
+            bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+            bytes byte_buf option unit unit_struct newtype_struct seq tuple
+            tuple_struct map enum identifier ignored_any
+        }
+    }
+    let mut fields = None;
+    let _ = T::deserialize(FieldsDeserializer {
+        fields: &mut fields,
+    });
+    fields
+}
+
+/// the aliased select list of table struct for `decode_prefix`, every column is selected as
+/// `{alias}.{column} as {alias}__{column}`. return None if `T` is not a plain struct
+/// ```rust
+/// #[derive(serde::Serialize, serde::Deserialize)]
+/// pub struct User{
+///    pub id: Option<String>,
+///    pub name: Option<String>,
+/// }
+/// assert_eq!(rbatis::utils::table_util::prefix_columns::<User>("u").unwrap(), "u.id as u__id,u.name as u__name");
+/// ```
+pub fn prefix_columns<T: serde::de::DeserializeOwned>(alias: &str) -> Option<String> {
+    let columns = table_columns::<T>()?;
+    Some(
+        columns
+            .iter()
+            .map(|column| {
+                format!(
+                    "{alias}.{column} as {alias}{}{column}",
+                    crate::decode::PREFIX_SEPARATOR
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(","),
+    )
+}
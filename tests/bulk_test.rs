@@ -0,0 +1,278 @@
+mod common;
+
+#[cfg(test)]
+mod test {
+    use crate::common::MockDriver;
+    use async_trait::async_trait;
+    use dark_std::sync::SyncVec;
+    use rbatis::bulk::BulkInsertOption;
+    use rbatis::executor::Executor;
+    use rbatis::hooks::TableHooks;
+    use rbatis::intercept::{Intercept, ResultType};
+    use rbatis::naming::{NameCase, NamingStrategy};
+    use rbatis::{Error, RBatis};
+    use rbdc::db::ExecResult;
+    use rbdc::rt::block_on;
+    use rbs::Value;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::Arc;
+
+    #[derive(Clone, serde::Serialize, serde::Deserialize)]
+    struct Activity {
+        id: i64,
+        name: String,
+        remark: Option<String>,
+    }
+
+    rbatis::crud!(Activity {});
+
+    impl TableHooks for Activity {
+        fn before_insert(&mut self, _executor: &dyn Executor) -> Result<(), Error> {
+            self.remark = Some("hooked".to_string());
+            Ok(())
+        }
+    }
+
+    #[derive(Debug)]
+    struct CaptureIntercept {
+        sqls: SyncVec<(String, Vec<Value>)>,
+    }
+
+    #[async_trait]
+    impl Intercept for CaptureIntercept {
+        async fn before(
+            &self,
+            _task_id: i64,
+            _rb: &dyn Executor,
+            sql: &mut String,
+            args: &mut Vec<Value>,
+            _result: ResultType<&mut Result<ExecResult, Error>, &mut Result<Vec<Value>, Error>>,
+        ) -> Result<Option<bool>, Error> {
+            self.sqls.push((sql.clone(), args.clone()));
+            Ok(Some(true))
+        }
+    }
+
+    /// the rows_affected of an insert is the rows of values
+    fn driver() -> MockDriver {
+        MockDriver::new().affected(|sql, _| sql.matches("(?").count() as u64)
+    }
+
+    fn rows(n: i64) -> impl Iterator<Item = Activity> {
+        (0..n).map(|id| Activity {
+            id,
+            name: format!("name{}", id),
+            remark: None,
+        })
+    }
+
+    #[test]
+    fn test_bulk_insert() {
+        let f = async move {
+            let driver = driver();
+            let mut rb = RBatis::new();
+            rb.batch_limit.max_params = Some(9);
+            rb.init(driver.clone(), "test").unwrap();
+            let progress = Arc::new(AtomicU64::new(0));
+            let p = progress.clone();
+            let option = BulkInsertOption::new().on_progress(move |total| {
+                p.store(total, Ordering::SeqCst);
+            });
+            let total = rb.bulk_insert("activity", rows(7), option).await.unwrap();
+            assert_eq!(total, 7);
+            assert_eq!(progress.load(Ordering::SeqCst), 7);
+            let execs = driver.take_log();
+            assert_eq!(
+                execs,
+                vec![
+                    ("begin".to_string(), 0),
+                    (
+                        "insert into activity (id,name,remark) values (?,?,?),(?,?,?),(?,?,?)"
+                            .to_string(),
+                        9
+                    ),
+                    (
+                        "insert into activity (id,name,remark) values (?,?,?),(?,?,?),(?,?,?)"
+                            .to_string(),
+                        9
+                    ),
+                    (
+                        "insert into activity (id,name,remark) values (?,?,?)".to_string(),
+                        3
+                    ),
+                    ("commit".to_string(), 0),
+                ]
+            );
+        };
+        block_on(f);
+    }
+
+    #[test]
+    fn test_bulk_insert_stream_packet_size() {
+        let f = async move {
+            let driver = driver();
+            let rb = RBatis::new();
+            rb.init(driver.clone(), "test").unwrap();
+            let option = BulkInsertOption {
+                max_packet_size: 120,
+                transaction: false,
+                ..BulkInsertOption::default()
+            };
+            let stream = futures::stream::iter(rows(4));
+            let total = rb
+                .bulk_insert_stream("activity", stream, option)
+                .await
+                .unwrap();
+            assert_eq!(total, 4);
+            let execs = driver.take_log();
+            assert_eq!(execs.len(), 2);
+            assert_eq!(execs[0].1, 6);
+            assert!(!execs.iter().any(|(sql, _)| sql == "begin"));
+        };
+        block_on(f);
+    }
+
+    #[test]
+    fn test_bulk_insert_rollback() {
+        let f = async move {
+            let driver = MockDriver::new();
+            let rb = RBatis::new();
+            rb.init(driver.clone(), "test").unwrap();
+            let r = rb
+                .bulk_insert("fail_table", rows(2), BulkInsertOption::default())
+                .await;
+            assert!(r.is_err());
+            let execs = driver.take_log();
+            assert_eq!(execs.last().unwrap().0, "rollback");
+        };
+        block_on(f);
+    }
+
+    #[test]
+    fn test_bulk_insert_commit_fail() {
+        let f = async move {
+            let driver = MockDriver::new().fail(|sql| sql == "commit");
+            let rb = RBatis::new();
+            rb.init(driver.clone(), "test").unwrap();
+            let r = rb
+                .bulk_insert("activity", rows(2), BulkInsertOption::default())
+                .await;
+            assert!(r.is_err());
+            let execs = driver.take_log();
+            let sqls: Vec<&str> = execs.iter().map(|(sql, _)| sql.as_str()).collect();
+            assert_eq!(sqls[sqls.len() - 2..], ["commit", "rollback"]);
+        };
+        block_on(f);
+    }
+
+    #[test]
+    fn test_bulk_insert_intercept_naming_hooks() {
+        let f = async move {
+            let driver = driver();
+            let rb = RBatis::new();
+            rb.naming.set_default(NamingStrategy::new(NameCase::Pascal));
+            rb.init(driver.clone(), "test").unwrap();
+            rb.intercepts.push(Arc::new(CaptureIntercept {
+                sqls: SyncVec::new(),
+            }));
+            let option = BulkInsertOption {
+                transaction: false,
+                ..BulkInsertOption::default()
+            };
+            let total = Activity::bulk_insert(&rb, rows(2), option.clone())
+                .await
+                .unwrap();
+            assert_eq!(total, 2);
+            let capture = rb.get_intercept::<CaptureIntercept>().unwrap();
+            let (sql, args) = capture.sqls.pop().unwrap();
+            assert_eq!(
+                sql,
+                "insert into activity (Id,Name,Remark) values (?,?,?),(?,?,?)"
+            );
+            assert_eq!(args[2], Value::String("hooked".to_string()));
+
+            //the hooks are only called by the crud method
+            rb.bulk_insert("activity", rows(1), option).await.unwrap();
+            let (sql, args) = capture.sqls.pop().unwrap();
+            assert_eq!(sql, "insert into activity (Id,Name,Remark) values (?,?,?)");
+            assert_eq!(args[2], Value::Null);
+        };
+        block_on(f);
+    }
+
+    #[test]
+    fn test_bulk_insert_mysql_packet_size() {
+        let f = async move {
+            let driver = driver().name("mysql").query(|sql, _| {
+                if sql == "select @@max_allowed_packet" {
+                    (
+                        vec!["@@max_allowed_packet".to_string()],
+                        vec![vec![Value::U64(1024 + 120)]],
+                    )
+                } else {
+                    (vec![], vec![])
+                }
+            });
+            let rb = RBatis::new();
+            rb.init(driver.clone(), "test").unwrap();
+            let option = BulkInsertOption {
+                transaction: false,
+                ..BulkInsertOption::default()
+            };
+            let total = rb.bulk_insert("activity", rows(4), option).await.unwrap();
+            assert_eq!(total, 4);
+            let execs = driver.take_log();
+            assert_eq!(execs.len(), 3);
+            assert_eq!(execs[0].0, "select @@max_allowed_packet");
+            assert_eq!(execs[1].1, 6);
+            assert_eq!(execs[2].1, 6);
+        };
+        block_on(f);
+    }
+
+    #[test]
+    fn test_bulk_insert_rows_affected() {
+        let f = async move {
+            //the database skip a row of every statement, like `insert ignore`
+            let driver = MockDriver::new()
+                .affected(|sql, _| (sql.matches("(?").count() as u64).saturating_sub(1));
+            let mut rb = RBatis::new();
+            rb.batch_limit.max_params = Some(9);
+            rb.init(driver.clone(), "test").unwrap();
+            let progress = Arc::new(SyncVec::new());
+            let p = progress.clone();
+            let option = BulkInsertOption::new().on_progress(move |total| {
+                p.push(total);
+            });
+            let total = rb.bulk_insert("activity", rows(7), option).await.unwrap();
+            assert_eq!(total, 4);
+            assert_eq!(
+                progress.iter().copied().collect::<Vec<u64>>(),
+                vec![2, 4, 4]
+            );
+        };
+        block_on(f);
+    }
+
+    #[test]
+    fn test_bulk_insert_columns_mismatch() {
+        let f = async move {
+            let driver = driver();
+            let rb = RBatis::new();
+            rb.init(driver.clone(), "test").unwrap();
+            let rows = vec![
+                rbs::to_value! {"id": 1, "name": "a"},
+                rbs::to_value! {"id": 2, "remark": "b"},
+            ];
+            let r = rb
+                .bulk_insert("activity", rows, BulkInsertOption::default())
+                .await;
+            assert_eq!(
+                r.unwrap_err().to_string(),
+                "[rb] bulk_insert the columns of row `id,remark` are not the columns of the first row `id,name`"
+            );
+            assert!(driver.take_log().is_empty());
+        };
+        block_on(f);
+    }
+}
@@ -0,0 +1,219 @@
+//! the mock driver shared by the tests, `mod common;` in the test file
+#![allow(dead_code)]
+
+use futures_core::future::BoxFuture;
+use rbatis::Error;
+use rbdc::db::{ConnectOptions, Connection, Driver, ExecResult, MetaData, Row};
+use rbs::Value;
+use std::sync::{Arc, Mutex};
+
+/// the (sql, args len) of every exec/query
+pub type Log = Arc<Mutex<Vec<(String, usize)>>>;
+
+/// the columns and rows of a query
+pub type QueryFn = fn(&str, &[Value]) -> (Vec<String>, Vec<Vec<Value>>);
+
+/// the exec fail if it return true
+pub type FailFn = fn(&str) -> bool;
+
+/// the rows_affected of an exec
+pub type AffectedFn = fn(&str, &[Value]) -> u64;
+
+#[derive(Clone, Debug)]
+pub struct MockDriver {
+    pub name: &'static str,
+    pub log: Log,
+    pub query: QueryFn,
+    pub fail: FailFn,
+    pub affected: AffectedFn,
+}
+
+impl Default for MockDriver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockDriver {
+    /// the query return no rows, the exec of the sql contains `fail` return an error,
+    /// the rows_affected of an exec is the args len
+    pub fn new() -> Self {
+        Self {
+            name: "test",
+            log: Log::default(),
+            query: |_, _| (vec![], vec![]),
+            fail: |sql| sql.contains("fail"),
+            affected: |_, args| args.len() as u64,
+        }
+    }
+
+    /// the driver name, which is the `driver_type` of RBatis
+    pub fn name(mut self, name: &'static str) -> Self {
+        self.name = name;
+        self
+    }
+
+    pub fn query(mut self, query: QueryFn) -> Self {
+        self.query = query;
+        self
+    }
+
+    pub fn fail(mut self, fail: FailFn) -> Self {
+        self.fail = fail;
+        self
+    }
+
+    pub fn affected(mut self, affected: AffectedFn) -> Self {
+        self.affected = affected;
+        self
+    }
+
+    /// take the logged (sql, args len)
+    pub fn take_log(&self) -> Vec<(String, usize)> {
+        std::mem::take(&mut *self.log.lock().unwrap())
+    }
+
+    fn connection(&self) -> Box<dyn Connection> {
+        Box::new(MockConnection {
+            driver: self.clone(),
+        })
+    }
+}
+
+impl Driver for MockDriver {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn connect(&self, _url: &str) -> BoxFuture<'_, Result<Box<dyn Connection>, Error>> {
+        let conn = self.connection();
+        Box::pin(async { Ok(conn) })
+    }
+
+    fn connect_opt<'a>(
+        &'a self,
+        _option: &'a dyn ConnectOptions,
+    ) -> BoxFuture<'a, Result<Box<dyn Connection>, Error>> {
+        let conn = self.connection();
+        Box::pin(async { Ok(conn) })
+    }
+
+    fn default_option(&self) -> Box<dyn ConnectOptions> {
+        Box::new(MockConnectOptions {
+            driver: self.clone(),
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct MockRowMetaData {
+    columns: Arc<Vec<String>>,
+}
+
+impl MetaData for MockRowMetaData {
+    fn column_len(&self) -> usize {
+        self.columns.len()
+    }
+
+    fn column_name(&self, i: usize) -> String {
+        self.columns[i].clone()
+    }
+
+    fn column_type(&self, _i: usize) -> String {
+        "String".to_string()
+    }
+}
+
+#[derive(Debug)]
+pub struct MockRow {
+    columns: Arc<Vec<String>>,
+    values: Vec<Value>,
+}
+
+impl Row for MockRow {
+    fn meta_data(&self) -> Box<dyn MetaData> {
+        Box::new(MockRowMetaData {
+            columns: self.columns.clone(),
+        })
+    }
+
+    fn get(&mut self, i: usize) -> Result<Value, Error> {
+        Ok(self.values[i].clone())
+    }
+}
+
+#[derive(Debug)]
+pub struct MockConnection {
+    driver: MockDriver,
+}
+
+impl MockConnection {
+    fn log(&self, sql: &str, params: &[Value]) {
+        self.driver
+            .log
+            .lock()
+            .unwrap()
+            .push((sql.to_string(), params.len()));
+    }
+}
+
+impl Connection for MockConnection {
+    fn get_rows(
+        &mut self,
+        sql: &str,
+        params: Vec<Value>,
+    ) -> BoxFuture<'_, Result<Vec<Box<dyn Row>>, Error>> {
+        self.log(sql, &params);
+        let (columns, rows) = (self.driver.query)(sql, &params);
+        let columns = Arc::new(columns);
+        let rows = rows
+            .into_iter()
+            .map(|values| {
+                Box::new(MockRow {
+                    columns: columns.clone(),
+                    values,
+                }) as Box<dyn Row>
+            })
+            .collect();
+        Box::pin(async { Ok(rows) })
+    }
+
+    fn exec(&mut self, sql: &str, params: Vec<Value>) -> BoxFuture<'_, Result<ExecResult, Error>> {
+        self.log(sql, &params);
+        let fail = (self.driver.fail)(sql);
+        let rows_affected = (self.driver.affected)(sql, &params);
+        Box::pin(async move {
+            if fail {
+                return Err(Error::from("mock fail"));
+            }
+            Ok(ExecResult {
+                rows_affected,
+                last_insert_id: Value::Null,
+            })
+        })
+    }
+
+    fn close(&mut self) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(async { Ok(()) })
+    }
+
+    fn ping(&mut self) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(async { Ok(()) })
+    }
+}
+
+#[derive(Debug)]
+pub struct MockConnectOptions {
+    driver: MockDriver,
+}
+
+impl ConnectOptions for MockConnectOptions {
+    fn connect(&self) -> BoxFuture<'_, Result<Box<dyn Connection>, Error>> {
+        let conn = self.driver.connection();
+        Box::pin(async { Ok(conn) })
+    }
+
+    fn set_uri(&mut self, _uri: &str) -> Result<(), Error> {
+        Ok(())
+    }
+}
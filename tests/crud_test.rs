@@ -13,10 +13,14 @@ extern crate rbatis;
 mod test {
     use dark_std::sync::SyncVec;
     use futures_core::future::BoxFuture;
-    use rbatis::executor::{Executor, RBatisConnExecutor};
+    use rbatis::executor::{Context, Executor, RBatisConnExecutor};
+    use rbatis::hooks::TableHooks;
     use rbatis::intercept::{Intercept, ResultType};
     use rbatis::intercept_page::PageIntercept;
+    use rbatis::naming::{NameCase, NamingStrategy};
     use rbatis::plugin::PageRequest;
+    use rbatis::tracked::Tracked;
+    use rbatis::wrapper::{LockMode, QueryWrapper};
     use rbatis::{impl_delete, impl_select, impl_select_page, impl_update};
     use rbatis::{DefaultPool, Error, RBatis};
     use rbdc::datetime::DateTime;
@@ -90,7 +94,9 @@ mod test {
 
     impl MetaData for MockRowMetaData {
         fn column_len(&self) -> usize {
-            if self.sql.contains("select count") {
+            if self.sql.contains("select count")
+                || (self.sql.contains("agg_value") && !self.sql.contains("agg_key"))
+            {
                 1
             } else {
                 2
@@ -100,6 +106,12 @@ mod test {
         fn column_name(&self, i: usize) -> String {
             if self.sql.contains("select count") {
                 "count".to_string()
+            } else if self.sql.contains("agg_value") {
+                if i == 0 && self.sql.contains("agg_key") {
+                    "agg_key".to_string()
+                } else {
+                    "agg_value".to_string()
+                }
             } else {
                 if i == 0 {
                     "sql".to_string()
@@ -128,7 +140,9 @@ mod test {
         }
 
         fn get(&mut self, i: usize) -> Result<Value, Error> {
-            if self.sql.contains("select count") {
+            if self.sql.contains("select count")
+                || (self.sql.contains("agg_value") && !self.sql.contains("agg_key"))
+            {
                 Ok(Value::U64(self.count))
             } else {
                 if i == 0 {
@@ -187,7 +201,7 @@ mod test {
         }
     }
 
-    #[derive(Clone, Debug, serde::Serialize, serde::Deserialize, PartialEq)]
+    #[derive(Clone, Debug, serde::Serialize, serde::Deserialize, PartialEq, rbatis::Columns)]
     struct MockTable {
         pub id: Option<String>,
         pub name: Option<String>,
@@ -938,6 +952,36 @@ mod test {
         };
         block_on(f);
     }
+    #[test]
+    fn test_select_page_with_context() {
+        let f = async move {
+            let mut rb = RBatis::new();
+            let queue = Arc::new(SyncVec::new());
+            rb.set_intercepts(vec![
+                Arc::new(PageIntercept::new()),
+                Arc::new(MockIntercept::new(queue.clone())),
+            ]);
+            rb.init(MockDriver {}, "test").unwrap();
+            let executor = rb.with_context(Context::new());
+            MockTable::select_page(&executor, &PageRequest::new(2, 10), "1")
+                .await
+                .unwrap();
+            let (sql, _) = queue.pop().unwrap();
+            assert_eq!(
+                sql,
+                "select * from mock_table order by create_time desc limit 10,10 "
+            );
+            let (sql, _) = queue.pop().unwrap();
+            assert_eq!(
+                sql,
+                "select count(1) as count from mock_table order by create_time desc"
+            );
+            let page = rb.get_intercept::<PageIntercept>().unwrap();
+            assert!(page.count_ids.is_empty());
+            assert!(page.select_ids.is_empty());
+        };
+        block_on(f);
+    }
     impl_select_page!(MockTable{select_page_by_name(name:&str,account:&str) =>"
      if name != null && name != '':
        `where name != #{name}`
@@ -1202,4 +1246,995 @@ mod test {
         };
         block_on(f);
     }
+
+    #[test]
+    fn test_select_by_wrapper() {
+        let f = async move {
+            let mut rb = RBatis::new();
+            let queue = Arc::new(SyncVec::new());
+            rb.set_intercepts(vec![Arc::new(MockIntercept::new(queue.clone()))]);
+            rb.init(MockDriver {}, "test").unwrap();
+            let w = QueryWrapper::new()
+                .select(&["id", "name"])
+                .eq("status", 1)
+                .between("sort", 1, 10)
+                .and(|w| w.like_right("name", "a").or(|w| w.is_null("remark")))
+                .not_in("id", &["1", "2"])
+                .group_by(&["id", "name"])
+                .order_by_asc("id")
+                .limit(10);
+            let r = MockTable::select_by_wrapper(&rb, &w).await.unwrap();
+            let (sql, args) = queue.pop().unwrap();
+            assert_eq!(
+                sql,
+                "select id,name from mock_table where status = ? and sort between ? and ? and (name like ? or (remark is null)) and id not in (?,?) group by id,name order by id asc limit 10"
+            );
+            assert_eq!(
+                args,
+                vec![
+                    to_value!(1),
+                    to_value!(1),
+                    to_value!(10),
+                    to_value!("a%"),
+                    to_value!("1"),
+                    to_value!("2"),
+                ]
+            );
+            let r = MockTable::count_by_wrapper(&rb, &w).await.unwrap();
+            let (sql, args) = queue.pop().unwrap();
+            assert_eq!(
+                sql,
+                "select count(1) as count from (select id,name from mock_table where status = ? and sort between ? and ? and (name like ? or (remark is null)) and id not in (?,?) group by id,name) t"
+            );
+            assert_eq!(args.len(), 6);
+
+            let r = MockTable::count_by_wrapper(&rb, &QueryWrapper::new().in_("id", &[1, 2]))
+                .await
+                .unwrap();
+            assert_eq!(r, 1);
+            let (sql, args) = queue.pop().unwrap();
+            assert_eq!(
+                sql,
+                "select count(1) as count from mock_table where id in (?,?)"
+            );
+
+            let r = MockTable::select_by_wrapper(&rb, &QueryWrapper::new().eq("id;drop", 1)).await;
+            assert!(r.is_err());
+        };
+        block_on(f);
+    }
+
+    #[test]
+    fn test_update_delete_by_wrapper() {
+        let f = async move {
+            let mut rb = RBatis::new();
+            let queue = Arc::new(SyncVec::new());
+            rb.set_intercepts(vec![Arc::new(MockIntercept::new(queue.clone()))]);
+            rb.init(MockDriver {}, "test").unwrap();
+            let t = MockTable {
+                id: None,
+                name: Some("2".into()),
+                pc_link: None,
+                h5_link: None,
+                pc_banner_img: None,
+                h5_banner_img: None,
+                sort: None,
+                status: Some(2),
+                remark: None,
+                create_time: None,
+                version: None,
+                delete_flag: None,
+                count: 0,
+            };
+            let w = QueryWrapper::new().ge("version", 1).ne("status", 0);
+            let r = MockTable::update_by_wrapper(&rb, &t, &w, true)
+                .await
+                .unwrap();
+            let (sql, args) = queue.pop().unwrap();
+            assert_eq!(
+                sql,
+                "update mock_table set name=?,status=?,count=? where version >= ? and status <> ?"
+            );
+            assert_eq!(
+                args,
+                vec![
+                    to_value!("2"),
+                    to_value!(2),
+                    to_value!(0u64),
+                    to_value!(1),
+                    to_value!(0)
+                ]
+            );
+            let r = MockTable::delete_by_wrapper(&rb, &w).await.unwrap();
+            let (sql, args) = queue.pop().unwrap();
+            assert_eq!(
+                sql,
+                "delete from mock_table where version >= ? and status <> ?"
+            );
+            assert_eq!(args, vec![to_value!(1), to_value!(0)]);
+            //empty where is rejected
+            let r = MockTable::delete_by_wrapper(&rb, &QueryWrapper::new()).await;
+            assert!(r.is_err());
+            let r = MockTable::update_by_wrapper(&rb, &t, &QueryWrapper::new(), true).await;
+            assert!(r.is_err());
+        };
+        block_on(f);
+    }
+
+    #[derive(Clone, Debug, serde::Serialize, serde::Deserialize, rbatis::Columns)]
+    struct MockRename {
+        pub id: Option<i64>,
+        #[serde(rename = "type")]
+        pub kind: Option<String>,
+        #[serde(skip)]
+        pub cache: Option<String>,
+    }
+
+    #[derive(Clone, Debug, serde::Serialize, serde::Deserialize, rbatis::Columns)]
+    #[serde(rename_all = "camelCase")]
+    pub struct MockRenameAll {
+        pub id: Option<i64>,
+        pub create_time: Option<String>,
+        #[serde(rename = "kind")]
+        pub pc_link: Option<String>,
+    }
+
+    #[test]
+    fn test_columns_derive() {
+        assert_eq!(MockTable::COL_PC_LINK.name(), "pc_link");
+        assert_eq!(MockTable::COLUMNS.len(), 13);
+        assert_eq!(MockRename::COL_KIND.name(), "type");
+        assert_eq!(MockRename::COLUMNS, &["id", "type"]);
+        assert_eq!(MockRenameAll::COL_CREATE_TIME.name(), "createTime");
+        assert_eq!(MockRenameAll::COLUMNS, &["id", "createTime", "kind"]);
+        let v = to_value!(MockRenameAll {
+            id: None,
+            create_time: None,
+            pc_link: None,
+        });
+        let keys: Vec<String> = v.into_iter().map(|(k, _)| k.into_string().unwrap()).collect();
+        assert_eq!(keys, MockRenameAll::COLUMNS);
+        let w = QueryWrapper::new()
+            .eq(MockRename::COL_ID, 1)
+            .like(MockRename::COL_KIND, "a")
+            .order_by_desc(MockRename::COL_ID);
+        let (sql, args) = w.to_select_sql("mysql", "mock_rename").unwrap();
+        assert_eq!(
+            sql,
+            "select * from mock_rename where id = ? and type like ? order by id desc"
+        );
+        assert_eq!(args, vec![to_value!(1), to_value!("%a%")]);
+    }
+
+    #[test]
+    fn test_select_by_typed_column() {
+        let f = async move {
+            let mut rb = RBatis::new();
+            let queue = Arc::new(SyncVec::new());
+            rb.set_intercepts(vec![Arc::new(MockIntercept::new(queue.clone()))]);
+            rb.init(MockDriver {}, "test").unwrap();
+            let r = MockTable::select_by_column(&rb, MockTable::COL_ID, "1")
+                .await
+                .unwrap();
+            let (sql, args) = queue.pop().unwrap();
+            assert_eq!(sql.trim(), "select * from mock_table  where id = ?");
+            assert_eq!(args, vec![to_value!("1")]);
+            let r = MockTable::delete_in_column(&rb, MockTable::COL_STATUS, &[1, 2])
+                .await
+                .unwrap();
+            let (sql, args) = queue.pop().unwrap();
+            assert_eq!(sql.trim(), "delete from mock_table where status in (?,?)");
+            let column = "id".to_string();
+            let r = MockTable::select_by_column(&rb, &column, "1")
+                .await
+                .unwrap();
+            let (sql, args) = queue.pop().unwrap();
+            assert_eq!(sql.trim(), "select * from mock_table  where id = ?");
+
+            let r = MockTable::select_by_column(&rb, "id = 1 or 1", "1").await;
+            assert!(r.is_err());
+            let r = MockTable::delete_by_column(&rb, "id;drop table mock_table", "1").await;
+            assert!(r.is_err());
+            let r = MockTable::delete_in_column(&rb, "id)", &["1"]).await;
+            assert!(r.is_err());
+            assert!(queue.is_empty());
+        };
+        block_on(f);
+    }
+
+    #[test]
+    fn test_by_map() {
+        let f = async move {
+            let mut rb = RBatis::new();
+            let queue = Arc::new(SyncVec::new());
+            rb.set_intercepts(vec![Arc::new(MockIntercept::new(queue.clone()))]);
+            rb.init(MockDriver {}, "test").unwrap();
+            let map = to_value! {"status": 1, "id": ["1", "2"], "remark": Value::Null};
+            let r = MockTable::select_by_map(&rb, map.clone()).await.unwrap();
+            let (sql, args) = queue.pop().unwrap();
+            assert_eq!(
+                sql,
+                "select * from mock_table where status = ? and id in (?,?) and remark is null"
+            );
+            assert_eq!(args, vec![to_value!(1), to_value!("1"), to_value!("2")]);
+
+            let r = MockTable::count_by_map(&rb, map.clone()).await.unwrap();
+            let (sql, args) = queue.pop().unwrap();
+            assert_eq!(
+                sql,
+                "select count(1) as count from mock_table where status = ? and id in (?,?) and remark is null"
+            );
+
+            let r = MockTable::delete_by_map(&rb, to_value! {"id": Value::Array(vec![])})
+                .await
+                .unwrap();
+            let (sql, args) = queue.pop().unwrap();
+            assert_eq!(sql, "delete from mock_table where 1 <> 1");
+            assert!(args.is_empty());
+
+            let t = MockTable {
+                id: None,
+                name: Some("2".into()),
+                pc_link: None,
+                h5_link: None,
+                pc_banner_img: None,
+                h5_banner_img: None,
+                sort: None,
+                status: None,
+                remark: None,
+                create_time: None,
+                version: None,
+                delete_flag: None,
+                count: 0,
+            };
+            let r = MockTable::update_by_map(&rb, &t, to_value! {"id": "1"})
+                .await
+                .unwrap();
+            let (sql, args) = queue.pop().unwrap();
+            assert_eq!(sql, "update mock_table set name=?,count=? where id = ?");
+            assert_eq!(args, vec![to_value!("2"), to_value!(0u64), to_value!("1")]);
+
+            //unknown column is rejected
+            let r = MockTable::select_by_map(&rb, to_value! {"id = 1 or 1": 1}).await;
+            assert!(r.is_err());
+            let r = MockTable::delete_by_map(&rb, to_value! {"not_exist": 1}).await;
+            assert!(r.is_err());
+            //empty where is rejected
+            let r = MockTable::delete_by_map(&rb, to_value! {}).await;
+            assert!(r.is_err());
+        };
+        block_on(f);
+    }
+
+    #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+    struct MockTableBrief {
+        pub id: Option<String>,
+        #[serde(rename = "name")]
+        pub title: Option<String>,
+    }
+
+    impl_select_page!(MockTable{select_page_brief(name:&str) -> MockTableBrief => "`where name = #{name}`"});
+
+    #[test]
+    fn test_select_as() {
+        let f = async move {
+            let mut rb = RBatis::new();
+            let queue = Arc::new(SyncVec::new());
+            rb.set_intercepts(vec![
+                Arc::new(PageIntercept::new()),
+                Arc::new(MockIntercept::new(queue.clone())),
+            ]);
+            rb.init(MockDriver {}, "test").unwrap();
+            let r: Vec<MockTableBrief> = MockTable::select_all_as(&rb).await.unwrap();
+            let (sql, args) = queue.pop().unwrap();
+            assert_eq!(sql, "select id,name from mock_table");
+
+            let r: Vec<MockTableBrief> = MockTable::select_by_column_as(&rb, "id", "1")
+                .await
+                .unwrap();
+            let (sql, args) = queue.pop().unwrap();
+            assert_eq!(sql, "select id,name from mock_table where id = ?");
+            assert_eq!(args, vec![to_value!("1")]);
+
+            let r = MockTable::select_in_column_as::<MockTableBrief, _, _>(&rb, "id", &["1", "2"])
+                .await
+                .unwrap();
+            let (sql, args) = queue.pop().unwrap();
+            assert_eq!(sql, "select id,name from mock_table where id in (?,?)");
+
+            //explicit columns
+            let w = QueryWrapper::new().select(&["id"]).eq("status", 1);
+            let r: Vec<MockTableBrief> = MockTable::select_by_wrapper_as(&rb, &w).await.unwrap();
+            let (sql, args) = queue.pop().unwrap();
+            assert_eq!(sql, "select id from mock_table where status = ?");
+
+            let page = MockTable::select_page_brief(&rb, &PageRequest::new(1, 10), "a")
+                .await
+                .unwrap();
+            let (sql, args) = queue.pop().unwrap();
+            assert_eq!(
+                sql,
+                "select id,name from mock_table where name = ? limit 0,10 "
+            );
+            let (sql, args) = queue.pop().unwrap();
+            assert_eq!(
+                sql,
+                "select count(1) as count from mock_table where name = ?"
+            );
+        };
+        block_on(f);
+    }
+
+    #[test]
+    fn test_select_page_naming() {
+        let f = async move {
+            let mut rb = RBatis::new();
+            let queue = Arc::new(SyncVec::new());
+            rb.set_intercepts(vec![
+                Arc::new(PageIntercept::new()),
+                Arc::new(MockIntercept::new(queue.clone())),
+            ]);
+            rb.naming.set_table(
+                "mock_table",
+                NamingStrategy::new(NameCase::Pascal).rename("name", "sql"),
+            );
+            rb.init(MockDriver {}, "test").unwrap();
+            let page = MockTable::select_page_brief(&rb, &PageRequest::new(1, 10), "a")
+                .await
+                .unwrap();
+            let (sql, _) = queue.pop().unwrap();
+            assert_eq!(sql, "select Id,sql from mock_table where name = ? limit 0,10 ");
+            assert_eq!(page.total, 1);
+            //the column `sql` is decoded into the field `name`
+            assert_eq!(page.records[0].title.as_deref(), Some(sql.as_str()));
+            let page = MockTable::select_page(&rb, &PageRequest::new(1, 10), "a")
+                .await
+                .unwrap();
+            let (sql, _) = queue.pop().unwrap();
+            assert_eq!(
+                sql,
+                "select * from mock_table order by create_time desc limit 0,10 "
+            );
+            assert_eq!(page.records[0].name.as_deref(), Some(sql.as_str()));
+            assert_eq!(page.records[0].count, 1);
+        };
+        block_on(f);
+    }
+
+    #[test]
+    fn test_count_exists_aggregate() {
+        let f = async move {
+            let mut rb = RBatis::new();
+            let queue = Arc::new(SyncVec::new());
+            rb.set_intercepts(vec![Arc::new(MockIntercept::new(queue.clone()))]);
+            rb.init(MockDriver {}, "test").unwrap();
+            let r = MockTable::count_all(&rb).await.unwrap();
+            assert_eq!(r, 1);
+            let (sql, args) = queue.pop().unwrap();
+            assert_eq!(sql, "select count(1) as count from mock_table");
+
+            let r = MockTable::count_by_column(&rb, MockTable::COL_STATUS, 1)
+                .await
+                .unwrap();
+            assert_eq!(r, 1);
+            let (sql, args) = queue.pop().unwrap();
+            assert_eq!(
+                sql,
+                "select count(1) as count from mock_table where status = ?"
+            );
+            assert_eq!(args, vec![to_value!(1)]);
+
+            let r = MockTable::exists_by_column(&rb, "id", "1").await.unwrap();
+            assert!(r);
+            let (sql, args) = queue.pop().unwrap();
+            assert_eq!(sql, "select 1 from mock_table where id = ? limit 1");
+
+            let w = QueryWrapper::new().eq("status", 1);
+            let r: Option<u64> = MockTable::sum_by(&rb, "sort", &w).await.unwrap();
+            assert_eq!(r, Some(1));
+            let (sql, args) = queue.pop().unwrap();
+            assert_eq!(
+                sql,
+                "select sum(sort) as agg_value from mock_table where status = ?"
+            );
+            assert_eq!(args, vec![to_value!(1)]);
+
+            let r: f64 = MockTable::avg_by(&rb, MockTable::COL_SORT, &QueryWrapper::new())
+                .await
+                .unwrap();
+            assert_eq!(r, 1.0);
+            let (sql, args) = queue.pop().unwrap();
+            assert_eq!(sql, "select avg(sort) as agg_value from mock_table");
+
+            let r: HashMap<String, u64> =
+                MockTable::aggregate_group_by(&rb, "max", MockTable::COL_SORT, "name", &w)
+                    .await
+                    .unwrap();
+            let (sql, args) = queue.pop().unwrap();
+            assert_eq!(
+                sql,
+                "select name as agg_key,max(sort) as agg_value from mock_table where status = ? group by name"
+            );
+            assert_eq!(r.get(&sql), Some(&1));
+
+            let r: Result<u64, Error> = MockTable::aggregate_by(&rb, "sleep", "sort", &w).await;
+            assert!(r.is_err());
+            let r: Result<u64, Error> = MockTable::min_by(&rb, "sort;drop", &w).await;
+            assert!(r.is_err());
+        };
+        block_on(f);
+    }
+
+    #[test]
+    fn test_select_for_update() {
+        let f = async move {
+            let mut rb = RBatis::new();
+            let queue = Arc::new(SyncVec::new());
+            rb.set_intercepts(vec![Arc::new(MockIntercept::new(queue.clone()))]);
+            rb.init(MockDriver {}, "test").unwrap();
+            let r = MockTable::select_by_column_for_update(&rb, "id", "1").await;
+            assert!(r.is_err());
+            assert!(queue.is_empty());
+
+            let tx = rb.acquire_begin().await.unwrap();
+            queue.clear();
+            let r = MockTable::select_by_column_for_update(&tx, "id", "1")
+                .await
+                .unwrap();
+            let (sql, args) = queue.pop().unwrap();
+            assert_eq!(sql, "select * from mock_table where id = ? for update");
+            assert_eq!(args, vec![to_value!("1")]);
+
+            let w = QueryWrapper::new().eq("status", 0).limit(10);
+            let r = MockTable::select_for_update_skip_locked(&tx, &w)
+                .await
+                .unwrap();
+            let (sql, args) = queue.pop().unwrap();
+            assert_eq!(
+                sql,
+                "select * from mock_table where status = ? limit 10 for update skip locked"
+            );
+
+            let (sql, _) = w
+                .clone()
+                .for_update_skip_locked()
+                .to_select_sql("mssql", "mock_table")
+                .unwrap();
+            assert_eq!(
+                sql,
+                "select * from mock_table with (updlock,rowlock,readpast) where status = ? order by (select null) offset 0 rows fetch next 10 rows only"
+            );
+            let (sql, _) = w
+                .clone()
+                .lock(LockMode::ForUpdateNoWait)
+                .to_select_sql("pg", "mock_table")
+                .unwrap();
+            assert_eq!(
+                sql,
+                "select * from mock_table where status = ? limit 10 for update nowait"
+            );
+            assert!(w
+                .clone()
+                .for_update()
+                .to_select_sql("sqlite", "mock_table")
+                .is_err());
+        };
+        block_on(f);
+    }
+
+    #[test]
+    fn test_insert_returning() {
+        let f = async move {
+            let mut rb = RBatis::new();
+            let queue = Arc::new(SyncVec::new());
+            rb.set_intercepts(vec![Arc::new(MockIntercept::new(queue.clone()))]);
+            rb.init(MockDriver {}, "test").unwrap();
+            let t = MockTable {
+                id: Some("2".into()),
+                name: Some("2".into()),
+                pc_link: None,
+                h5_link: None,
+                pc_banner_img: None,
+                h5_banner_img: None,
+                sort: None,
+                status: Some(2),
+                remark: None,
+                create_time: None,
+                version: None,
+                delete_flag: None,
+                count: 0,
+            };
+            let r = MockTable::insert_returning(&rb, &t, "id").await.unwrap();
+            assert_eq!(r.count, 1);
+            let (sql, args) = queue.pop().unwrap();
+            assert_eq!(
+                sql,
+                "insert into mock_table (id,name,status,count) values (?,?,?,?) returning *"
+            );
+            assert_eq!(
+                args,
+                vec![
+                    to_value!("2"),
+                    to_value!("2"),
+                    to_value!(2),
+                    to_value!(0u64)
+                ]
+            );
+
+            let mut t2 = t.clone();
+            t2.id = None;
+            t2.remark = Some("3".into());
+            let ts = vec![t.clone(), t2.clone(), t];
+            let r = MockTable::insert_batch_returning(&rb, &ts, 2, "id")
+                .await
+                .unwrap();
+            assert_eq!(r.len(), 2);
+            let (sql, args) = queue.pop().unwrap();
+            assert_eq!(
+                sql,
+                "insert into mock_table (id,name,status,count) values (?,?,?,?) returning *"
+            );
+            let (sql, args) = queue.pop().unwrap();
+            assert_eq!(
+                sql,
+                "insert into mock_table (id,name,status,remark,count) values (?,?,?,?,?),(?,?,?,?,?) returning *"
+            );
+            assert_eq!(args.len(), 10);
+            assert_eq!(args[5], Value::Null);
+
+            let r = MockTable::insert_batch_returning_ids(&rb, &ts, 10, "id")
+                .await
+                .unwrap();
+            assert_eq!(r, vec![Value::Null]);
+            let (sql, args) = queue.pop().unwrap();
+            assert_eq!(
+                sql,
+                "insert into mock_table (id,name,status,remark,count) values (?,?,?,?,?),(?,?,?,?,?),(?,?,?,?,?) returning id"
+            );
+            assert!(MockTable::insert_batch_returning_ids(&rb, &ts, 10, "id;")
+                .await
+                .is_err());
+            assert!(MockTable::insert_batch_returning(&rb, &ts, 10, "id;")
+                .await
+                .is_err());
+            assert!(MockTable::insert_batch_returning(&rb, &[], 10, "id")
+                .await
+                .is_err());
+        };
+        block_on(f);
+    }
+
+    #[test]
+    fn test_batch_limit_split() {
+        let f = async move {
+            let mut rb = RBatis::new();
+            rb.batch_limit.max_params = Some(3);
+            let queue = Arc::new(SyncVec::new());
+            rb.set_intercepts(vec![Arc::new(MockIntercept::new(queue.clone()))]);
+            rb.init(MockDriver {}, "test").unwrap();
+            let r = MockTable::select_in_column(&rb, "id", &["1", "2", "3", "4"])
+                .await
+                .unwrap();
+            assert_eq!(r.len(), 2);
+            assert_eq!(queue.len(), 2);
+            let (sql, args) = queue.pop().unwrap();
+            assert_eq!(sql, "select * from mock_table  where id in (?)");
+            assert_eq!(args, vec![to_value!("4")]);
+            let (sql, args) = queue.pop().unwrap();
+            assert_eq!(sql, "select * from mock_table  where id in (?,?,?)");
+
+            let r = MockTable::delete_in_column(&rb, "id", &["1", "2", "3", "4"])
+                .await
+                .unwrap();
+            assert_eq!(queue.len(), 2);
+            let (sql, args) = queue.pop().unwrap();
+            assert_eq!(sql, "delete from mock_table where id in (?)");
+            let (sql, args) = queue.pop().unwrap();
+            assert_eq!(sql, "delete from mock_table where id in (?,?,?)");
+            assert_eq!(args, vec![to_value!("1"), to_value!("2"), to_value!("3")]);
+
+            //13 columns, at least one row
+            rb.batch_limit.max_params = Some(30);
+            let t = MockTable {
+                id: Some("2".into()),
+                name: Some("2".into()),
+                pc_link: None,
+                h5_link: None,
+                pc_banner_img: None,
+                h5_banner_img: None,
+                sort: None,
+                status: None,
+                remark: None,
+                create_time: None,
+                version: None,
+                delete_flag: None,
+                count: 0,
+            };
+            let ts = vec![t.clone(), t.clone(), t.clone(), t];
+            let r = MockTable::insert_batch(&rb, &ts, 10).await.unwrap();
+            assert_eq!(queue.len(), 2);
+            let (sql, args) = queue.pop().unwrap();
+            assert_eq!(
+                sql,
+                "insert into mock_table (id,name,count) VALUES (?,?,?),(?,?,?)"
+            );
+            queue.clear();
+
+            rb.batch_limit.max_params = None;
+            rb.batch_limit.max_sql_len = Some(80);
+            let r = MockTable::insert_batch(&rb, &ts, 10).await.unwrap();
+            assert_eq!(queue.len(), 4);
+            queue.clear();
+
+            assert_eq!(
+                rb.batch_limit.ranges("mssql", 5000, 0, 1, 0, 0),
+                vec![(0, 2098), (2098, 4196), (4196, 5000)]
+            );
+            assert_eq!(
+                rb.batch_limit.ranges("sqlite", 5, 2, 1, 0, 0),
+                vec![(0, 2), (2, 4), (4, 5)]
+            );
+        };
+        block_on(f);
+    }
+
+    #[test]
+    fn test_naming() {
+        let f = async move {
+            let mut rb = RBatis::new();
+            let queue = Arc::new(SyncVec::new());
+            rb.set_intercepts(vec![Arc::new(MockIntercept::new(queue.clone()))]);
+            rb.naming.set_table(
+                "mock_table",
+                NamingStrategy::new(NameCase::Pascal).rename("name", "sql"),
+            );
+            rb.init(MockDriver {}, "test").unwrap();
+            let t = MockTable {
+                id: Some("2".into()),
+                name: Some("2".into()),
+                pc_link: None,
+                h5_link: Some("2".into()),
+                pc_banner_img: None,
+                h5_banner_img: None,
+                sort: None,
+                status: Some(2),
+                remark: None,
+                create_time: None,
+                version: None,
+                delete_flag: None,
+                count: 0,
+            };
+            MockTable::insert(&rb, &t).await.unwrap();
+            let (sql, _) = queue.pop().unwrap();
+            assert_eq!(
+                sql,
+                "insert into mock_table (Id,sql,H5Link,Status,Count) VALUES (?,?,?,?,?)"
+            );
+            MockTable::update_by_column(&rb, &t, "id").await.unwrap();
+            let (sql, args) = queue.pop().unwrap();
+            assert_eq!(
+                sql,
+                "update mock_table set sql=?,H5Link=?,Status=?,Count=? where Id = ?"
+            );
+            assert_eq!(args.last(), Some(&to_value!("2")));
+            //the columns are selected as the fields
+            let columns = "Id as id,sql as name,PcLink as pc_link,H5Link as h5_link,\
+                PcBannerImg as pc_banner_img,H5BannerImg as h5_banner_img,Sort as sort,\
+                Status as status,Remark as remark,CreateTime as create_time,\
+                Version as version,DeleteFlag as delete_flag,Count as count";
+            let r = MockTable::select_all(&rb).await.unwrap();
+            assert_eq!(r[0].count, 1);
+            let (sql, _) = queue.pop().unwrap();
+            assert_eq!(sql, format!("select {} from mock_table ", columns));
+            //the where columns are mapped too
+            let r = MockTable::select_by_column(&rb, "pc_link", "a").await.unwrap();
+            assert_eq!(r[0].count, 1);
+            let (sql, _) = queue.pop().unwrap();
+            assert_eq!(
+                sql,
+                format!("select {} from mock_table  where PcLink = ?", columns)
+            );
+            MockTable::select_in_column(&rb, "h5_link", &["a"])
+                .await
+                .unwrap();
+            let (sql, _) = queue.pop().unwrap();
+            assert_eq!(
+                sql,
+                format!("select {} from mock_table  where H5Link in (?)", columns)
+            );
+            MockTable::count_by_column(&rb, "create_time", 1)
+                .await
+                .unwrap();
+            let (sql, _) = queue.pop().unwrap();
+            assert_eq!(
+                sql,
+                "select count(1) as count from mock_table where CreateTime = ?"
+            );
+            MockTable::exists_by_column(&rb, "id", "1").await.unwrap();
+            let (sql, _) = queue.pop().unwrap();
+            assert_eq!(sql, "select 1 from mock_table where Id = ? limit 1");
+            let w = QueryWrapper::new()
+                .eq("status", 1)
+                .or(|w| w.is_null("delete_flag"))
+                .order_by_desc("create_time");
+            MockTable::select_by_wrapper(&rb, &w).await.unwrap();
+            let (sql, _) = queue.pop().unwrap();
+            assert_eq!(
+                sql,
+                "select * from mock_table where Status = ? or (DeleteFlag is null) order by CreateTime desc"
+            );
+            MockTable::delete_by_column(&rb, "name", "a").await.unwrap();
+            let (sql, _) = queue.pop().unwrap();
+            assert_eq!(sql, "delete from mock_table where sql = ?");
+            MockTable::delete_in_column(&rb, "id", &["1"]).await.unwrap();
+            let (sql, _) = queue.pop().unwrap();
+            assert_eq!(sql, "delete from mock_table where Id in (?)");
+            MockTable::delete_by_wrapper(&rb, &w).await.unwrap();
+            let (sql, _) = queue.pop().unwrap();
+            assert_eq!(
+                sql,
+                "delete from mock_table where Status = ? or (DeleteFlag is null)"
+            );
+            //other tables are not changed
+            assert_eq!(rb.naming_of("other").to_column("pc_link"), "pc_link");
+            //the clones(connections) of RBatis share the naming
+            let conn = rb.acquire().await.unwrap();
+            rb.naming.set_table("other", NamingStrategy::new(NameCase::UpperSnake));
+            assert_eq!(conn.rb.naming_of("other").to_column("pc_link"), "PC_LINK");
+        };
+        block_on(f);
+    }
+
+    #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+    struct MockHooked {
+        pub id: Option<String>,
+        pub name: Option<String>,
+        pub sql: Option<String>,
+        pub count: u64,
+    }
+    crud!(MockHooked {});
+
+    impl TableHooks for MockHooked {
+        fn before_insert(&mut self, _executor: &dyn Executor) -> Result<(), Error> {
+            self.name = self.name.as_ref().map(|v| v.trim().to_string());
+            if self.name.as_deref() == Some("") {
+                return Err(Error::from("name is empty"));
+            }
+            Ok(())
+        }
+
+        fn before_update(&mut self, _executor: &dyn Executor) -> Result<(), Error> {
+            self.count = 9;
+            Ok(())
+        }
+
+        fn after_load(&mut self, _executor: &dyn Executor) -> Result<(), Error> {
+            self.count += 100;
+            Ok(())
+        }
+
+        fn before_delete(_executor: &dyn Executor, condition: &QueryWrapper) -> Result<(), Error> {
+            let (sql, _) = condition.to_delete_sql("mock_hooked")?;
+            if sql.contains("name") {
+                return Err(Error::from("delete by name is not allowed"));
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_table_hooks() {
+        let f = async move {
+            let mut rb = RBatis::new();
+            let queue = Arc::new(SyncVec::new());
+            rb.set_intercepts(vec![Arc::new(MockIntercept::new(queue.clone()))]);
+            rb.init(MockDriver {}, "test").unwrap();
+            let t = MockHooked {
+                id: Some("1".into()),
+                name: Some(" a ".into()),
+                sql: None,
+                count: 0,
+            };
+            MockHooked::insert(&rb, &t).await.unwrap();
+            let (sql, args) = queue.pop().unwrap();
+            assert_eq!(
+                sql,
+                "insert into mock_hooked (id,name,count) VALUES (?,?,?)"
+            );
+            assert_eq!(args, vec![to_value!("1"), to_value!("a"), to_value!(0u64)]);
+            //the argument is not changed
+            assert_eq!(t.name.as_deref(), Some(" a "));
+            //an error aborts the statement
+            let mut empty = t.clone();
+            empty.name = Some(" ".into());
+            let r = MockHooked::insert_batch(&rb, &[t.clone(), empty], 10).await;
+            assert_eq!(r.err().unwrap().to_string(), "name is empty");
+            assert!(queue.pop().is_none());
+
+            MockHooked::update_by_column(&rb, &t, "id").await.unwrap();
+            let (sql, args) = queue.pop().unwrap();
+            assert_eq!(sql, "update mock_hooked set name=?,count=? where id = ?");
+            assert_eq!(args[1], to_value!(9u64));
+            let w = QueryWrapper::new().eq("id", "1");
+            MockHooked::update_by_wrapper(&rb, &t, &w, true)
+                .await
+                .unwrap();
+            let (_, args) = queue.pop().unwrap();
+            assert_eq!(args[2], to_value!(9u64));
+
+            let r = MockHooked::select_all(&rb).await.unwrap();
+            assert_eq!(r[0].count, 101);
+            let r = MockHooked::select_by_wrapper(&rb, &w).await.unwrap();
+            assert_eq!(r[0].count, 101);
+            let r = MockHooked::select_in_column(&rb, "id", &["1"])
+                .await
+                .unwrap();
+            assert_eq!(r[0].count, 101);
+
+            MockHooked::delete_by_column(&rb, "id", "1").await.unwrap();
+            let (sql, _) = queue.pop().unwrap();
+            assert_eq!(sql, "delete from mock_hooked where id = ?");
+            let len = queue.len();
+            let r = MockHooked::delete_by_column(&rb, "name", "a").await;
+            assert!(r.is_err());
+            let r = MockHooked::delete_in_column(&rb, "name", &["a"]).await;
+            assert!(r.is_err());
+            let r = MockHooked::delete_by_wrapper(&rb, &QueryWrapper::new().eq("name", "a")).await;
+            assert!(r.is_err());
+            assert_eq!(queue.len(), len);
+        };
+        block_on(f);
+    }
+
+    #[test]
+    fn test_update_changed() {
+        let f = async move {
+            let mut rb = RBatis::new();
+            let queue = Arc::new(SyncVec::new());
+            rb.set_intercepts(vec![Arc::new(MockIntercept::new(queue.clone()))]);
+            rb.init(MockDriver {}, "test").unwrap();
+            let mut rows = MockTable::select_by_column_tracked(&rb, "id", "1")
+                .await
+                .unwrap();
+            queue.pop().unwrap();
+            let row = &mut rows[0];
+            assert!(!row.is_changed());
+            //nothing is executed
+            let r = MockTable::update_changed(&rb, row, "id").await.unwrap();
+            assert_eq!(r.rows_affected, 0);
+            assert!(queue.pop().is_none());
+            row.name = Some("n".into());
+            MockTable::update_changed(&rb, row, "id").await.unwrap();
+            let (sql, args) = queue.pop().unwrap();
+            assert_eq!(sql, "update mock_table set name=? where id = ?");
+            assert_eq!(args, vec![to_value!("n"), Value::Null]);
+
+            let mut t = Tracked::new(MockTable {
+                id: Some("2".into()),
+                name: Some("2".into()),
+                pc_link: Some("2".into()),
+                h5_link: None,
+                pc_banner_img: None,
+                h5_banner_img: None,
+                sort: None,
+                status: Some(2),
+                remark: None,
+                create_time: None,
+                version: None,
+                delete_flag: None,
+                count: 0,
+            });
+            //set null explicitly, the condition column is not changed
+            t.pc_link = None;
+            t.status = Some(3);
+            t.id = Some("3".into());
+            MockTable::update_changed(&rb, &t, MockTable::COL_ID)
+                .await
+                .unwrap();
+            let (sql, args) = queue.pop().unwrap();
+            assert_eq!(sql, "update mock_table set pc_link=?,status=? where id = ?");
+            assert_eq!(args, vec![Value::Null, to_value!(3), to_value!("2")]);
+            t.reset();
+            assert!(!t.is_changed());
+
+            //decode the rows of raw sql
+            let rows: Vec<Tracked<MockTable>> = rb
+                .query_decode("select * from mock_table", vec![])
+                .await
+                .unwrap();
+            assert_eq!(rows[0].snapshot()["count"], to_value!(1u64));
+        };
+        block_on(f);
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, rbatis::DbEnum)]
+    enum MockStatus {
+        #[db(int = 1)]
+        Enabled,
+        #[db(int = 0)]
+        Disabled,
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, rbatis::DbEnum)]
+    enum MockLevel {
+        #[db(str = "low")]
+        Low,
+        #[db(str = "high")]
+        High,
+    }
+
+    #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+    struct MockEnumTable {
+        pub id: Option<String>,
+        pub status: Option<MockStatus>,
+        pub level: Option<MockLevel>,
+    }
+    crud!(MockEnumTable {}, "mock_table");
+
+    #[test]
+    fn test_db_enum() {
+        use rbatis::db_enum::DbEnum;
+        use rbatis::table_sync::{
+            MssqlTableMapper, MysqlTableMapper, PGTableMapper, SqliteTableMapper,
+        };
+        let f = async move {
+            let mut rb = RBatis::new();
+            let queue = Arc::new(SyncVec::new());
+            rb.set_intercepts(vec![Arc::new(MockIntercept::new(queue.clone()))]);
+            rb.init(MockDriver {}, "test").unwrap();
+            let t = MockEnumTable {
+                id: Some("1".into()),
+                status: Some(MockStatus::Disabled),
+                level: Some(MockLevel::High),
+            };
+            MockEnumTable::insert(&rb, &t).await.unwrap();
+            let (sql, args) = queue.pop().unwrap();
+            assert_eq!(
+                sql,
+                "insert into mock_table (id,status,level) VALUES (?,?,?)"
+            );
+            assert_eq!(args, vec![to_value!("1"), Value::I32(0), to_value!("high")]);
+            MockEnumTable::select_by_column(&rb, "status", MockStatus::Enabled)
+                .await
+                .unwrap();
+            let (sql, args) = queue.pop().unwrap();
+            assert_eq!(args, vec![Value::I32(1)]);
+
+            //the mock row is {"sql": sql, "count": 1u64}
+            #[derive(Debug, serde::Deserialize)]
+            struct StatusRow {
+                count: Option<MockStatus>,
+            }
+            let rows: Vec<StatusRow> = rb
+                .query_decode("select * from mock_table", vec![])
+                .await
+                .unwrap();
+            assert_eq!(rows[0].count, Some(MockStatus::Enabled));
+            #[derive(Debug, serde::Deserialize)]
+            struct LevelRow {
+                count: Option<MockLevel>,
+            }
+            let e = rb
+                .query_decode::<Vec<LevelRow>>("select * from mock_table", vec![])
+                .await
+                .unwrap_err();
+            assert!(
+                e.to_string()
+                    .contains(r#"[rb] unknown MockLevel value: 1, expected one of "low","high""#),
+                "{}",
+                e
+            );
+        };
+        block_on(f);
+        assert_eq!(
+            MockStatus::from_value(&to_value!("0")).unwrap(),
+            MockStatus::Disabled
+        );
+        assert_eq!(MockStatus::column_type(&MysqlTableMapper {}), "INT");
+        assert_eq!(MockStatus::column_type(&PGTableMapper {}), "INT4");
+        assert_eq!(MockLevel::column_type(&MysqlTableMapper {}), "VARCHAR(4)");
+        assert_eq!(MockLevel::column_type(&SqliteTableMapper {}), "TEXT");
+        assert_eq!(MockLevel::column_type(&MssqlTableMapper {}), "NVARCHAR(4)");
+        assert_eq!(serde_json::to_string(&MockLevel::Low).unwrap(), r#""low""#);
+    }
 }
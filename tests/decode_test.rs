@@ -26,7 +26,7 @@ mod test {
         let v = rbatis::decode::<A>(m).err().unwrap();
         assert_eq!(
             v.to_string(),
-            "invalid type: string \"\", expected i32, key = `aa`"
+            "[rb] decode decode_test::test::test_decode_type_fail::A fail at `[0].aa`(String \"\"): invalid type: string \"\", expected i32, key = `aa`"
         );
     }
 
@@ -39,7 +39,30 @@ mod test {
         let v = rbatis::decode::<i64>(m).err().unwrap();
         assert_eq!(
             v.to_string(),
-            "invalid type: floating point `0.0`, expected i64"
+            "[rb] decode i64 fail at `[0].aa`(F64 0): invalid type: floating point `0.0`, expected i64"
+        );
+    }
+
+    #[test]
+    fn test_decode_fail_path() {
+        #[derive(Serialize, Deserialize, Debug)]
+        pub struct B {
+            pub aa: i32,
+        }
+        #[derive(Serialize, Deserialize, Debug)]
+        pub struct A {
+            pub id: i64,
+            pub inner: Option<B>,
+        }
+        let m = Value::Array(vec![
+            to_value! {"id": 1, "inner": Value::Null},
+            to_value! {"id": 2, "inner": to_value!{"aa": "x"}},
+        ]);
+        let v = rbatis::decode::<Vec<A>>(m).err().unwrap().to_string();
+        assert!(
+            v.starts_with("[rb] decode alloc::vec::Vec<decode_test::test::test_decode_fail_path::A> fail at `[1].inner.aa`(String \"x\"): invalid type"),
+            "{}",
+            v
         );
     }
 
@@ -111,4 +134,86 @@ mod test {
             rbs::from_value(rbs::to_value!(datetime.clone())).unwrap();
         assert_eq!(datetime, datetime_new);
     }
+
+    #[test]
+    fn test_decode_json_column() {
+        use rbatis::json::Json;
+        #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+        pub struct Attrs {
+            pub color: Option<String>,
+            pub size: Option<i32>,
+        }
+        #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+        pub struct Goods {
+            pub id: Option<String>,
+            pub attrs: Option<Json<Attrs>>,
+        }
+        let attrs = Attrs {
+            color: Some("red".to_string()),
+            size: Some(1),
+        };
+        let text = r#"{"color":"red","size":1}"#;
+        assert_eq!(
+            to_value!(Json(attrs.clone())),
+            Value::Ext(
+                rbatis::json::JSON_VALUE_EXT,
+                Box::new(to_value! {"color": "red", "size": 1})
+            )
+        );
+        //the bind value of drivers
+        assert_eq!(
+            rbatis::json::encode_json_value(to_value!(Json(attrs.clone()))),
+            Value::Ext("Json", Box::new(Value::String(text.to_string())))
+        );
+        assert_eq!(serde_json::to_string(&Json(attrs.clone())).unwrap(), text);
+        //mysql json string, pg json value, sqlite blob, the bind value
+        for column in [
+            Value::String(text.to_string()),
+            to_value! {"color": "red", "size": 1},
+            Value::Binary(text.as_bytes().to_vec()),
+            to_value!(Json(attrs.clone())),
+            rbatis::json::encode_json_value(to_value!(Json(attrs.clone()))),
+        ] {
+            let v: Vec<Goods> = rbatis::decode(Value::Array(vec![to_value! {
+                "id": "1",
+                "attrs": column,
+            }]))
+            .unwrap();
+            assert_eq!(v[0].attrs.as_deref(), Some(&attrs));
+        }
+        let v: Vec<Goods> = rbatis::decode(Value::Array(vec![to_value! {
+            "id": "1",
+            "attrs": Value::Null,
+        }]))
+        .unwrap();
+        assert_eq!(v[0].attrs, None);
+        //the unquoted text of a json string
+        let v: Json<String> = rbs::from_value(Value::String("red".to_string())).unwrap();
+        assert_eq!(v.0, "red");
+        let v: Json<String> = rbs::from_value(Value::String(r#""red""#.to_string())).unwrap();
+        assert_eq!(v.0, "red");
+        assert!(rbs::from_value::<Json<Attrs>>(Value::String("{".to_string())).is_err());
+        //the bind value of a Json<String> is the quoted json string
+        let v: Json<String> = rbs::from_value(rbatis::json::encode_json_value(to_value!(Json(
+            r#""red""#.to_string()
+        ))))
+        .unwrap();
+        assert_eq!(v.0, r#""red""#);
+        //the values of in lists and batch rows
+        let json = Value::Ext("Json", Box::new(Value::String(text.to_string())));
+        let mut args = vec![
+            Value::Array(vec![to_value!(Json(attrs.clone())), to_value!(1)]),
+            to_value! {"id": "1", "attrs": Json(attrs.clone())},
+            to_value!(Json(attrs.clone())),
+        ];
+        rbatis::json::encode_json_args(&mut args);
+        assert_eq!(
+            args,
+            vec![
+                Value::Array(vec![json.clone(), to_value!(1)]),
+                to_value! {"id": "1", "attrs": json.clone()},
+                json,
+            ]
+        );
+    }
 }
@@ -1,11 +1,21 @@
+mod common;
+
 #[cfg(test)]
 mod test {
     use async_trait::async_trait;
+    use dark_std::sync::SyncVec;
     use futures_core::future::BoxFuture;
-    use log::{Log, Metadata, Record};
-    use rbatis::executor::Executor;
+    use log::{LevelFilter, Log, Metadata, Record};
+    use rbatis::executor::{BatchExecResult, Context, Executor};
     use rbatis::intercept::{Intercept, ResultType};
-    use rbatis::{Error, RBatis};
+    use rbatis::intercept_data_scope::DataScopeIntercept;
+    use rbatis::intercept_encrypt::{
+        is_encrypted_value, Cipher, EncryptIntercept, Encrypted, EncryptedEq,
+    };
+    use rbatis::intercept_guard::{SkipSqlGuard, SqlGuardIntercept};
+    use rbatis::intercept_log::{LogInterceptor, MaskRule};
+    use rbatis::intercept_page::PageIntercept;
+    use rbatis::{Error, PageRequest, RBatis};
     use rbdc::db::{ConnectOptions, Connection, Driver, ExecResult, MetaData, Row};
     use rbdc::rt::block_on;
     use rbs::Value;
@@ -127,14 +137,10 @@ mod test {
             })
         }
 
-        fn exec(
-            &mut self,
-            _sql: &str,
-            _params: Vec<Value>,
-        ) -> BoxFuture<Result<ExecResult, Error>> {
+        fn exec(&mut self, _sql: &str, params: Vec<Value>) -> BoxFuture<Result<ExecResult, Error>> {
             Box::pin(async move {
                 Ok(ExecResult {
-                    rows_affected: 0,
+                    rows_affected: params.len() as u64,
                     last_insert_id: Value::Null,
                 })
             })
@@ -223,4 +229,763 @@ mod test {
         m.inner.store(1, Ordering::SeqCst);
         assert_eq!(m.inner.load(Ordering::Relaxed), 1);
     }
+    #[derive(Debug)]
+    pub struct XorCipher {}
+
+    impl Cipher for XorCipher {
+        fn encrypt(&self, plain: &[u8], _deterministic: bool) -> Result<Vec<u8>, Error> {
+            Ok(plain.iter().map(|v| v ^ 0x5a).collect())
+        }
+
+        fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>, Error> {
+            Ok(data.iter().map(|v| v ^ 0x5a).collect())
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct CaptureIntercept {
+        pub sqls: SyncVec<String>,
+        pub args: SyncVec<Vec<Value>>,
+    }
+
+    #[async_trait]
+    impl Intercept for CaptureIntercept {
+        async fn before(
+            &self,
+            _task_id: i64,
+            _rb: &dyn Executor,
+            sql: &mut String,
+            args: &mut Vec<Value>,
+            _result: ResultType<&mut Result<ExecResult, Error>, &mut Result<Vec<Value>, Error>>,
+        ) -> Result<Option<bool>, Error> {
+            self.sqls.push(sql.clone());
+            self.args.push(args.clone());
+            Ok(Some(true))
+        }
+    }
+
+    #[test]
+    fn test_encrypt_intercept() {
+        let rb = RBatis::new();
+        rb.init(MockDriver {}, "test").unwrap();
+        rb.intercepts.clear();
+        rb.intercepts
+            .push(Arc::new(EncryptIntercept::new(XorCipher {}).column("sql")));
+        rb.intercepts.push(Arc::new(CaptureIntercept {
+            sqls: SyncVec::new(),
+            args: SyncVec::new(),
+        }));
+        let f = async move {
+            let arg = rbs::to_value!(Encrypted("13800000000".to_string()));
+            assert!(is_encrypted_value(&arg));
+            rb.exec("update user set phone = ?", vec![arg])
+                .await
+                .unwrap();
+            let capture = rb.get_intercept::<CaptureIntercept>().unwrap();
+            let args = capture.args.pop().unwrap();
+            let intercept = rb.get_intercept::<EncryptIntercept>().unwrap();
+            let cipher_text = args[0].clone();
+            assert!(cipher_text.is_str());
+            assert_ne!(cipher_text.as_str(), Some("13800000000"));
+            assert_eq!(
+                intercept.decrypt_value(&cipher_text).unwrap(),
+                Value::String("13800000000".to_string())
+            );
+            let v = intercept
+                .encrypt_value(Value::String("select 1".to_string()), true)
+                .unwrap();
+            assert_eq!(
+                v,
+                intercept
+                    .encrypt_value(Value::String("select 1".to_string()), true)
+                    .unwrap()
+            );
+            //mock row column `sql` is the query sql
+            let rows = rb
+                .query(v.as_str().unwrap_or_default(), vec![])
+                .await
+                .unwrap();
+            assert_eq!(rows[0]["sql"].as_str(), Some("select 1"));
+            #[derive(serde::Deserialize)]
+            struct SqlRow {
+                sql: String,
+            }
+            let row: SqlRow = rb
+                .query_decode(v.as_str().unwrap_or_default(), vec![])
+                .await
+                .unwrap();
+            assert_eq!(row.sql, "select 1");
+            let encrypted: Encrypted<String> =
+                rbs::from_value(Value::String("a".to_string())).unwrap();
+            assert_eq!(encrypted.into_inner(), "a");
+            assert_eq!(format!("{:?}", EncryptedEq("a")), "EncryptedEq(***)");
+            //only the Encrypted/EncryptedEq ext is unwrapped
+            let json = Value::Ext("Json", Box::new(Value::String("{}".to_string())));
+            assert!(intercept.encrypt_value(json, false).is_err());
+        };
+        block_on(f);
+    }
+
+    #[test]
+    fn test_encrypt_intercept_alias_legacy() {
+        //the column `p` is the ciphertext of `13800000000`, the column `legacy` is a plaintext
+        let driver = crate::common::MockDriver::new().query(|_, _| {
+            let mut plain = vec![b'S'];
+            plain.extend_from_slice(b"13800000000");
+            let cipher_text = XorCipher {}.encrypt(&plain, true).unwrap();
+            (
+                vec!["p".to_string(), "legacy".to_string()],
+                vec![vec![
+                    Value::String(hex::encode(cipher_text)),
+                    Value::String("13900000000".to_string()),
+                ]],
+            )
+        });
+        let rb = RBatis::new();
+        rb.init(driver.clone(), "test").unwrap();
+        rb.intercepts.push(Arc::new(
+            EncryptIntercept::new(XorCipher {})
+                .column("phone")
+                .column("legacy"),
+        ));
+        #[derive(Debug, serde::Deserialize)]
+        struct PhoneRow {
+            p: String,
+            legacy: String,
+        }
+        let f = async move {
+            let sql = "select u.phone as p,legacy from user u";
+            let rows = rb.query(sql, vec![]).await.unwrap();
+            assert_eq!(rows[0]["p"].as_str(), Some("13800000000"));
+            assert_eq!(rows[0]["legacy"].as_str(), Some("13900000000"));
+            let rows: Vec<PhoneRow> = rb.query_decode(sql, vec![]).await.unwrap();
+            assert_eq!(rows[0].p, "13800000000");
+            assert_eq!(rows[0].legacy, "13900000000");
+            //not an alias of an encrypted column
+            let rows = rb
+                .query("select name as p,legacy from user", vec![])
+                .await
+                .unwrap();
+            assert_ne!(rows[0]["p"].as_str(), Some("13800000000"));
+
+            rb.intercepts.clear();
+            rb.intercepts.push(Arc::new(
+                EncryptIntercept::new(XorCipher {})
+                    .column("legacy")
+                    .strict(true),
+            ));
+            assert!(rb.query(sql, vec![]).await.is_err());
+            assert!(rb.query_decode::<Vec<PhoneRow>>(sql, vec![]).await.is_err());
+        };
+        block_on(f);
+    }
+    #[test]
+    fn test_log_intercept_render_mask() {
+        let log = LogInterceptor::new(LevelFilter::Debug);
+        log.add_mask_rule(MaskRule::column("password"));
+        log.add_mask_rule(MaskRule::matcher(|v| v.as_str() == Some("token")));
+        log.set_max_value_len(3);
+        let args = vec![
+            Value::String("abcdef".to_string()),
+            Value::String("123456".to_string()),
+            Value::String("token".to_string()),
+            Value::Binary(vec![1, 2, 3, 4]),
+            Value::Null,
+        ];
+        let sql = "update user set name = ?, password = ?, token = ?, avatar = ? where remark = ?";
+        assert_eq!(
+            log.render("mysql", sql, &args),
+            "update user set name = 'abc...', password = '***', token = '***', avatar = X'010203' where remark = NULL"
+        );
+        assert_eq!(
+            log.render(
+                "pg",
+                "select * from t where id = $2 and name = $1",
+                &[Value::String("a'b".to_string()), Value::I32(1)]
+            ),
+            "select * from t where id = 1 and name = 'a''b'"
+        );
+        let log = log.clone();
+        assert_eq!(log.mask_rules.len(), 2);
+        assert_eq!(log.get_max_value_len(), 3);
+    }
+    #[test]
+    fn test_sql_guard_check() {
+        let guard = SqlGuardIntercept::new();
+        assert!(guard.check("delete from user").is_err());
+        assert!(guard.check("update user set name = ?").is_err());
+        assert!(guard.check("delete from user where 1 = 1").is_err());
+        assert!(guard
+            .check("delete from user where (1=1) or id = ?")
+            .is_err());
+        assert!(guard
+            .check("update user set a = ? where 'a' = 'a' and true")
+            .is_err());
+        assert!(guard
+            .check("delete from user where id = ?;drop table user")
+            .is_err());
+        assert!(guard.check("truncate table user").is_err());
+        assert!(guard.check("drop table user").is_err());
+        assert!(guard.check("delete from user where id = ?").is_ok());
+        assert!(guard
+            .check("delete from user where 1 = 1 and id = ?")
+            .is_ok());
+        assert!(guard
+            .check("update user set a = (select b from t where t.id = 1) where id = ?")
+            .is_ok());
+        assert!(guard
+            .check("update user set a = (select b from t where t.id = 1)")
+            .is_err());
+        assert!(guard.check("select * from user").is_ok());
+        let guard = SqlGuardIntercept {
+            deny_no_where: false,
+            deny_truncate: false,
+            deny_full_table_select: true,
+            ..SqlGuardIntercept::default()
+        };
+        assert!(guard.check("delete from user").is_ok());
+        assert!(guard.check("truncate table user").is_ok());
+        assert!(guard.check("select * from user").is_err());
+        assert!(guard.check("select * from user limit 1").is_ok());
+    }
+
+    #[test]
+    fn test_sql_guard_intercept() {
+        let rb = RBatis::new();
+        rb.init(MockDriver {}, "test").unwrap();
+        rb.intercepts.push(Arc::new(SqlGuardIntercept::new()));
+        let f = async move {
+            let r = rb.exec("delete from user", vec![]).await;
+            assert!(r.is_err());
+            let executor = rb.with_context(Context::new().with(SkipSqlGuard));
+            let r = executor.exec("delete from user", vec![]).await;
+            assert!(r.is_ok());
+            let tx = executor.acquire_begin().await.unwrap();
+            let r = tx.exec("delete from user", vec![]).await;
+            assert!(r.is_ok());
+            //other executors are still checked
+            let conn = rb.acquire().await.unwrap();
+            let r = conn.exec("delete from user", vec![]).await;
+            assert!(r.is_err());
+        };
+        block_on(f);
+    }
+
+    fn data_scope() -> DataScopeIntercept {
+        let scope = DataScopeIntercept::new();
+        scope.add_rule("biz_order", |_executor, alias| {
+            Some((
+                format!("({}.region_id = ? or {}.team_id = ?)", alias, alias),
+                vec![Value::I64(10), Value::I64(20)],
+            ))
+        });
+        scope.add_rule("biz_region", |_executor, alias| {
+            Some((format!("{}.enable = ?", alias), vec![Value::Bool(true)]))
+        });
+        scope
+    }
+
+    #[test]
+    fn test_data_scope_apply() {
+        let rb = RBatis::new();
+        let scope = data_scope();
+        let mut sql = "select * from biz_order".to_string();
+        let mut args = vec![];
+        scope.apply(&rb, &mut sql, &mut args).unwrap();
+        assert_eq!(
+            sql,
+            "select * from biz_order where (biz_order.region_id = ? or biz_order.team_id = ?)"
+        );
+        assert_eq!(args, vec![Value::I64(10), Value::I64(20)]);
+
+        let mut sql =
+            "select o.* from biz_order as o where o.status = ? or o.id = ? group by o.id order by o.id limit 10"
+                .to_string();
+        let mut args = vec![Value::I64(1), Value::I64(2)];
+        scope.apply(&rb, &mut sql, &mut args).unwrap();
+        assert_eq!(
+            sql,
+            "select o.* from biz_order as o where (o.status = ? or o.id = ?) and (o.region_id = ? or o.team_id = ?) group by o.id order by o.id limit 10"
+        );
+        assert_eq!(
+            args,
+            vec![Value::I64(1), Value::I64(2), Value::I64(10), Value::I64(20)]
+        );
+
+        let mut sql =
+            "select o.id from biz_order o left join biz_region r on r.id = o.region_id and r.code = ? where o.status = ?"
+                .to_string();
+        let mut args = vec![Value::I64(1), Value::I64(2)];
+        scope.apply(&rb, &mut sql, &mut args).unwrap();
+        assert_eq!(
+            sql,
+            "select o.id from biz_order o left join biz_region r on (r.id = o.region_id and r.code = ?) and r.enable = ? where (o.status = ?) and (o.region_id = ? or o.team_id = ?)"
+        );
+        assert_eq!(
+            args,
+            vec![
+                Value::I64(1),
+                Value::Bool(true),
+                Value::I64(2),
+                Value::I64(10),
+                Value::I64(20)
+            ]
+        );
+
+        //sub query and union
+        let mut sql =
+            "select * from (select id from biz_order where id > ?) t union all select id from biz_order"
+                .to_string();
+        let mut args = vec![Value::I64(1)];
+        scope.apply(&rb, &mut sql, &mut args).unwrap();
+        assert_eq!(
+            sql,
+            "select * from (select id from biz_order where (id > ?) and (biz_order.region_id = ? or biz_order.team_id = ?)) t union all select id from biz_order where (biz_order.region_id = ? or biz_order.team_id = ?)"
+        );
+        assert_eq!(
+            args,
+            vec![
+                Value::I64(1),
+                Value::I64(10),
+                Value::I64(20),
+                Value::I64(10),
+                Value::I64(20)
+            ]
+        );
+
+        //from sub query without where
+        let mut sql = "select * from (select * from biz_order) t where t.id = ?".to_string();
+        let mut args = vec![Value::I64(1)];
+        scope.apply(&rb, &mut sql, &mut args).unwrap();
+        assert_eq!(
+            sql,
+            "select * from (select * from biz_order where (biz_order.region_id = ? or biz_order.team_id = ?)) t where t.id = ?"
+        );
+        assert_eq!(args, vec![Value::I64(10), Value::I64(20), Value::I64(1)]);
+
+        //in sub query, nested sub query and parenthesized union
+        let mut sql =
+            "select * from biz_user u where u.id in (select o.user_id from biz_order o where o.status = ? and o.region_id in (select r.id from biz_region r)) and u.name = ?"
+                .to_string();
+        let mut args = vec![Value::I64(1), Value::String("a".to_string())];
+        scope.apply(&rb, &mut sql, &mut args).unwrap();
+        assert_eq!(
+            sql,
+            "select * from biz_user u where u.id in (select o.user_id from biz_order o where (o.status = ? and o.region_id in (select r.id from biz_region r where r.enable = ?)) and (o.region_id = ? or o.team_id = ?)) and u.name = ?"
+        );
+        assert_eq!(
+            args,
+            vec![
+                Value::I64(1),
+                Value::Bool(true),
+                Value::I64(10),
+                Value::I64(20),
+                Value::String("a".to_string())
+            ]
+        );
+        let mut sql = "(select id from biz_order) union (select id from biz_region)".to_string();
+        let mut args = vec![];
+        scope.apply(&rb, &mut sql, &mut args).unwrap();
+        assert_eq!(
+            sql,
+            "(select id from biz_order where (biz_order.region_id = ? or biz_order.team_id = ?)) union (select id from biz_region where biz_region.enable = ?)"
+        );
+        assert_eq!(args.len(), 3);
+
+        //exec sql and other tables are not changed
+        let mut sql = "select * from biz_user u where u.id = ?".to_string();
+        scope.apply(&rb, &mut sql, &mut vec![]).unwrap();
+        assert_eq!(sql, "select * from biz_user u where u.id = ?");
+
+        //the main query of `with` can not bypass the rule
+        let mut sql = "with t as (select 1) select * from biz_order".to_string();
+        let mut args = vec![];
+        scope.apply(&rb, &mut sql, &mut args).unwrap();
+        assert_eq!(
+            sql,
+            "with t as (select 1) select * from biz_order where (biz_order.region_id = ? or biz_order.team_id = ?)"
+        );
+        assert_eq!(args, vec![Value::I64(10), Value::I64(20)]);
+        let mut sql =
+            "with recursive t(id) as (select id from biz_region where id > ?), o as materialized (select * from biz_order) select * from t join o on o.region_id = t.id"
+                .to_string();
+        let mut args = vec![Value::I64(1)];
+        scope.apply(&rb, &mut sql, &mut args).unwrap();
+        assert_eq!(
+            sql,
+            "with recursive t(id) as (select id from biz_region where (id > ?) and biz_region.enable = ?), o as materialized (select * from biz_order where (biz_order.region_id = ? or biz_order.team_id = ?)) select * from t join o on o.region_id = t.id"
+        );
+        assert_eq!(
+            args,
+            vec![
+                Value::I64(1),
+                Value::Bool(true),
+                Value::I64(10),
+                Value::I64(20)
+            ]
+        );
+
+        //every statement is limited
+        let mut sql = "select * from biz_user; select * from biz_order".to_string();
+        let mut args = vec![];
+        scope.apply(&rb, &mut sql, &mut args).unwrap();
+        assert_eq!(
+            sql,
+            "select * from biz_user; select * from biz_order where (biz_order.region_id = ? or biz_order.team_id = ?)"
+        );
+
+        //a table with rule which can not be limited is rejected
+        for sql in [
+            "insert into biz_user select * from biz_order",
+            "with t as select * from biz_order",
+            "select * from biz_order where",
+        ] {
+            let mut sql = sql.to_string();
+            assert!(scope.apply(&rb, &mut sql, &mut vec![]).is_err(), "{}", sql);
+        }
+    }
+
+    #[test]
+    fn test_data_scope_page_count() {
+        let rb = RBatis::new();
+        rb.init(MockDriver {}, "test").unwrap();
+        rb.intercepts.push(Arc::new(data_scope()));
+        rb.intercepts.push(Arc::new(CaptureIntercept {
+            sqls: SyncVec::new(),
+            args: SyncVec::new(),
+        }));
+        let f = async move {
+            let conn = rb.acquire().await.unwrap();
+            let page = rb.get_intercept::<PageIntercept>().unwrap();
+            page.count_ids.insert(conn.id, PageRequest::new(1, 10));
+            conn.query(
+                "select * from biz_order o where o.status = ? order by o.id limit 0,10",
+                vec![Value::I64(1)],
+            )
+            .await
+            .unwrap();
+            let capture = rb.get_intercept::<CaptureIntercept>().unwrap();
+            assert_eq!(
+                capture.sqls.pop().unwrap(),
+                "select count(1) as count from biz_order o where (o.status = ?) and (o.region_id = ? or o.team_id = ?) order by o.id"
+            );
+            assert_eq!(
+                capture.args.pop().unwrap(),
+                vec![Value::I64(1), Value::I64(10), Value::I64(20)]
+            );
+        };
+        block_on(f);
+    }
+
+    pub struct CurrentUser(pub i64);
+
+    #[derive(Debug)]
+    pub struct ContextIntercept {
+        pub users: SyncVec<Option<i64>>,
+    }
+
+    #[async_trait]
+    impl Intercept for ContextIntercept {
+        async fn before(
+            &self,
+            _task_id: i64,
+            rb: &dyn Executor,
+            _sql: &mut String,
+            _args: &mut Vec<Value>,
+            _result: ResultType<&mut Result<ExecResult, Error>, &mut Result<Vec<Value>, Error>>,
+        ) -> Result<Option<bool>, Error> {
+            self.users
+                .push(rb.context_value::<CurrentUser>().map(|v| v.0));
+            Ok(Some(true))
+        }
+    }
+
+    #[test]
+    fn test_executor_context() {
+        let rb = RBatis::new();
+        rb.init(MockDriver {}, "test").unwrap();
+        rb.intercepts.push(Arc::new(ContextIntercept {
+            users: SyncVec::new(),
+        }));
+        let f = async move {
+            let intercept = rb.get_intercept::<ContextIntercept>().unwrap();
+            rb.exec("delete from user where id = 1", vec![])
+                .await
+                .unwrap();
+            assert_eq!(intercept.users.pop().unwrap(), None);
+
+            let executor = rb.with_context(Context::new().with(CurrentUser(1)));
+            assert_eq!(executor.id(), rb.id());
+            executor
+                .exec("delete from user where id = 1", vec![])
+                .await
+                .unwrap();
+            assert_eq!(intercept.users.pop().unwrap(), Some(1));
+
+            //conn -> tx
+            let conn = rb
+                .acquire()
+                .await
+                .unwrap()
+                .with_context(Context::new().with(CurrentUser(2)));
+            let tx = conn.begin().await.unwrap();
+            tx.query("select * from user", vec![]).await.unwrap();
+            assert_eq!(intercept.users.pop().unwrap(), Some(2));
+
+            let tx = executor.acquire_begin().await.unwrap();
+            tx.exec("delete from user where id = 1", vec![])
+                .await
+                .unwrap();
+            assert_eq!(intercept.users.pop().unwrap(), Some(1));
+
+            let mut ctx = Context::new();
+            assert!(ctx.is_empty());
+            ctx.insert(CurrentUser(3));
+            assert!(ctx.contains::<CurrentUser>());
+            assert!(ctx.remove::<CurrentUser>());
+            assert!(ctx.get::<CurrentUser>().is_none());
+        };
+        block_on(f);
+    }
+
+    #[derive(Debug)]
+    pub struct BatchIntercept {
+        pub batches: SyncVec<(String, usize)>,
+        pub results: SyncVec<u64>,
+    }
+
+    #[async_trait]
+    impl Intercept for BatchIntercept {
+        async fn before_batch(
+            &self,
+            _task_id: i64,
+            _rb: &dyn Executor,
+            sql: &mut String,
+            args: &mut Vec<Vec<Value>>,
+            _result: &mut Result<BatchExecResult, Error>,
+        ) -> Result<Option<bool>, Error> {
+            self.batches.push((sql.clone(), args.len()));
+            Ok(Some(true))
+        }
+
+        async fn after_batch(
+            &self,
+            _task_id: i64,
+            _rb: &dyn Executor,
+            _sql: &mut String,
+            _args: &mut Vec<Vec<Value>>,
+            result: &mut Result<BatchExecResult, Error>,
+        ) -> Result<Option<bool>, Error> {
+            if let Ok(v) = result {
+                self.results.push(v.rows_affected);
+            }
+            Ok(Some(true))
+        }
+    }
+
+    #[test]
+    fn test_exec_batch() {
+        let rb = RBatis::new();
+        rb.init(MockDriver {}, "test").unwrap();
+        rb.intercepts.clear();
+        rb.intercepts.push(Arc::new(SqlGuardIntercept::new()));
+        rb.intercepts.push(Arc::new(
+            EncryptIntercept::new(XorCipher {}).column("phone"),
+        ));
+        rb.intercepts.push(Arc::new(CaptureIntercept {
+            sqls: SyncVec::new(),
+            args: SyncVec::new(),
+        }));
+        rb.intercepts.push(Arc::new(BatchIntercept {
+            batches: SyncVec::new(),
+            results: SyncVec::new(),
+        }));
+        rb.intercepts
+            .push(Arc::new(LogInterceptor::new(LevelFilter::Debug)));
+        let f = async move {
+            let sql = "update user set phone = ? where id = ?";
+            let phone = |v: &str| rbs::to_value!(Encrypted(v.to_string()));
+            let r = rb
+                .exec_batch(
+                    sql,
+                    vec![
+                        vec![phone("1"), rbs::to_value!(1)],
+                        vec![phone("2"), rbs::to_value!(2)],
+                        vec![phone("3"), rbs::to_value!(3)],
+                    ],
+                )
+                .await
+                .unwrap();
+            assert_eq!(r.results.len(), 3);
+            assert_eq!(r.rows_affected, 6);
+            //one logical batch call
+            let batch = rb.get_intercept::<BatchIntercept>().unwrap();
+            assert_eq!(batch.batches.len(), 1);
+            assert_eq!(batch.batches.pop().unwrap(), (sql.to_string(), 3));
+            assert_eq!(batch.results.pop().unwrap(), 6);
+            //default before_batch run `before` for every args
+            let capture = rb.get_intercept::<CaptureIntercept>().unwrap();
+            assert_eq!(capture.args.len(), 3);
+            let args = capture.args.pop().unwrap();
+            assert!(args[0].is_str());
+            assert_ne!(args[0].as_str(), Some("3"));
+
+            //in a tx
+            let tx = rb.acquire_begin().await.unwrap();
+            let r = tx
+                .exec_batch(sql, vec![vec![phone("1"), rbs::to_value!(1)]])
+                .await
+                .unwrap();
+            assert_eq!(r.rows_affected, 2);
+            let r = tx.exec_batch(sql, vec![]).await.unwrap();
+            assert_eq!(r, BatchExecResult::default());
+
+            //the guard check once
+            let r = rb
+                .exec_batch("delete from user", vec![vec![], vec![]])
+                .await;
+            assert!(r.is_err());
+            assert_eq!(batch.batches.len(), 2);
+        };
+        block_on(f);
+    }
+
+    #[test]
+    fn test_tx_query_before_chain() {
+        let rb = RBatis::new();
+        rb.init(MockDriver {}, "test").unwrap();
+        rb.intercepts.clear();
+        rb.intercepts.push(Arc::new(ContextIntercept {
+            users: SyncVec::new(),
+        }));
+        rb.intercepts.push(Arc::new(CaptureIntercept {
+            sqls: SyncVec::new(),
+            args: SyncVec::new(),
+        }));
+        let f = async move {
+            let tx = rb.acquire_begin().await.unwrap();
+            tx.query("select * from user", vec![]).await.unwrap();
+            //the first intercept return `Some(true)`, the next one still run
+            let capture = rb.get_intercept::<CaptureIntercept>().unwrap();
+            assert_eq!(capture.sqls.pop().unwrap(), "select * from user");
+        };
+        block_on(f);
+    }
+
+    #[test]
+    fn test_exec_batch_commit_fail() {
+        let driver = crate::common::MockDriver::new().fail(|sql| sql == "commit");
+        let rb = RBatis::new();
+        rb.init(driver.clone(), "test").unwrap();
+        let f = async move {
+            let r = rb
+                .exec_batch(
+                    "update user set name = ? where id = ?",
+                    vec![vec![rbs::to_value!("a"), rbs::to_value!(1)]],
+                )
+                .await;
+            assert!(r.is_err());
+            let log = driver.take_log();
+            assert_eq!(log.last().unwrap().0, "rollback");
+        };
+        block_on(f);
+    }
+
+    #[derive(Debug)]
+    pub struct AfterCountIntercept {
+        count: AtomicI64,
+    }
+
+    #[async_trait]
+    impl Intercept for AfterCountIntercept {
+        async fn after(
+            &self,
+            _task_id: i64,
+            _rb: &dyn Executor,
+            _sql: &mut String,
+            _args: &mut Vec<Value>,
+            _result: ResultType<&mut Result<ExecResult, Error>, &mut Result<Vec<Value>, Error>>,
+        ) -> Result<Option<bool>, Error> {
+            self.count.fetch_add(1, Ordering::SeqCst);
+            Ok(Some(true))
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct DoubleCountIntercept {}
+
+    #[async_trait]
+    impl Intercept for DoubleCountIntercept {
+        fn wants_values(&self) -> bool {
+            true
+        }
+
+        async fn after(
+            &self,
+            _task_id: i64,
+            _rb: &dyn Executor,
+            _sql: &mut String,
+            _args: &mut Vec<Value>,
+            result: ResultType<&mut Result<ExecResult, Error>, &mut Result<Vec<Value>, Error>>,
+        ) -> Result<Option<bool>, Error> {
+            if let ResultType::Query(Ok(rows)) = result {
+                for row in rows.iter_mut() {
+                    let count = row["count"].as_u64().unwrap_or_default();
+                    row.insert(rbs::to_value!("count"), Value::U64(count * 2));
+                }
+            }
+            Ok(Some(true))
+        }
+    }
+
+    #[test]
+    fn test_query_rows() {
+        #[derive(serde::Deserialize, Debug, PartialEq)]
+        struct Count {
+            sql: String,
+            count: u64,
+        }
+        let rb = RBatis::new();
+        rb.init(MockDriver {}, "test").unwrap();
+        let f = async move {
+            let rows = rb.query_rows("select * from user", vec![]).await.unwrap();
+            assert_eq!(rows.columns, vec!["sql", "count"]);
+            assert_eq!(
+                rows.rows,
+                vec![vec![Value::from("select * from user"), Value::U64(1)]]
+            );
+            let v: Vec<Count> = rb.query_decode("select * from user", vec![]).await.unwrap();
+            let expect: Vec<Count> =
+                rbatis::decode(rb.query("select * from user", vec![]).await.unwrap()).unwrap();
+            assert_eq!(v, expect);
+            let count: u64 = rb
+                .query_decode("select count(1) from user", vec![])
+                .await
+                .unwrap();
+            assert_eq!(count, 1);
+
+            //the rows are not converted to `Value` maps for an intercept only override `after`
+            rb.intercepts.push(Arc::new(AfterCountIntercept {
+                count: AtomicI64::new(0),
+            }));
+            let v: Count = rb.query_decode("select * from user", vec![]).await.unwrap();
+            assert_eq!(v.count, 1);
+            let after = rb.get_intercept::<AfterCountIntercept>().unwrap();
+            assert_eq!(after.count.load(Ordering::SeqCst), 0);
+            rb.query("select * from user", vec![]).await.unwrap();
+            assert_eq!(after.count.load(Ordering::SeqCst), 1);
+
+            //an intercept with `wants_values` see the rows as `Value` maps
+            rb.intercepts.push(Arc::new(DoubleCountIntercept {}));
+            let v: Count = rb.query_decode("select * from user", vec![]).await.unwrap();
+            assert_eq!(v.count, 2);
+            let tx = rb.acquire_begin().await.unwrap();
+            let count: u64 = tx
+                .query_decode("select count(1) from user", vec![])
+                .await
+                .unwrap();
+            assert_eq!(count, 2);
+        };
+        block_on(f);
+    }
 }
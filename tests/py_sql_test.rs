@@ -235,6 +235,43 @@ mod test {
         block_on(f);
     }
 
+    #[test]
+    fn test_json_path() {
+        use rbatis::json::JsonPath;
+        let f = async move {
+            let mut rb = RBatis::new();
+            rb.init(MockDriver {}, "test").unwrap();
+            let queue = Arc::new(SyncVec::new());
+            rb.set_intercepts(vec![Arc::new(MockIntercept::new(queue.clone()))]);
+            #[py_sql("`select * from goods where ${driver_type.json_path('attrs','$.color')} = #{color}`")]
+            pub async fn select_by_color(rb: &RBatis, color: &str) -> Result<Value, Error> {
+                impled!()
+            }
+            select_by_color(&rb, "red").await.unwrap();
+            let (sql, args) = queue.pop().unwrap();
+            assert_eq!(
+                sql,
+                "select * from goods where json_extract(attrs,'$.color') = ?"
+            );
+            assert_eq!(args, vec![Value::String("red".to_string())]);
+        };
+        block_on(f);
+        assert_eq!(
+            "postgres".json_path("attrs", "$.a.b[1]"),
+            "attrs->'a'->'b'->>1"
+        );
+        assert_eq!(
+            "mysql".json_path("attrs", "a.b[1]"),
+            "JSON_UNQUOTE(JSON_EXTRACT(attrs,'$.a.b[1]'))"
+        );
+        assert_eq!(
+            "sqlite".json_path("attrs", "$.a"),
+            "json_extract(attrs,'$.a')"
+        );
+        assert_eq!("mssql".json_path("attrs", "$.a"), "JSON_VALUE(attrs,'$.a')");
+        assert_eq!("pg".json_path("attrs", "$.it's"), "attrs->>'it''s'");
+    }
+
     #[test]
     fn test_macro_bench() {
         pub trait QPS {
@@ -0,0 +1,190 @@
+mod common;
+
+#[cfg(test)]
+mod test {
+    use crate::common::MockDriver;
+    use rbatis::naming::{NameCase, NamingStrategy};
+    use rbatis::RBatis;
+    use rbdc::rt::block_on;
+    use rbs::Value;
+
+    /// the rows of a table
+    fn table_rows(table: &str) -> (Vec<&'static str>, Vec<Vec<i64>>) {
+        match table {
+            "user_role" => (
+                vec!["user_id", "role_id"],
+                vec![vec![1, 7], vec![2, 7], vec![2, 8]],
+            ),
+            "order" => (
+                vec!["id", "user_id"],
+                vec![vec![100, 1], vec![101, 1], vec![102, 2]],
+            ),
+            "dept" => (vec!["id"], vec![vec![10]]),
+            "role" => (vec!["id"], vec![vec![7], vec![8]]),
+            _ => (vec![], vec![]),
+        }
+    }
+
+    /// `select ... from {table} where {column} in (?,?)`
+    fn query(sql: &str, params: &[Value]) -> (Vec<String>, Vec<Vec<Value>>) {
+        let words: Vec<&str> = sql.split_whitespace().collect();
+        let find = |w: &str| words.iter().position(|v| *v == w).map(|i| words[i + 1]);
+        let table = find("from").unwrap_or_default();
+        let column = find("where").unwrap_or_default();
+        let (columns, rows) = table_rows(table);
+        let index = columns.iter().position(|v| *v == column);
+        let rows = rows
+            .into_iter()
+            .filter(|row| match index {
+                Some(index) => params.iter().any(|v| v.as_i64() == Some(row[index])),
+                None => true,
+            })
+            .map(|row| row.into_iter().map(Value::I64).collect())
+            .collect();
+        (columns.iter().map(|v| v.to_string()).collect(), rows)
+    }
+
+    #[derive(Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+    struct Dept {
+        id: Option<i64>,
+    }
+
+    #[derive(Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+    struct Order {
+        id: Option<i64>,
+        user_id: Option<i64>,
+    }
+
+    #[derive(Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+    struct Role {
+        id: Option<i64>,
+    }
+
+    #[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+    struct User {
+        id: Option<i64>,
+        dept_id: Option<i64>,
+        #[serde(skip)]
+        orders: Vec<Order>,
+        #[serde(skip)]
+        dept: Option<Dept>,
+        #[serde(skip)]
+        roles: Vec<Role>,
+    }
+
+    rbatis::crud!(Dept {});
+    rbatis::crud!(Order {});
+    rbatis::crud!(Role {});
+    rbatis::impl_relation!(User {
+        orders: has_many(Order, user_id => id) as orders_map,
+        dept: belongs_to(Dept, dept_id => id) as dept_map,
+        roles: many_to_many(Role, "user_role"(user_id => id, role_id => id)) as roles_map,
+    });
+
+    fn user(id: i64, dept_id: Option<i64>) -> User {
+        User {
+            id: Some(id),
+            dept_id,
+            ..User::default()
+        }
+    }
+
+    #[test]
+    fn test_load_relations() {
+        let f = async move {
+            let driver = MockDriver::new().query(query);
+            let mut rb = RBatis::new();
+            rb.batch_limit.max_params = Some(2);
+            rb.init(driver.clone(), "test").unwrap();
+            let mut users = vec![user(1, Some(10)), user(2, Some(10)), user(3, None)];
+            User::load_relations(&rb, &mut users).await.unwrap();
+
+            let order = |id, user_id| Order {
+                id: Some(id),
+                user_id: Some(user_id),
+            };
+            assert_eq!(users[0].orders, vec![order(100, 1), order(101, 1)]);
+            assert_eq!(users[1].orders, vec![order(102, 2)]);
+            assert!(users[2].orders.is_empty());
+            assert_eq!(users[0].dept, Some(Dept { id: Some(10) }));
+            assert_eq!(users[1].dept, Some(Dept { id: Some(10) }));
+            assert_eq!(users[2].dept, None);
+            assert_eq!(users[0].roles, vec![Role { id: Some(7) }]);
+            assert_eq!(
+                users[1].roles,
+                vec![Role { id: Some(7) }, Role { id: Some(8) }]
+            );
+            assert!(users[2].roles.is_empty());
+
+            //3 user ids are split into 2 queries by max_params, the dept ids are distinct
+            let queries = driver.take_log();
+            let params: Vec<usize> = queries.iter().map(|(_, v)| *v).collect();
+            assert_eq!(params, vec![2, 1, 1, 2, 1, 2]);
+            assert_eq!(queries[2].0, "select * from dept  where id in (?)");
+            assert_eq!(
+                queries[3].0,
+                "select user_id,role_id from user_role where user_id in (?,?)"
+            );
+        };
+        block_on(f);
+    }
+
+    #[test]
+    fn test_relation_map() {
+        let f = async move {
+            let driver = MockDriver::new().query(query);
+            let rb = RBatis::new();
+            rb.init(driver.clone(), "test").unwrap();
+            let users = vec![user(1, None), user(2, None)];
+            let map = User::orders_map(&rb, &users).await.unwrap();
+            assert_eq!(map.len(), 2);
+            assert_eq!(map["1"].len(), 2);
+            assert_eq!(map["2"].len(), 1);
+            //no dept ids, no query
+            let map = User::dept_map(&rb, &users).await.unwrap();
+            assert!(map.is_empty());
+            assert_eq!(driver.take_log().len(), 1);
+        };
+        block_on(f);
+    }
+
+    #[test]
+    fn test_load_duplicate_parents() {
+        let f = async move {
+            let driver = MockDriver::new().query(query);
+            let rb = RBatis::new();
+            rb.init(driver.clone(), "test").unwrap();
+            let mut users = vec![user(1, Some(10)), user(1, Some(10))];
+            User::load_relations(&rb, &mut users).await.unwrap();
+            for user in &users {
+                assert_eq!(user.orders.len(), 2);
+                assert_eq!(user.dept, Some(Dept { id: Some(10) }));
+                assert_eq!(user.roles, vec![Role { id: Some(7) }]);
+            }
+        };
+        block_on(f);
+    }
+
+    #[test]
+    fn test_relation_naming() {
+        let f = async move {
+            let driver = MockDriver::new().query(query);
+            let rb = RBatis::new();
+            rb.naming.set_default(NamingStrategy::new(NameCase::Pascal));
+            rb.init(driver.clone(), "test").unwrap();
+            let users = vec![user(1, None)];
+            User::orders_map(&rb, &users).await.unwrap();
+            User::roles_map(&rb, &users).await.unwrap();
+            let queries = driver.take_log();
+            assert_eq!(
+                queries[0].0,
+                "select Id as id,UserId as user_id from order  where UserId in (?)"
+            );
+            assert_eq!(
+                queries[1].0,
+                "select UserId,RoleId from user_role where UserId in (?)"
+            );
+        };
+        block_on(f);
+    }
+}
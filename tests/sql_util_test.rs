@@ -0,0 +1,62 @@
+#[cfg(test)]
+mod test {
+    use rbatis::utils::sql_util::{placeholder_columns, tokenize, TokenKind};
+
+    #[test]
+    fn test_tokenize() {
+        let tokens = tokenize("select * from `user` where name = 'a''b ?' and id = $2 -- ?");
+        let placeholders: Vec<&TokenKind> = tokens
+            .iter()
+            .map(|v| &v.kind)
+            .filter(|v| matches!(v, TokenKind::Placeholder(_)))
+            .collect();
+        assert_eq!(placeholders, vec![&TokenKind::Placeholder(Some(1))]);
+        assert_eq!(tokens[3].kind, TokenKind::QuotedIdent);
+        assert_eq!(tokens[3].text, "user");
+        assert_eq!(tokens[7].kind, TokenKind::Literal);
+        assert_eq!(tokens.last().unwrap().kind, TokenKind::Comment);
+    }
+
+    #[test]
+    fn test_placeholder_columns() {
+        let columns = placeholder_columns(
+            "select * from user u where u.name = ? and password<>? and id in (?,?) and age between ? and ? and remark not like ? and ? = 1",
+        );
+        assert_eq!(
+            columns,
+            vec![
+                Some("name".to_string()),
+                Some("password".to_string()),
+                Some("id".to_string()),
+                Some("id".to_string()),
+                Some("age".to_string()),
+                Some("age".to_string()),
+                Some("remark".to_string()),
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn test_placeholder_columns_insert() {
+        let columns = placeholder_columns("insert into user (id,password) VALUES (?,?),(?,?)");
+        assert_eq!(
+            columns,
+            vec![
+                Some("id".to_string()),
+                Some("password".to_string()),
+                Some("id".to_string()),
+                Some("password".to_string())
+            ]
+        );
+        let columns = placeholder_columns("update user set name=?,password=? where id = ?");
+        assert_eq!(
+            columns,
+            vec![
+                Some("name".to_string()),
+                Some("password".to_string()),
+                Some("id".to_string())
+            ]
+        );
+    }
+}
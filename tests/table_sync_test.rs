@@ -0,0 +1,115 @@
+mod common;
+
+#[cfg(test)]
+mod test {
+    use crate::common::MockDriver;
+    use rbatis::db_enum::{to_schema_value, DB_ENUM_EXT};
+    use rbatis::table_sync::{sync, sync_table, MysqlTableMapper, PGTableMapper};
+    use rbatis::RBatis;
+    use rbdc::rt::block_on;
+    use rbs::{to_value, Value};
+
+    #[derive(Clone, Copy, Debug, PartialEq, rbatis::DbEnum)]
+    enum Status {
+        #[db(int = 1)]
+        Enabled,
+        #[db(int = 0)]
+        Disabled,
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, rbatis::DbEnum)]
+    enum BigStatus {
+        #[db(int = 1)]
+        Small,
+        #[db(int = 4294967296)]
+        Big,
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, rbatis::DbEnum)]
+    enum Level {
+        #[db(str = "low")]
+        Low,
+        #[db(str = "medium")]
+        Medium,
+    }
+
+    #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+    struct User {
+        id: String,
+        status: Status,
+        big_status: BigStatus,
+        level: Option<Level>,
+    }
+
+    fn user() -> User {
+        User {
+            id: "".to_string(),
+            status: Status::Disabled,
+            big_status: BigStatus::Small,
+            level: Some(Level::Low),
+        }
+    }
+
+    #[test]
+    fn test_to_schema_value() {
+        let v = to_schema_value(&user()).unwrap();
+        assert_eq!(
+            v["level"],
+            Value::Ext(DB_ENUM_EXT, Box::new(to_value!(["low", "medium"])))
+        );
+        assert_eq!(
+            v["status"],
+            Value::Ext(
+                DB_ENUM_EXT,
+                Box::new(Value::Array(vec![Value::I32(1), Value::I32(0)]))
+            )
+        );
+        //the values are scalars out of the schema
+        let v = to_value!(user());
+        assert_eq!(v["level"], to_value!("low"));
+        assert_eq!(v["status"], Value::I32(0));
+    }
+
+    #[test]
+    fn test_sync_table_db_enum() {
+        let f = async move {
+            let driver = MockDriver::new().name("mysql");
+            let rb = RBatis::new();
+            rb.init(driver.clone(), "test").unwrap();
+            sync_table(&rb, &MysqlTableMapper {}, &user(), "user")
+                .await
+                .unwrap();
+            let execs = driver.take_log();
+            assert_eq!(
+                execs[0].0,
+                "CREATE TABLE user (id TEXT,status INT,big_status BIGINT,level VARCHAR(6));"
+            );
+
+            //the value of `to_value!` is a scalar, the string enum value is a custom column type
+            sync(&rb, &MysqlTableMapper {}, to_value!(user()), "user")
+                .await
+                .unwrap();
+            let execs = driver.take_log();
+            assert!(execs[0].0.ends_with("level low);"), "{}", execs[0].0);
+        };
+        block_on(f);
+    }
+
+    #[test]
+    fn test_sync_table_db_enum_pg() {
+        let f = async move {
+            let driver = MockDriver::new().name("postgres");
+            let rb = RBatis::new();
+            rb.init(driver.clone(), "test").unwrap();
+            sync_table(&rb, &PGTableMapper {}, &user(), "user")
+                .await
+                .unwrap();
+            let execs = driver.take_log();
+            assert_eq!(
+                execs[0].0,
+                "CREATE TABLE user (id TEXT,status INT4,big_status INT8,level VARCHAR(6));"
+            );
+        };
+        block_on(f);
+    }
+}